@@ -1,22 +1,86 @@
 use super::model::{Class, Gender, Student};
-use rand::{Rng, rng};
+use super::names::{NameMatch, describe_group, duplicate_name_groups, name_group_ids};
+use super::objective::Objective;
+use super::partition::{FeatureTable, Partition, Partitionable};
+use super::preferences::{PreferenceIndex, PreferenceReport, preference_report};
+use rand::{Rng, SeedableRng, rngs::StdRng};
 use rayon::prelude::*;
+use std::{
+    collections::BinaryHeap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use tokio_util::sync::CancellationToken;
 
+/// 并行实例之间同步（检查早停与取消）的迭代间隔
+const SYNC_INTERVAL: usize = 1000;
+
+/// 默认的最大迭代次数（每个并行实例）
+pub const DEFAULT_MAX_ITERATIONS: usize = 500_000;
+
+/// 指定了随机种子但未指定 `num_parallel_instances` 时使用的并行实例数，
+/// 使结果不随运行机器的 CPU 核心数变化
+pub const SEEDED_PARALLEL_INSTANCES: usize = 8;
+
 /// 分班配置
 #[derive(Debug, Clone)]
 pub struct DivideConfig {
     pub num_classes: usize,
+    /// 每个并行实例的最大迭代次数，默认 [`DEFAULT_MAX_ITERATIONS`]；按原值执行，不会被自动调高。
+    /// 设置了时间预算时忽略
     pub max_iterations: usize,
     pub optimization_params: OptimizationParams,
+    /// 随机种子，None 表示每次随机生成
+    ///
+    /// 相同的种子、输入数据、并行实例数和迭代次数会得到完全相同的分班结果。
+    /// 指定种子而未指定 `num_parallel_instances` 时实例数固定为 [`SEEDED_PARALLEL_INSTANCES`]，
+    /// 不随 CPU 核心数变化。
+    pub seed: Option<u64>,
+    /// 时间预算，None 表示只按迭代次数停止
+    ///
+    /// 设置后以时间为准，不再受 `max_iterations` 限制，停止时的迭代次数取决于机器速度；
+    /// 需要复现时用结果中记录的 `iterations` 与 `num_instances` 重新运行（不设时间预算）。
+    pub time_budget: Option<Duration>,
+    /// 热启动的初始分配，`initial_assignments[i]` 为第 i 个学生所在班级的索引
+    ///
+    /// 用于在已有结果（例如取消时得到的部分结果）的基础上继续优化
+    pub initial_assignments: Option<Vec<usize>>,
+    /// 收敛轨迹的记录间隔（迭代次数），None 表示不记录
+    pub trace_interval: Option<usize>,
+    /// 微调模式的原分班，`reference_assignments[i]` 为第 i 个学生的原班级索引，None 表示新生
+    ///
+    /// 设置后在原分班基础上插入新生并重新平衡，每调动一名学生增加 `move_penalty` 的代价；
+    /// 班级数可以与原分班不同
+    pub reference_assignments: Option<Vec<Option<usize>>>,
+    /// 部分重新优化：只在这些班级（索引）的学生之间调整，其余班级保持不变
+    ///
+    /// 需要同时提供 `initial_assignments`；代价仍按全部班级计算，
+    /// 使调整后的班级与未变动的班级保持均衡
+    pub active_classes: Option<Vec<usize>>,
+    /// 备选方案：除最优解外再保留若干个彼此差异足够大的较优解，None 表示只返回最优解
+    pub alternatives: Option<AlternativeConfig>,
+    /// 多目标模式：各实例使用不同的目标权重，并保留总分、性别比例、单科三个最大差值上
+    /// 互不支配的解（帕累托前沿）
+    pub pareto: bool,
+    /// 自定义目标项，与内置代价一起计入软约束代价（见 [`Objective`]）
+    pub objectives: Vec<Box<dyn Objective>>,
 }
 
 impl Default for DivideConfig {
     fn default() -> Self {
         Self {
             num_classes: 2,
-            max_iterations: 500000,
+            max_iterations: DEFAULT_MAX_ITERATIONS,
             optimization_params: OptimizationParams::default(),
+            seed: None,
+            time_budget: None,
+            initial_assignments: None,
+            trace_interval: None,
+            reference_assignments: None,
+            active_classes: None,
+            alternatives: None,
+            pareto: false,
+            objectives: Vec::new(),
         }
     }
 }
@@ -38,6 +102,513 @@ impl DivideConfig {
         self.optimization_params = params;
         self
     }
+
+    pub fn with_seed(mut self, seed: Option<u64>) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub fn with_time_budget(mut self, time_budget: Option<Duration>) -> Self {
+        self.time_budget = time_budget;
+        self
+    }
+
+    pub fn with_initial_assignments(mut self, assignments: Option<Vec<usize>>) -> Self {
+        self.initial_assignments = assignments;
+        self
+    }
+
+    pub fn with_trace_interval(mut self, trace_interval: Option<usize>) -> Self {
+        self.trace_interval = trace_interval;
+        self
+    }
+
+    pub fn with_reference_assignments(mut self, reference: Option<Vec<Option<usize>>>) -> Self {
+        self.reference_assignments = reference;
+        self
+    }
+
+    pub fn with_active_classes(mut self, active_classes: Option<Vec<usize>>) -> Self {
+        self.active_classes = active_classes;
+        self
+    }
+
+    pub fn with_alternatives(mut self, alternatives: Option<AlternativeConfig>) -> Self {
+        self.alternatives = alternatives;
+        self
+    }
+
+    pub fn with_pareto(mut self, pareto: bool) -> Self {
+        self.pareto = pareto;
+        self
+    }
+
+    pub fn with_objective(mut self, objective: impl Objective + 'static) -> Self {
+        self.objectives.push(Box::new(objective));
+        self
+    }
+}
+
+/// 备选方案配置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AlternativeConfig {
+    /// 方案总数（含最优解）
+    pub count: usize,
+    /// 任意两个方案之间至少相差的学生数（见 [`assignment_distance`]）
+    pub min_distance: usize,
+}
+
+impl Default for AlternativeConfig {
+    fn default() -> Self {
+        Self {
+            count: 3,
+            min_distance: 10,
+        }
+    }
+}
+
+/// 两个分班方案之间的差异人数
+///
+/// 班级编号本身没有意义，因此先按共同学生数贪心地把两边的班级一一对齐，
+/// 再统计对齐后所在班级不同的学生数（即对齐后的汉明距离）。
+/// 班级数取两个方案中出现的最大编号，两个方案人数不同时多出的学生都计为差异
+pub fn assignment_distance(a: &[usize], b: &[usize]) -> usize {
+    let num_classes = a.iter().chain(b).map(|&class| class + 1).max().unwrap_or(0);
+    let mut overlap = vec![vec![0usize; num_classes]; num_classes];
+    for (&x, &y) in a.iter().zip(b) {
+        overlap[x][y] += 1;
+    }
+
+    let mut pairs: Vec<(usize, usize, usize)> = overlap
+        .iter()
+        .enumerate()
+        .flat_map(|(x, row)| row.iter().enumerate().map(move |(y, &n)| (n, x, y)))
+        .filter(|&(n, _, _)| n > 0)
+        .collect();
+    pairs.sort_by(|p, q| q.cmp(p));
+
+    let mut used_a = vec![false; num_classes];
+    let mut used_b = vec![false; num_classes];
+    let mut matched = 0;
+    for (n, x, y) in pairs {
+        if !used_a[x] && !used_b[y] {
+            used_a[x] = true;
+            used_b[y] = true;
+            matched += n;
+        }
+    }
+    a.len().max(b.len()) - matched
+}
+
+/// 搜索结束的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum StopReason {
+    /// 达到最大迭代次数
+    IterationLimit,
+    /// 达到时间预算
+    TimeBudget,
+    /// 硬约束全部满足且代价长时间没有改进
+    Converged,
+    /// 代价低于良好解阈值
+    TargetReached,
+    /// 用户取消
+    Cancelled,
+}
+
+impl StopReason {
+    pub fn label(&self) -> &'static str {
+        match self {
+            StopReason::IterationLimit => "达到最大迭代次数",
+            StopReason::TimeBudget => "达到时间预算",
+            StopReason::Converged => "已收敛",
+            StopReason::TargetReached => "达到目标代价",
+            StopReason::Cancelled => "已取消",
+        }
+    }
+}
+
+/// 代价分项
+///
+/// 硬约束惩罚与软约束代价均为乘以权重之后的值，各项之和即为总代价
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CostBreakdown {
+    // ===== 硬约束惩罚 =====
+    /// 总分差值惩罚
+    pub score_penalty: f64,
+    /// 单科分差值惩罚（各科之和）
+    pub subject_penalty: f64,
+    /// 性别比例差值惩罚
+    pub gender_penalty: f64,
+    /// 班级人数差值惩罚
+    pub class_size_penalty: f64,
+    /// 重名学生同班惩罚
+    pub duplicate_name_penalty: f64,
+
+    // ===== 软约束代价 =====
+    /// 总分方差
+    pub score_variance: f64,
+    /// 科目方差（各科之和）
+    pub subject_variance: f64,
+    /// 性别比例方差
+    pub gender_variance: f64,
+    /// 班级人数方差
+    pub class_size_variance: f64,
+    /// 好友志愿代价（未满足的志愿权重与一条志愿都未满足的学生）
+    pub preference_cost: f64,
+    /// 自定义目标项的代价之和
+    pub custom_cost: f64,
+
+    // ===== 微调代价 =====
+    /// 调班代价（调班人数 × 每人代价）
+    pub move_cost: f64,
+}
+
+impl CostBreakdown {
+    /// 硬约束惩罚之和
+    pub fn hard_penalty(&self) -> f64 {
+        self.score_penalty
+            + self.gender_penalty
+            + self.subject_penalty
+            + self.class_size_penalty
+            + self.duplicate_name_penalty
+    }
+
+    /// 软约束代价之和
+    pub fn soft_cost(&self) -> f64 {
+        self.score_variance
+            + self.gender_variance
+            + self.class_size_variance
+            + self.subject_variance
+            + self.preference_cost
+            + self.custom_cost
+    }
+
+    /// 总代价
+    pub fn total(&self) -> f64 {
+        self.hard_penalty() + self.soft_cost() + self.move_cost
+    }
+
+    /// 各分项及其名称
+    pub fn terms(&self) -> Vec<(&'static str, f64)> {
+        vec![
+            ("总分差值惩罚", self.score_penalty),
+            ("单科分差值惩罚", self.subject_penalty),
+            ("性别比例惩罚", self.gender_penalty),
+            ("班级人数惩罚", self.class_size_penalty),
+            ("重名同班惩罚", self.duplicate_name_penalty),
+            ("总分方差", self.score_variance),
+            ("科目方差", self.subject_variance),
+            ("性别方差", self.gender_variance),
+            ("班级人数方差", self.class_size_variance),
+            ("好友志愿代价", self.preference_cost),
+            ("自定义目标代价", self.custom_cost),
+            ("调班代价", self.move_cost),
+        ]
+    }
+}
+
+/// 各项硬约束是否满足
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ConstraintFlags {
+    pub score: bool,
+    pub subject: bool,
+    pub gender: bool,
+    pub class_size: bool,
+    /// 没有重名学生同班（未开启重名检查时总为 true）
+    #[serde(default = "default_true")]
+    pub names: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl ConstraintFlags {
+    pub fn all(&self) -> bool {
+        self.score && self.subject && self.gender && self.class_size && self.names
+    }
+}
+
+/// 收敛轨迹中的一个采样点
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TracePoint {
+    /// 实例编号
+    pub instance: usize,
+    /// 该实例已执行的迭代次数
+    pub iteration: usize,
+    pub temperature: f64,
+    pub current_cost: f64,
+    pub best_cost: f64,
+    /// 最近一个记录间隔内的接受率
+    pub acceptance_rate: f64,
+    /// 累计重新加热次数
+    pub reheats: usize,
+    /// 当前解的硬约束满足情况
+    pub constraints: ConstraintFlags,
+}
+
+/// 分班结果
+#[derive(Debug, Clone)]
+pub struct DivideResult {
+    pub classes: Vec<Class>,
+    /// 每个学生所在班级的索引（与输入学生顺序一致，可用于热启动）
+    pub assignments: Vec<usize>,
+    /// 代价分项
+    pub cost: CostBreakdown,
+    pub validation: ConstraintValidation,
+    /// 每个实例执行的迭代次数
+    pub iterations: usize,
+    /// 并行实例数
+    pub num_instances: usize,
+    /// 运行用时
+    pub elapsed: Duration,
+    /// 给出最终解的实例编号
+    pub winning_instance: usize,
+    /// 实际使用的随机种子
+    pub seed: u64,
+    pub stop_reason: StopReason,
+    /// 收敛轨迹（按迭代次数、实例编号排序），未开启记录时为空
+    pub trace: Vec<TracePoint>,
+    /// 微调模式下相对原分班调动的学生数，未提供原分班时为 None
+    pub moved: Option<usize>,
+    /// 好友志愿的满足情况，没有学生填写志愿时为 None
+    pub preferences: Option<PreferenceReport>,
+    /// 各自定义目标项的名称与代价
+    pub objective_costs: Vec<(String, f64)>,
+    /// 按代价升序排列的备选方案（不含本方案），未开启备选方案时为空
+    pub alternatives: Vec<DivideResult>,
+    /// 多目标模式下的帕累托前沿（按总分最大差值升序），未开启时为空
+    pub pareto_front: Vec<DivideResult>,
+    /// 运行前发现并已忽略的无效设置（如与学生数不匹配的热启动分配）
+    pub warnings: Vec<String>,
+}
+
+impl DivideResult {
+    /// 按通用分组结果组建班级并核对约束
+    fn from_partition(
+        students: &[Student],
+        partition: Partition,
+        params: &OptimizationParams,
+    ) -> Self {
+        let mut classes: Vec<Class> = (0..partition.num_groups).map(Class::new).collect();
+        for (student, &class_id) in students.iter().zip(&partition.assignments) {
+            classes[class_id].add_student(student.clone());
+        }
+        let validation = validate_constraints_with_params(&classes, params);
+        let preferences = preference_report(&classes);
+        let convert = |partitions: Vec<Partition>| {
+            partitions
+                .into_iter()
+                .map(|partition| Self::from_partition(students, partition, params))
+                .collect()
+        };
+
+        Self {
+            classes,
+            assignments: partition.assignments,
+            cost: partition.cost,
+            validation,
+            iterations: partition.iterations,
+            num_instances: partition.num_instances,
+            elapsed: partition.elapsed,
+            winning_instance: partition.winning_instance,
+            seed: partition.seed,
+            stop_reason: partition.stop_reason,
+            trace: partition.trace,
+            moved: partition.moved,
+            preferences,
+            objective_costs: partition.objective_costs,
+            alternatives: convert(partition.alternatives),
+            pareto_front: convert(partition.pareto_front),
+            warnings: partition.warnings,
+        }
+    }
+
+    /// 是否为取消时返回的部分结果
+    pub fn is_partial(&self) -> bool {
+        self.stop_reason == StopReason::Cancelled
+    }
+
+    /// 用于界面摘要与导出的运行信息
+    pub fn summary_items(&self) -> Vec<(String, String)> {
+        let v = &self.validation;
+        let mut items = vec![
+            ("学生总数".to_string(), self.assignments.len().to_string()),
+            ("班级数量".to_string(), self.classes.len().to_string()),
+            (
+                "总分最大差值".to_string(),
+                format!("{:.2}分", v.max_score_diff),
+            ),
+            (
+                "性别比例最大差".to_string(),
+                format!("{:.1}%", v.max_gender_ratio_diff * 100.0),
+            ),
+            (
+                "班级人数最大差".to_string(),
+                format!("{}人", v.max_class_size_diff),
+            ),
+            ("总代价".to_string(), format!("{:.4}", self.cost.total())),
+        ];
+
+        if let Some(moved) = self.moved {
+            items.push(("调班人数".to_string(), format!("{}人", moved)));
+        }
+
+        if let Some(report) = &self.preferences {
+            items.push((
+                "好友志愿满足率".to_string(),
+                format!(
+                    "{:.1}%（{}/{} 条）",
+                    report.satisfaction_rate() * 100.0,
+                    report.satisfied,
+                    report.requests
+                ),
+            ));
+            if !report.unmet_students.is_empty() {
+                let names: Vec<String> = report
+                    .unmet_students
+                    .iter()
+                    .map(|(class_idx, name)| format!("{}（{}班）", name, class_idx + 1))
+                    .collect();
+                items.push((
+                    format!("志愿均未满足（{}人）", names.len()),
+                    names.join("、"),
+                ));
+            }
+        }
+
+        for (name, cost) in &self.objective_costs {
+            items.push((name.clone(), format!("{:.4}", cost)));
+        }
+
+        // 仍有重名学生同班的班级
+        for (class_idx, groups) in &v.duplicate_names {
+            items.push((format!("{}班重名", class_idx + 1), groups.join("、")));
+        }
+
+        // 只列出非零的代价分项
+        items.extend(
+            self.cost
+                .terms()
+                .into_iter()
+                .filter(|(_, value)| *value > 0.0)
+                .map(|(name, value)| (format!("  {}", name), format!("{:.4}", value))),
+        );
+
+        items.extend([
+            ("迭代次数".to_string(), self.iterations.to_string()),
+            ("并行实例数".to_string(), self.num_instances.to_string()),
+            (
+                "用时".to_string(),
+                format!("{:.1}秒", self.elapsed.as_secs_f64()),
+            ),
+            (
+                "最优实例".to_string(),
+                format!("#{}", self.winning_instance + 1),
+            ),
+            ("随机种子".to_string(), self.seed.to_string()),
+            ("结束原因".to_string(), self.stop_reason.label().to_string()),
+        ]);
+        items.extend(
+            self.warnings
+                .iter()
+                .map(|warning| ("提示".to_string(), warning.clone())),
+        );
+
+        items
+    }
+
+    /// 与之前结果的对比，用于继续优化后展示改进
+    pub fn improvement_items(&self, before: &DivideResult) -> Vec<(String, String)> {
+        let (old, new) = (&before.validation, &self.validation);
+        let (old_cost, new_cost) = (before.cost.total(), self.cost.total());
+        let constraints = |result: &DivideResult| {
+            if result.cost.hard_penalty() == 0.0 {
+                "全部满足"
+            } else {
+                "未全部满足"
+            }
+        };
+
+        let mut cost_change = format!("{:.4} → {:.4}", old_cost, new_cost);
+        if old_cost > 0.0 {
+            cost_change.push_str(&format!(
+                "（降低 {:.1}%）",
+                (old_cost - new_cost) / old_cost * 100.0
+            ));
+        }
+
+        vec![
+            ("总代价".to_string(), cost_change),
+            (
+                "总分最大差值".to_string(),
+                format!("{:.2}分 → {:.2}分", old.max_score_diff, new.max_score_diff),
+            ),
+            (
+                "性别比例最大差".to_string(),
+                format!(
+                    "{:.1}% → {:.1}%",
+                    old.max_gender_ratio_diff * 100.0,
+                    new.max_gender_ratio_diff * 100.0
+                ),
+            ),
+            (
+                "班级人数最大差".to_string(),
+                format!(
+                    "{}人 → {}人",
+                    old.max_class_size_diff, new.max_class_size_diff
+                ),
+            ),
+            (
+                "硬约束".to_string(),
+                format!("{} → {}", constraints(before), constraints(self)),
+            ),
+        ]
+    }
+}
+
+/// 初始解的构造策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum SeedingStrategy {
+    /// 贪心 LPT：按总分降序依次放入最合适的班级
+    #[default]
+    Lpt,
+    /// 多维最大差分法（Karmarkar–Karp），同时平衡总分、各科成绩和性别
+    KarmarkarKarp,
+    /// 按性别分层后蛇形排列
+    Snake,
+}
+
+impl SeedingStrategy {
+    pub const ALL: [SeedingStrategy; 3] = [
+        SeedingStrategy::Lpt,
+        SeedingStrategy::KarmarkarKarp,
+        SeedingStrategy::Snake,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SeedingStrategy::Lpt => "贪心 LPT",
+            SeedingStrategy::KarmarkarKarp => "最大差分法（Karmarkar–Karp）",
+            SeedingStrategy::Snake => "分性别蛇形排列",
+        }
+    }
+
+    /// 用于界面选择框的标识
+    pub fn key(&self) -> &'static str {
+        match self {
+            SeedingStrategy::Lpt => "lpt",
+            SeedingStrategy::KarmarkarKarp => "kk",
+            SeedingStrategy::Snake => "snake",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|strategy| strategy.key() == key)
+            .unwrap_or_default()
+    }
 }
 
 /// 优化参数配置
@@ -71,6 +642,10 @@ pub struct OptimizationParams {
     /// 性别比例差值超出阈值的惩罚权重（默认：1,000,000,000.0）
     pub gender_ratio_penalty_weight: f64,
 
+    /// 重名学生同班的惩罚权重（默认：1,000,000,000,000.0）
+    #[serde(default = "default_duplicate_name_penalty_weight")]
+    pub duplicate_name_penalty_weight: f64,
+
     /// 惩罚函数的幂次（默认：4，越高越严格）
     pub penalty_power: i32,
 
@@ -94,7 +669,7 @@ pub struct OptimizationParams {
     /// 冷却速率（默认：0.99990）
     pub cooling_rate: f64,
 
-    /// 并行实例数（默认：自动检测 CPU 核心数）
+    /// 并行实例数（默认：指定种子时为 [`SEEDED_PARALLEL_INSTANCES`]，否则按 CPU 核心数自动检测）
     pub num_parallel_instances: Option<usize>,
 
     /// 温度多样性增量（默认：1,000.0，为不同实例增加温度差异）
@@ -112,6 +687,54 @@ pub struct OptimizationParams {
 
     /// 重新加热的最小接受次数阈值（默认：100）
     pub reheat_min_accept_count: usize,
+
+    /// 收敛判定窗口：硬约束全部满足后，连续多少次迭代没有改进即停止（默认：50,000，0 表示不启用）
+    #[serde(default = "default_plateau_window")]
+    pub plateau_window: usize,
+
+    // ===== 微调参数 =====
+    /// 微调模式下每调动一名学生的代价（默认：10.0）
+    #[serde(default = "default_move_penalty")]
+    pub move_penalty: f64,
+
+    // ===== 初始解 =====
+    /// 初始解的构造策略（默认：贪心 LPT）
+    #[serde(default)]
+    pub seeding_strategy: SeedingStrategy,
+
+    // ===== 重名 =====
+    /// 避免重名学生同班的判定方式，None 表示不检查（默认）
+    #[serde(default)]
+    pub duplicate_names: Option<NameMatch>,
+
+    // ===== 好友志愿 =====
+    /// 每条未满足的好友志愿（按志愿权重）的代价（默认：2.0）
+    #[serde(default = "default_preference_weight")]
+    pub preference_weight: f64,
+
+    /// 每名一条志愿都未满足的学生的代价（默认：10.0）
+    #[serde(default = "default_unmet_student_weight")]
+    pub unmet_student_weight: f64,
+}
+
+fn default_plateau_window() -> usize {
+    50_000
+}
+
+fn default_duplicate_name_penalty_weight() -> f64 {
+    1_000_000_000_000.0
+}
+
+fn default_move_penalty() -> f64 {
+    10.0
+}
+
+fn default_preference_weight() -> f64 {
+    2.0
+}
+
+fn default_unmet_student_weight() -> f64 {
+    10.0
 }
 
 impl Default for OptimizationParams {
@@ -128,7 +751,8 @@ impl Default for OptimizationParams {
             class_size_penalty_weight: 1_000_000_000_000.0, // 最高优先级：班级人数（1万亿）
             subject_score_penalty_weight: 1_000_000_000.0,
             gender_ratio_penalty_weight: 100_000_000_000.0, // 次优先级：性别比例（100亿）
-            penalty_power: 6,                               // 提高幂次以加强惩罚
+            duplicate_name_penalty_weight: default_duplicate_name_penalty_weight(),
+            penalty_power: 6, // 提高幂次以加强惩罚
 
             // 软约束权重 - 在满足硬约束后进一步优化
             total_variance_weight: 10.0,
@@ -147,6 +771,12 @@ impl Default for OptimizationParams {
             reheat_after_iterations: 1_000,
             reheat_temperature_factor: 0.5,
             reheat_min_accept_count: 100,
+            plateau_window: default_plateau_window(),
+            move_penalty: default_move_penalty(),
+            seeding_strategy: SeedingStrategy::default(),
+            duplicate_names: None,
+            preference_weight: default_preference_weight(),
+            unmet_student_weight: default_unmet_student_weight(),
         }
     }
 }
@@ -185,6 +815,48 @@ impl OptimizationParams {
         }
     }
 
+    /// 班内分小组的参数配置
+    ///
+    /// 每组只有 6 人左右，平均分与性别比例的波动远大于分班，因此放宽阈值、降低惩罚幂次，
+    /// 并以较低的温度起步，使软约束（方差）在短时间内即可收敛
+    pub fn small_groups() -> Self {
+        Self {
+            max_score_diff: 10.0,
+            max_subject_score_diff: 10.0,
+            max_class_size_diff: 1,
+            max_gender_ratio_diff: 0.2,
+            penalty_power: 2,
+            initial_temperature: 100.0,
+            temperature_diversity_delta: 10.0,
+            cooling_rate: 0.9995,
+            num_parallel_instances: Some(2),
+            ..Default::default()
+        }
+    }
+
+    /// 用 `preset` 中由预设方案决定的字段（约束阈值、惩罚与方差权重、初始温度与冷却速率）
+    /// 覆盖本参数；重名、好友志愿、初始解策略、收敛窗口、调动代价等其余设置保持不变
+    pub fn with_preset(self, preset: &OptimizationParams) -> Self {
+        Self {
+            max_score_diff: preset.max_score_diff,
+            max_subject_score_diff: preset.max_subject_score_diff,
+            max_class_size_diff: preset.max_class_size_diff,
+            max_gender_ratio_diff: preset.max_gender_ratio_diff,
+            total_score_penalty_weight: preset.total_score_penalty_weight,
+            class_size_penalty_weight: preset.class_size_penalty_weight,
+            subject_score_penalty_weight: preset.subject_score_penalty_weight,
+            gender_ratio_penalty_weight: preset.gender_ratio_penalty_weight,
+            penalty_power: preset.penalty_power,
+            total_variance_weight: preset.total_variance_weight,
+            gender_variance_weight: preset.gender_variance_weight,
+            class_size_variance_weight: preset.class_size_variance_weight,
+            subject_variance_weight: preset.subject_variance_weight,
+            initial_temperature: preset.initial_temperature,
+            cooling_rate: preset.cooling_rate,
+            ..self
+        }
+    }
+
     /// 根据学生规模自适应调整参数
     pub fn adaptive(student_count: usize) -> Self {
         let mut params = Self::default();
@@ -211,53 +883,102 @@ pub struct ConstraintValidation {
     pub max_gender_ratio_diff: f64,
     pub max_class_size_diff: usize,
     pub subject_max_diffs: Vec<(String, f64)>,
+    /// 没有重名学生同班（未开启重名检查时总为 true）
+    pub name_constraints_met: bool,
+    /// 有重名学生的班级索引及重名组（如“张伟×2”）
+    pub duplicate_names: Vec<(usize, Vec<String>)>,
+}
+
+/// 最大值与最小值之差
+fn value_range(values: impl Iterator<Item = f64>) -> f64 {
+    let (min, max) = values.fold((f64::MAX, f64::MIN), |(min, max), v| {
+        (min.min(v), max.max(v))
+    });
+    max - min
 }
 
 /// 高性能缓存的班级统计数据
 #[derive(Debug, Clone)]
 struct CachedClassStats {
-    total_sum: f64,
     student_count: usize,
-    male_count: usize,
-    female_count: usize,
-    subject_sums: Vec<f64>, // 按科目顺序存储
+    /// 各数值特征之和：第 0 个为总分，其后按科目顺序
+    sums: Vec<f64>,
+    /// `category_counts[c][v]`：第 c 个类别特征（如性别）取值为 v 的人数
+    category_counts: Vec<Vec<usize>>,
+    /// `known_counts[c]`：第 c 个类别特征取值已知的人数
+    known_counts: Vec<usize>,
 }
 
 impl CachedClassStats {
-    fn new(subjects_count: usize) -> Self {
+    fn new(table: &FeatureTable) -> Self {
         Self {
-            total_sum: 0.0,
             student_count: 0,
-            male_count: 0,
-            female_count: 0,
-            subject_sums: vec![0.0; subjects_count],
+            sums: vec![0.0; table.numeric_count()],
+            category_counts: table
+                .category_sizes
+                .iter()
+                .map(|&size| vec![0; size])
+                .collect(),
+            known_counts: vec![0; table.category_sizes.len()],
         }
     }
 
     #[inline]
-    fn avg_total(&self) -> f64 {
+    fn avg(&self, feature: usize) -> f64 {
         if self.student_count == 0 {
             0.0
         } else {
-            self.total_sum / self.student_count as f64
+            self.sums[feature] / self.student_count as f64
         }
     }
 
     #[inline]
-    fn avg_subject(&self, subject_idx: usize) -> f64 {
-        if self.student_count == 0 {
+    fn avg_total(&self) -> f64 {
+        if self.sums.is_empty() {
             0.0
         } else {
-            self.subject_sums[subject_idx] / self.student_count as f64
+            self.avg(0)
         }
     }
 
     #[inline]
-    fn male_ratio(&self) -> f64 {
-        if self.student_count == 0 {
-            0.5
+    fn avg_subject(&self, subject_idx: usize) -> f64 {
+        self.avg(subject_idx + 1)
+    }
+
+    /// 第 c 个类别特征取值为 v 的人在取值已知者中的比例（如男生在已知性别学生中的比例），
+    /// 取值未知的人不计入；没有已知取值时视为各取值均分，与 [`Class::gender_ratio`] 一致
+    #[inline]
+    fn share(&self, category: usize, value: usize) -> f64 {
+        let known = self.known_counts[category];
+        if known == 0 {
+            1.0 / self.category_counts[category].len() as f64
+        } else {
+            self.category_counts[category][value] as f64 / known as f64
+        }
+    }
+
+    /// 加入或移出（`sign` 为 -1）一个项目
+    #[inline]
+    fn update(&mut self, table: &FeatureTable, idx: usize, sign: f64) {
+        if sign > 0.0 {
+            self.student_count += 1;
         } else {
-            self.male_count as f64 / self.student_count as f64
+            self.student_count -= 1;
+        }
+        for (sum, value) in self.sums.iter_mut().zip(&table.numeric[idx]) {
+            *sum += sign * value;
+        }
+        for (c, value) in table.categories[idx].iter().enumerate() {
+            if let Some(value) = *value {
+                if sign > 0.0 {
+                    self.category_counts[c][value] += 1;
+                    self.known_counts[c] += 1;
+                } else {
+                    self.category_counts[c][value] -= 1;
+                    self.known_counts[c] -= 1;
+                }
+            }
         }
     }
 }
@@ -268,120 +989,236 @@ struct Solution {
     assignments: Vec<usize>, // assignments[student_idx] = class_id
     class_stats: Vec<CachedClassStats>,
     subjects_count: usize,
+    /// 微调模式的原分班（各实例共享）
+    reference: Option<Arc<[Option<usize>]>>,
+    /// 相对原分班调动的学生数
+    moves: usize,
+    /// 每个学生所在的重名组（各实例共享），None 表示不检查重名
+    name_groups: Option<Arc<[Option<usize>]>>,
+    /// `name_counts[class][group]`：各班每个重名组的人数
+    name_counts: Vec<Vec<u32>>,
+    /// 同班的重名学生对数
+    name_conflicts: usize,
+    /// 好友志愿（各实例共享），None 表示没有志愿
+    preferences: Option<Arc<PreferenceIndex>>,
+    /// 未满足的志愿权重之和
+    unmet_weight: f64,
+    /// 每个学生已满足的志愿数
+    satisfied_counts: Vec<u32>,
+    /// 提出了志愿但一条也未满足的学生数
+    unmet_students: usize,
+    /// 自定义目标项（每个解各持有一份状态）
+    objectives: Vec<Box<dyn Objective>>,
 }
 
 impl Solution {
-    fn new(num_students: usize, num_classes: usize, subjects_count: usize) -> Self {
+    fn new(num_classes: usize, table: &FeatureTable) -> Self {
         Self {
-            assignments: vec![0; num_students],
-            class_stats: vec![CachedClassStats::new(subjects_count); num_classes],
-            subjects_count,
+            assignments: vec![0; table.len()],
+            class_stats: vec![CachedClassStats::new(table); num_classes],
+            subjects_count: table.numeric_count().saturating_sub(1),
+            reference: None,
+            moves: 0,
+            name_groups: None,
+            name_counts: Vec::new(),
+            name_conflicts: 0,
+            preferences: None,
+            unmet_weight: 0.0,
+            satisfied_counts: Vec::new(),
+            unmet_students: 0,
+            objectives: Vec::new(),
         }
     }
 
-    /// 添加学生到班级（初始化时使用）
-    #[inline]
-    fn assign_student(
-        &mut self,
-        student_idx: usize,
-        class_id: usize,
-        student: &Student,
-        subject_order: &[String],
-    ) {
-        self.assignments[student_idx] = class_id;
-        let stats = &mut self.class_stats[class_id];
+    /// 设置原分班并重新统计调动人数
+    fn with_reference(mut self, reference: Option<Arc<[Option<usize>]>>) -> Self {
+        self.reference = reference;
+        self.moves = (0..self.assignments.len())
+            .filter(|&idx| self.is_moved(idx, self.assignments[idx]))
+            .count();
+        self
+    }
 
-        stats.total_sum += student.total_score;
-        stats.student_count += 1;
-        if student.gender == Gender::Male {
-            stats.male_count += 1;
-        } else {
-            stats.female_count += 1;
+    /// 设置重名分组并重新统计同班的重名学生对数
+    fn with_name_groups(mut self, name_groups: Option<Arc<[Option<usize>]>>) -> Self {
+        let num_groups = name_groups
+            .as_ref()
+            .and_then(|groups| groups.iter().flatten().max())
+            .map_or(0, |&max| max + 1);
+        self.name_counts = vec![vec![0; num_groups]; self.class_stats.len()];
+        self.name_conflicts = 0;
+        if let Some(groups) = &name_groups {
+            for (idx, group) in groups.iter().enumerate() {
+                if let Some(group) = *group {
+                    let count = &mut self.name_counts[self.assignments[idx]][group];
+                    self.name_conflicts += *count as usize;
+                    *count += 1;
+                }
+            }
         }
+        self.name_groups = name_groups;
+        self
+    }
 
-        for (idx, subject) in subject_order.iter().enumerate() {
-            if let Some(&score) = student.scores.get(subject) {
-                stats.subject_sums[idx] += score;
+    /// 设置好友志愿并重新统计满足情况
+    fn with_preferences(mut self, preferences: Option<Arc<PreferenceIndex>>) -> Self {
+        self.unmet_weight = 0.0;
+        self.satisfied_counts = vec![0; self.assignments.len()];
+        self.unmet_students = 0;
+        if let Some(index) = &preferences {
+            for (idx, links) in index.links.iter().enumerate() {
+                for link in links.iter().filter(|link| link.outgoing) {
+                    if self.assignments[idx] == self.assignments[link.other] {
+                        self.satisfied_counts[idx] += 1;
+                    } else {
+                        self.unmet_weight += link.weight;
+                    }
+                }
             }
+            self.unmet_students = (0..self.assignments.len())
+                .filter(|&idx| index.request_counts[idx] > 0 && self.satisfied_counts[idx] == 0)
+                .count();
         }
+        self.preferences = preferences;
+        self
     }
 
-    /// 交换两个学生（增量更新统计）
-    #[inline]
-    fn swap_students(
-        &mut self,
-        idx1: usize,
-        idx2: usize,
-        students: &[Student],
-        subject_order: &[String],
-    ) {
-        let class1 = self.assignments[idx1];
-        let class2 = self.assignments[idx2];
+    /// 设置自定义目标项并按当前分配重新统计
+    fn with_objectives(mut self, objectives: &[Box<dyn Objective>]) -> Self {
+        self.objectives = objectives.to_vec();
+        for objective in &mut self.objectives {
+            objective.reset(self.class_stats.len());
+            for (idx, &class_id) in self.assignments.iter().enumerate() {
+                objective.on_add(idx, class_id);
+            }
+        }
+        self
+    }
 
-        if class1 == class2 {
+    /// 各自定义目标项的名称与代价
+    fn objective_costs(&self) -> Vec<(String, f64)> {
+        self.objectives
+            .iter()
+            .map(|objective| (objective.name().to_string(), objective.cost()))
+            .collect()
+    }
+
+    /// 学生 `idx` 从 `from` 班调到 `to` 班时更新志愿满足情况
+    ///
+    /// 与 `partner` 交换时两人之间的志愿前后都不满足，跳过
+    fn move_preferences(&mut self, idx: usize, partner: usize, from: usize, to: usize) {
+        let Some(index) = self.preferences.clone() else {
             return;
+        };
+        for link in &index.links[idx] {
+            if link.other == partner {
+                continue;
+            }
+            let other_class = self.assignments[link.other];
+            let (before, after) = (other_class == from, other_class == to);
+            if before == after {
+                continue;
+            }
+            let requester = if link.outgoing { idx } else { link.other };
+            let was_unmet = self.satisfied_counts[requester] == 0;
+            if after {
+                self.unmet_weight -= link.weight;
+                self.satisfied_counts[requester] += 1;
+            } else {
+                self.unmet_weight += link.weight;
+                self.satisfied_counts[requester] -= 1;
+            }
+            let is_unmet = self.satisfied_counts[requester] == 0;
+            match (was_unmet, is_unmet) {
+                (true, false) => self.unmet_students -= 1,
+                (false, true) => self.unmet_students += 1,
+                _ => {}
+            }
         }
+    }
 
-        // 从原班级移除
-        self.remove_student(class1, &students[idx1], subject_order);
-        self.remove_student(class2, &students[idx2], subject_order);
+    /// 重名组 `group` 的一名学生从 `from` 班调到 `to` 班
+    #[inline]
+    fn move_name(&mut self, group: usize, from: usize, to: usize) {
+        self.name_counts[from][group] -= 1;
+        self.name_conflicts -= self.name_counts[from][group] as usize;
+        self.name_conflicts += self.name_counts[to][group] as usize;
+        self.name_counts[to][group] += 1;
+    }
 
-        // 添加到新班级
-        self.add_student_to_class(idx1, class2, &students[idx1], subject_order);
-        self.add_student_to_class(idx2, class1, &students[idx2], subject_order);
+    /// 学生分到 `class_id` 时是否算作调班（新生不算）
+    #[inline]
+    fn is_moved(&self, student_idx: usize, class_id: usize) -> bool {
+        self.reference
+            .as_ref()
+            .is_some_and(|reference| reference[student_idx].is_some_and(|c| c != class_id))
+    }
+
+    /// 添加学生到班级（初始化时使用）
+    #[inline]
+    fn assign_student(&mut self, student_idx: usize, class_id: usize, table: &FeatureTable) {
+        self.assignments[student_idx] = class_id;
+        self.class_stats[class_id].update(table, student_idx, 1.0);
     }
 
+    /// 交换两个学生（增量更新统计）
     #[inline]
-    fn remove_student(&mut self, class_id: usize, student: &Student, subject_order: &[String]) {
-        let stats = &mut self.class_stats[class_id];
+    fn swap_students(&mut self, idx1: usize, idx2: usize, table: &FeatureTable) {
+        let class1 = self.assignments[idx1];
+        let class2 = self.assignments[idx2];
 
-        stats.total_sum -= student.total_score;
-        stats.student_count -= 1;
-        if student.gender == Gender::Male {
-            stats.male_count -= 1;
-        } else {
-            stats.female_count -= 1;
+        if class1 == class2 {
+            return;
         }
 
-        for (idx, subject) in subject_order.iter().enumerate() {
-            if let Some(&score) = student.scores.get(subject) {
-                stats.subject_sums[idx] -= score;
+        if let Some(groups) = &self.name_groups {
+            let (group1, group2) = (groups[idx1], groups[idx2]);
+            if let Some(group) = group1 {
+                self.move_name(group, class1, class2);
+            }
+            if let Some(group) = group2 {
+                self.move_name(group, class2, class1);
             }
         }
-    }
 
-    #[inline]
-    fn add_student_to_class(
-        &mut self,
-        student_idx: usize,
-        class_id: usize,
-        student: &Student,
-        subject_order: &[String],
-    ) {
-        self.assignments[student_idx] = class_id;
-        let stats = &mut self.class_stats[class_id];
+        self.move_preferences(idx1, idx2, class1, class2);
+        self.move_preferences(idx2, idx1, class2, class1);
 
-        stats.total_sum += student.total_score;
-        stats.student_count += 1;
-        if student.gender == Gender::Male {
-            stats.male_count += 1;
-        } else {
-            stats.female_count += 1;
+        for objective in &mut self.objectives {
+            objective.on_remove(idx1, class1);
+            objective.on_remove(idx2, class2);
+            objective.on_add(idx1, class2);
+            objective.on_add(idx2, class1);
         }
 
-        for (idx, subject) in subject_order.iter().enumerate() {
-            if let Some(&score) = student.scores.get(subject) {
-                stats.subject_sums[idx] += score;
-            }
+        if self.reference.is_some() {
+            let before =
+                self.is_moved(idx1, class1) as usize + self.is_moved(idx2, class2) as usize;
+            let after = self.is_moved(idx1, class2) as usize + self.is_moved(idx2, class1) as usize;
+            self.moves = self.moves + after - before;
         }
+
+        // 从原班级移除
+        self.class_stats[class1].update(table, idx1, -1.0);
+        self.class_stats[class2].update(table, idx2, -1.0);
+
+        // 添加到新班级
+        self.assign_student(idx1, class2, table);
+        self.assign_student(idx2, class1, table);
     }
 
     /// 计算代价（使用缓存数据和参数）
     #[inline]
     fn calculate_cost(&self, params: &OptimizationParams) -> f64 {
+        self.cost_breakdown(params).total()
+    }
+
+    /// 计算代价的各分项
+    #[inline]
+    fn cost_breakdown(&self, params: &OptimizationParams) -> CostBreakdown {
         let num_classes = self.class_stats.len();
         if num_classes == 0 {
-            return 0.0;
+            return CostBreakdown::default();
         }
 
         // 计算总分的最大差值和方差
@@ -399,20 +1236,33 @@ impl Solution {
             .sum::<f64>()
             / num_classes as f64;
 
-        // 计算性别比例的最大差值和方差
-        let male_ratios: Vec<f64> = self.class_stats.iter().map(|s| s.male_ratio()).collect();
-        let male_ratio_mean = male_ratios.iter().sum::<f64>() / num_classes as f64;
-
-        let max_gender_diff = male_ratios
-            .iter()
-            .map(|&r| (r - male_ratio_mean).abs())
-            .fold(0.0f64, f64::max);
-
-        let gender_variance: f64 = male_ratios
-            .iter()
-            .map(|&r| (r - male_ratio_mean).powi(2))
-            .sum::<f64>()
-            / num_classes as f64;
+        // 计算性别比例（各类别特征的构成比例）的最大差值和方差
+        let mut max_gender_diff = 0.0f64;
+        let mut gender_variance = 0.0;
+        for (category, counts) in self.class_stats[0].category_counts.iter().enumerate() {
+            let mut category_variance = 0.0;
+            for value in 0..counts.len() {
+                let shares: Vec<f64> = self
+                    .class_stats
+                    .iter()
+                    .map(|s| s.share(category, value))
+                    .collect();
+                let share_mean = shares.iter().sum::<f64>() / num_classes as f64;
+
+                max_gender_diff = shares
+                    .iter()
+                    .map(|&r| (r - share_mean).abs())
+                    .fold(max_gender_diff, f64::max);
+
+                category_variance += shares
+                    .iter()
+                    .map(|&r| (r - share_mean).powi(2))
+                    .sum::<f64>()
+                    / num_classes as f64;
+            }
+            // 按取值个数平均，两个取值时与男生比例的方差相同
+            gender_variance += category_variance / counts.len() as f64;
+        }
 
         // 计算各科目的最大差值和方差
         let mut subject_penalties = 0.0;
@@ -449,21 +1299,24 @@ impl Solution {
         }
 
         // 组合代价
-        let mut cost = 0.0;
+        let mut cost = CostBreakdown {
+            subject_penalty: subject_penalties,
+            ..Default::default()
+        };
 
         // 硬约束惩罚（权重极高，确保必须满足）
         if max_total_diff > params.max_score_diff {
-            cost += (max_total_diff - params.max_score_diff).powi(params.penalty_power)
+            cost.score_penalty = (max_total_diff - params.max_score_diff)
+                .powi(params.penalty_power)
                 * params.total_score_penalty_weight;
         }
 
         if max_gender_diff > params.max_gender_ratio_diff {
-            cost += (max_gender_diff - params.max_gender_ratio_diff).powi(params.penalty_power)
+            cost.gender_penalty = (max_gender_diff - params.max_gender_ratio_diff)
+                .powi(params.penalty_power)
                 * params.gender_ratio_penalty_weight;
         }
 
-        cost += subject_penalties;
-
         // 班级人数差值硬约束惩罚
         let class_sizes: Vec<usize> = self.class_stats.iter().map(|s| s.student_count).collect();
         let max_class_size = class_sizes.iter().max().copied().unwrap_or(0);
@@ -472,7 +1325,8 @@ impl Solution {
 
         if class_size_diff > params.max_class_size_diff {
             let excess = (class_size_diff - params.max_class_size_diff) as f64;
-            cost += excess.powi(params.penalty_power) * params.class_size_penalty_weight;
+            cost.class_size_penalty =
+                excess.powi(params.penalty_power) * params.class_size_penalty_weight;
         }
 
         // 计算班级人数方差（软约束优化）
@@ -487,47 +1341,184 @@ impl Solution {
             / num_classes as f64;
 
         // 软约束优化（只在接近满足硬约束时起作用）
-        cost += total_variance * params.total_variance_weight;
-        cost += gender_variance * params.gender_variance_weight;
-        cost += class_size_variance * params.class_size_variance_weight;
-        cost += subject_variance_sum * params.subject_variance_weight;
+        cost.score_variance = total_variance * params.total_variance_weight;
+        cost.gender_variance = gender_variance * params.gender_variance_weight;
+        cost.class_size_variance = class_size_variance * params.class_size_variance_weight;
+        cost.subject_variance = subject_variance_sum * params.subject_variance_weight;
+
+        // 重名学生同班硬约束惩罚
+        if self.name_conflicts > 0 {
+            cost.duplicate_name_penalty = (self.name_conflicts as f64).powi(params.penalty_power)
+                * params.duplicate_name_penalty_weight;
+        }
+
+        // 好友志愿（软约束）
+        cost.preference_cost = self.unmet_weight * params.preference_weight
+            + self.unmet_students as f64 * params.unmet_student_weight;
+
+        // 自定义目标项（软约束）
+        cost.custom_cost = self
+            .objectives
+            .iter()
+            .map(|objective| objective.cost())
+            .sum();
+
+        // 微调模式：调班代价
+        cost.move_cost = self.moves as f64 * params.move_penalty;
 
         cost
     }
 
-    /// 转换为 Class 列表
-    fn to_classes(&self, students: &[Student]) -> Vec<Class> {
-        let mut classes: Vec<Class> = (0..self.class_stats.len()).map(Class::new).collect();
+    /// 检查是否满足所有硬约束（与 `validate_constraints_with_params` 口径一致）
+    fn meets_hard_constraints(&self, params: &OptimizationParams) -> bool {
+        self.constraint_flags(params).all()
+    }
 
-        for (student_idx, &class_id) in self.assignments.iter().enumerate() {
-            classes[class_id].add_student(students[student_idx].clone());
+    /// 逐项检查硬约束
+    fn constraint_flags(&self, params: &OptimizationParams) -> ConstraintFlags {
+        const EPSILON: f64 = 1e-9;
+
+        let stats = &self.class_stats;
+        if stats.is_empty() {
+            return ConstraintFlags {
+                score: true,
+                subject: true,
+                gender: true,
+                class_size: true,
+                names: true,
+            };
         }
 
-        classes
+        let size_diff = stats.iter().map(|s| s.student_count).max().unwrap_or(0)
+            - stats.iter().map(|s| s.student_count).min().unwrap_or(0);
+
+        ConstraintFlags {
+            score: value_range(stats.iter().map(|s| s.avg_total()))
+                <= params.max_score_diff + EPSILON,
+            subject: (0..self.subjects_count).all(|idx| {
+                value_range(stats.iter().map(|s| s.avg_subject(idx)))
+                    <= params.max_subject_score_diff + EPSILON
+            }),
+            gender: self.max_share_range() <= params.max_gender_ratio_diff + EPSILON,
+            class_size: size_diff <= params.max_class_size_diff,
+            names: self.name_conflicts == 0,
+        }
     }
-}
 
-/// 创建初始解（使用改进的 LPT 算法）
-/// 生成初始解（改进的 LPT 算法，同时考虑总分和性别比例）
-fn create_initial_solution(
-    students: &[Student],
-    num_classes: usize,
-    subject_order: &[String],
-) -> Solution {
-    let mut solution = Solution::new(students.len(), num_classes, subject_order.len());
-
-    // 按总分降序排序的索引
-    let mut indices: Vec<usize> = (0..students.len()).collect();
-    indices.par_sort_unstable_by(|&a, &b| {
-        students[b]
-            .total_score
-            .partial_cmp(&students[a].total_score)
-            .unwrap()
-    });
+    /// 多目标模式的三个目标：总分、性别比例、单科平均分在班级间的最大差值
+    fn objectives(&self) -> [f64; 3] {
+        let stats = &self.class_stats;
+        if stats.is_empty() {
+            return [0.0; 3];
+        }
+        [
+            value_range(stats.iter().map(|s| s.avg_total())),
+            self.max_share_range(),
+            (0..self.subjects_count)
+                .map(|idx| value_range(stats.iter().map(|s| s.avg_subject(idx))))
+                .fold(0.0, f64::max),
+        ]
+    }
+
+    /// 各类别特征的构成比例在班级间的最大差值（如男生比例的最大差）
+    fn max_share_range(&self) -> f64 {
+        let stats = &self.class_stats;
+        stats.first().map_or(0.0, |first| {
+            first
+                .category_counts
+                .iter()
+                .enumerate()
+                .flat_map(|(category, counts)| {
+                    (0..counts.len()).map(move |value| {
+                        value_range(stats.iter().map(|s| s.share(category, value)))
+                    })
+                })
+                .fold(0.0, f64::max)
+        })
+    }
+
+    /// 从已有的分配构建解
+    fn from_assignments(assignments: &[usize], num_classes: usize, table: &FeatureTable) -> Self {
+        let mut solution = Self::new(num_classes, table);
+        for (student_idx, &class_id) in assignments.iter().enumerate() {
+            solution.assign_student(student_idx, class_id, table);
+        }
+        solution
+    }
+}
+
+/// 非 0 号实例构造初始解时排序键的随机扰动幅度（以标准差为单位）
+const INITIAL_JITTER: f64 = 0.05;
+
+/// 按 `keys` 降序排列的学生索引
+///
+/// `jitter > 0` 时给每个键加上 ±`jitter` 倍标准差的随机扰动，使不同实例得到不同的初始解
+fn jittered_order(keys: &[f64], jitter: f64, rng: &mut StdRng) -> Vec<usize> {
+    let mut keyed: Vec<(usize, f64)> = keys.iter().copied().enumerate().collect();
+    if jitter > 0.0 && !keys.is_empty() {
+        let mean = keys.iter().sum::<f64>() / keys.len() as f64;
+        let std = (keys.iter().map(|k| (k - mean).powi(2)).sum::<f64>() / keys.len() as f64).sqrt();
+        for (_, key) in keyed.iter_mut() {
+            *key += rng.random_range(-1.0..=1.0) * jitter * std;
+        }
+    }
+    keyed.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    keyed.into_iter().map(|(idx, _)| idx).collect()
+}
+
+/// 生成初始解
+///
+/// `jitter` 为排序键的随机扰动幅度，0 表示按策略确定性地构造
+fn create_initial_solution(
+    table: &FeatureTable,
+    num_classes: usize,
+    strategy: SeedingStrategy,
+    rng: &mut StdRng,
+    jitter: f64,
+) -> Solution {
+    let assignments = match strategy {
+        SeedingStrategy::Lpt => {
+            let totals: Vec<f64> = (0..table.len()).map(|idx| table.primary(idx)).collect();
+            let order = jittered_order(&totals, jitter, rng);
+            return create_lpt_solution(table, num_classes, &order);
+        }
+        SeedingStrategy::KarmarkarKarp => {
+            karmarkar_karp_assignments(table, num_classes, rng, jitter)
+        }
+        SeedingStrategy::Snake => snake_assignments(table, num_classes, rng, jitter),
+    };
+    Solution::from_assignments(&assignments, num_classes, table)
+}
+
+/// 改进的 LPT 算法，同时考虑总分和性别比例
+///
+/// 按 `order` 的顺序（通常为总分降序）依次放入最合适的班级
+fn create_lpt_solution(table: &FeatureTable, num_classes: usize, order: &[usize]) -> Solution {
+    let mut solution = Solution::new(num_classes, table);
+    // 目标性别比例为全体学生的实际比例
+    let target_shares = table.primary_category_shares();
+
+    // 加入该学生后班级性别比例与目标的偏差（两种性别时即男生比例之差）
+    let gender_deviation = |stats: &CachedClassStats, value: Option<usize>| {
+        if stats.student_count == 0 {
+            return target_shares.iter().sum::<f64>() / 2.0;
+        }
+        let counts = &stats.category_counts[0];
+        let known = stats.known_counts[0] + value.is_some() as usize;
+        target_shares
+            .iter()
+            .enumerate()
+            .map(|(v, target)| {
+                let count = counts[v] + (value == Some(v)) as usize;
+                (count as f64 / known.max(1) as f64 - target).abs()
+            })
+            .sum::<f64>()
+            / 2.0
+    };
 
     // 改进的 LPT：优先考虑人数均衡，其次考虑总分和性别比例
-    for &student_idx in &indices {
-        let student = &students[student_idx];
+    for &student_idx in order {
+        let value = table.primary_category(student_idx);
 
         // 找到最佳班级：优先人数最少的班级，然后综合考虑总分和性别比例
         let best_class = solution
@@ -540,311 +1531,1296 @@ fn create_initial_solution(
                 let size_penalty_b = b.student_count as f64 * 1_000_000.0;
 
                 // 优先级2：计算分配到该班级后的总分
-                let score_a = a.total_sum;
-                let score_b = b.total_sum;
+                let score_a = a.sums.first().copied().unwrap_or(0.0);
+                let score_b = b.sums.first().copied().unwrap_or(0.0);
 
                 // 优先级3：计算性别比例偏差
-                let male_ratio_a = if a.student_count == 0 {
+                let gender_penalty_a = if target_shares.is_empty() {
                     0.0
                 } else {
-                    let new_male = if student.gender == Gender::Male {
-                        a.male_count + 1
-                    } else {
-                        a.male_count
-                    };
-                    new_male as f64 / (a.student_count + 1) as f64
+                    gender_deviation(a, value)
                 };
-
-                let male_ratio_b = if b.student_count == 0 {
+                let gender_penalty_b = if target_shares.is_empty() {
                     0.0
                 } else {
-                    let new_male = if student.gender == Gender::Male {
-                        b.male_count + 1
-                    } else {
-                        b.male_count
-                    };
-                    new_male as f64 / (b.student_count + 1) as f64
+                    gender_deviation(b, value)
                 };
 
-                // 目标性别比例是 0.5（50%男生）
-                let gender_penalty_a = (male_ratio_a - 0.5).abs();
-                let gender_penalty_b = (male_ratio_b - 0.5).abs();
-
                 // 综合评分：人数（最高优先级） + 总分 + 性别比例惩罚
                 let cost_a = size_penalty_a + score_a + gender_penalty_a * 10000.0;
                 let cost_b = size_penalty_b + score_b + gender_penalty_b * 10000.0;
 
-                cost_a.partial_cmp(&cost_b).unwrap()
+                cost_a.total_cmp(&cost_b)
             })
             .map(|(idx, _)| idx)
             .unwrap_or(0);
 
-        solution.assign_student(
-            student_idx,
-            best_class,
-            &students[student_idx],
-            subject_order,
-        );
+        solution.assign_student(student_idx, best_class, table);
     }
 
     solution
 }
 
+/// 按性别分层后蛇形排列
+///
+/// 男生、女生（各类别取值）各自按总分降序，以 1→k、k→1 的往返顺序依次分班；
+/// 各分层连续排列，保证各班人数与各性别人数都最多相差 1
+fn snake_assignments(
+    table: &FeatureTable,
+    num_classes: usize,
+    rng: &mut StdRng,
+    jitter: f64,
+) -> Vec<usize> {
+    let totals: Vec<f64> = (0..table.len()).map(|idx| table.primary(idx)).collect();
+    let order = jittered_order(&totals, jitter, rng);
+
+    let mut assignments = vec![0; table.len()];
+    let mut position = 0;
+    let strata = (0..table.primary_category_size())
+        .map(Some)
+        .chain(std::iter::once(None));
+    for stratum in strata {
+        for &student_idx in order
+            .iter()
+            .filter(|&&idx| table.primary_category(idx) == stratum)
+        {
+            let round = position / num_classes;
+            let offset = position % num_classes;
+            assignments[student_idx] = if round.is_multiple_of(2) {
+                offset
+            } else {
+                num_classes - 1 - offset
+            };
+            position += 1;
+        }
+    }
+    assignments
+}
+
+/// 最大差分法中的部分划分：k 个子集及其各维度之和
+struct PartialPartition {
+    subsets: Vec<(Vec<f64>, Vec<usize>)>,
+    /// 各维度极差之和，越大越优先合并
+    spread: f64,
+}
+
+impl PartialPartition {
+    fn new(subsets: Vec<(Vec<f64>, Vec<usize>)>) -> Self {
+        let dims = subsets.first().map_or(0, |(sums, _)| sums.len());
+        let spread = (0..dims)
+            .map(|d| {
+                let (min, max) = subsets.iter().fold((f64::MAX, f64::MIN), |(min, max), s| {
+                    (min.min(s.0[d]), max.max(s.0[d]))
+                });
+                max - min
+            })
+            .sum();
+        Self { subsets, spread }
+    }
+
+    /// 子集的标量大小（各维度之和），用于配对
+    fn subset_size(sums: &[f64]) -> f64 {
+        sums.iter().sum()
+    }
+
+    /// 合并两个部分划分：一方最大的子集与另一方最小的子集配对
+    fn merge(mut self, mut other: Self) -> Self {
+        self.subsets
+            .sort_by(|a, b| Self::subset_size(&b.0).total_cmp(&Self::subset_size(&a.0)));
+        other
+            .subsets
+            .sort_by(|a, b| Self::subset_size(&a.0).total_cmp(&Self::subset_size(&b.0)));
+        let subsets = self
+            .subsets
+            .into_iter()
+            .zip(other.subsets)
+            .map(|((mut sums, mut members), (other_sums, other_members))| {
+                for (sum, other) in sums.iter_mut().zip(other_sums) {
+                    *sum += other;
+                }
+                members.extend(other_members);
+                (sums, members)
+            })
+            .collect();
+        Self::new(subsets)
+    }
+}
+
+impl PartialEq for PartialPartition {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == std::cmp::Ordering::Equal
+    }
+}
+
+impl Eq for PartialPartition {}
+
+impl PartialOrd for PartialPartition {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PartialPartition {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.spread.total_cmp(&other.spread)
+    }
+}
+
+/// 多维最大差分法（Karmarkar–Karp）
+///
+/// 每名学生是一个向量（总分、各科成绩、性别），各维度按标准差归一化。
+/// 按主维度排序后每 k 人组成一个部分划分（每个子集一人，保证人数均衡），
+/// 之后反复合并极差最大的两个部分划分，直到只剩一个
+fn karmarkar_karp_assignments(
+    table: &FeatureTable,
+    num_classes: usize,
+    rng: &mut StdRng,
+    jitter: f64,
+) -> Vec<usize> {
+    // 构造特征向量：总分、各科成绩、是否男生（每个类别特征除最后一个取值外各一维，
+    // 取值未知时取全体的比例）
+    let target_shares = table.primary_category_shares();
+    let category_dims = target_shares.len().saturating_sub(1);
+    let mut features: Vec<Vec<f64>> = (0..table.len())
+        .map(|idx| {
+            let mut feature = table.numeric[idx].clone();
+            let value = table.primary_category(idx);
+            feature.extend((0..category_dims).map(|v| match value {
+                Some(value) => (value == v) as u8 as f64,
+                None => target_shares[v],
+            }));
+            feature
+        })
+        .collect();
+
+    // 按标准差归一化，使各维度量纲一致
+    let dims = table.numeric_count() + category_dims;
+    for d in 0..dims {
+        let mean = features.iter().map(|f| f[d]).sum::<f64>() / table.len() as f64;
+        let std = (features.iter().map(|f| (f[d] - mean).powi(2)).sum::<f64>()
+            / table.len() as f64)
+            .sqrt();
+        for feature in features.iter_mut() {
+            feature[d] = if std > 0.0 {
+                (feature[d] - mean) / std
+            } else {
+                0.0
+            };
+        }
+    }
+
+    // 按总分分组，每 k 人组成一个部分划分（不足 k 人的用空子集补齐）
+    let keys: Vec<f64> = features
+        .iter()
+        .map(|f| f.first().copied().unwrap_or(0.0))
+        .collect();
+    let order = jittered_order(&keys, jitter, rng);
+    let mut heap: BinaryHeap<PartialPartition> = order
+        .chunks(num_classes)
+        .map(|chunk| {
+            let subsets = (0..num_classes)
+                .map(|slot| match chunk.get(slot) {
+                    Some(&idx) => (features[idx].clone(), vec![idx]),
+                    None => (vec![0.0; dims], Vec::new()),
+                })
+                .collect();
+            PartialPartition::new(subsets)
+        })
+        .collect();
+
+    // 反复合并极差最大的两个部分划分
+    while heap.len() > 1 {
+        let largest = heap.pop().unwrap();
+        let second = heap.pop().unwrap();
+        heap.push(largest.merge(second));
+    }
+
+    let mut assignments = vec![0; table.len()];
+    if let Some(partition) = heap.pop() {
+        for (class_id, (_, members)) in partition.subsets.into_iter().enumerate() {
+            for idx in members {
+                assignments[idx] = class_id;
+            }
+        }
+    }
+    assignments
+}
+
+/// 在原分班基础上构造初始解（微调模式）
+///
+/// 先确定各班目标人数（余数分给原人数多的班级），超员班级移出学生，
+/// 再把移出的学生、新生和原班级已不存在的学生按总分降序填入人数不足的班级，
+/// 尽量减少调动的学生数
+fn create_reference_solution(
+    table: &FeatureTable,
+    num_classes: usize,
+    reference: &Arc<[Option<usize>]>,
+) -> Solution {
+    let mut members: Vec<Vec<usize>> = vec![Vec::new(); num_classes];
+    let mut pending = Vec::new();
+    for (student_idx, class_id) in reference.iter().enumerate() {
+        match *class_id {
+            Some(class_id) if class_id < num_classes => members[class_id].push(student_idx),
+            _ => pending.push(student_idx),
+        }
+    }
+
+    // 各班目标人数
+    let mut targets = vec![table.len() / num_classes; num_classes];
+    let mut by_size: Vec<usize> = (0..num_classes).collect();
+    by_size.sort_by_key(|&class_id| std::cmp::Reverse(members[class_id].len()));
+    for &class_id in by_size.iter().take(table.len() % num_classes) {
+        targets[class_id] += 1;
+    }
+
+    let gender = |student_idx: usize| table.primary_category(student_idx);
+    let target_shares = table.primary_category_shares();
+
+    // 超员班级移出学生：优先移出比例偏高的性别中总分最接近班级平均分的学生
+    for class_id in 0..num_classes {
+        while members[class_id].len() > targets[class_id] {
+            let list = &members[class_id];
+            let known = list.iter().filter(|&&idx| gender(idx).is_some()).count();
+            let excess = |value: usize| {
+                let count = list
+                    .iter()
+                    .filter(|&&idx| gender(idx) == Some(value))
+                    .count();
+                count as f64 / known.max(1) as f64 - target_shares[value]
+            };
+            // 比例超出目标最多的性别，相同时取编号大的（两种性别时男生比例不高于目标即移出女生）
+            let evict = (0..target_shares.len()).max_by(|&a, &b| excess(a).total_cmp(&excess(b)));
+            let avg = list.iter().map(|&idx| table.primary(idx)).sum::<f64>() / list.len() as f64;
+            let distance = |idx: usize| (table.primary(idx) - avg).abs();
+
+            let pos = list
+                .iter()
+                .enumerate()
+                .filter(|&(_, &idx)| gender(idx) == evict)
+                .min_by(|&(_, &a), &(_, &b)| distance(a).total_cmp(&distance(b)))
+                .map(|(pos, _)| pos)
+                .unwrap_or(list.len() - 1);
+            pending.push(members[class_id].remove(pos));
+        }
+    }
+
+    // 按总分降序填入人数不足的班级，优先该性别比例最低的班级
+    pending.sort_by(|&a, &b| table.primary(b).total_cmp(&table.primary(a)));
+    for student_idx in pending {
+        let same_gender_share = |class_id: usize| {
+            let list = &members[class_id];
+            let same = list
+                .iter()
+                .filter(|&&idx| gender(idx) == gender(student_idx))
+                .count();
+            same as f64 / list.len().max(1) as f64
+        };
+        let class_id = (0..num_classes)
+            .filter(|&class_id| members[class_id].len() < targets[class_id])
+            .min_by(|&a, &b| {
+                same_gender_share(a)
+                    .total_cmp(&same_gender_share(b))
+                    .then(members[a].len().cmp(&members[b].len()))
+            })
+            .expect("目标人数之和等于学生数");
+        members[class_id].push(student_idx);
+    }
+
+    let mut assignments = vec![0; table.len()];
+    for (class_id, list) in members.iter().enumerate() {
+        for &student_idx in list {
+            assignments[student_idx] = class_id;
+        }
+    }
+    Solution::from_assignments(&assignments, num_classes, table)
+        .with_reference(Some(reference.clone()))
+}
+
+/// 与学生相关的附加约束（按学生索引），通用分组时为空
+#[derive(Default)]
+pub(crate) struct SearchExtras {
+    /// 每个学生所在的重名组，None 表示不检查重名
+    name_groups: Option<Arc<[Option<usize>]>>,
+    /// 好友志愿，None 表示没有志愿
+    preferences: Option<Arc<PreferenceIndex>>,
+}
+
+impl SearchExtras {
+    fn for_students(students: &[Student], params: &OptimizationParams) -> Self {
+        Self {
+            name_groups: params
+                .duplicate_names
+                .map(|mode| name_group_ids(students, mode))
+                .filter(|groups| groups.iter().any(Option::is_some))
+                .map(Arc::from),
+            preferences: PreferenceIndex::new(students).map(Arc::new),
+        }
+    }
+}
+
+/// 搜索过程中所有实例共享的只读数据
+struct SearchContext<'a> {
+    table: &'a FeatureTable,
+    /// 按性别（第一个类别特征的取值）分组的学生索引
+    category_indices: Vec<Vec<usize>>,
+    /// 性别未知的学生：按其人数占比被选中，与任意可交换的学生交换；不计入各班性别比例
+    unknown_indices: Vec<usize>,
+    /// 每个学生所在的重名组，None 表示不检查重名
+    name_groups: Option<Arc<[Option<usize>]>>,
+    /// 好友志愿，None 表示没有志愿
+    preferences: Option<Arc<PreferenceIndex>>,
+    /// 与好友志愿有关的学生，用于引导交换
+    friend_indices: Vec<usize>,
+    /// 自定义目标项的初始副本
+    objectives: &'a [Box<dyn Objective>],
+}
+
+impl<'a> SearchContext<'a> {
+    fn new(table: &'a FeatureTable, extras: SearchExtras) -> Self {
+        // 按性别分组索引
+        let mut category_indices = vec![Vec::new(); table.primary_category_size()];
+        let mut unknown_indices = Vec::new();
+        for idx in 0..table.len() {
+            match table.primary_category(idx) {
+                Some(value) => category_indices[value].push(idx),
+                None => unknown_indices.push(idx),
+            }
+        }
+
+        let friend_indices = extras
+            .preferences
+            .as_ref()
+            .map(|index| {
+                (0..table.len())
+                    .filter(|&idx| !index.links[idx].is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            table,
+            category_indices,
+            unknown_indices,
+            name_groups: extras.name_groups,
+            preferences: extras.preferences,
+            friend_indices,
+            objectives: &[],
+        }
+    }
+
+    fn with_objectives(mut self, objectives: &'a [Box<dyn Objective>]) -> Self {
+        self.objectives = objectives;
+        self
+    }
+
+    /// 只允许交换满足条件的学生，其余学生固定不动
+    fn restrict_to(mut self, movable: impl Fn(usize) -> bool) -> Self {
+        for indices in &mut self.category_indices {
+            indices.retain(|&idx| movable(idx));
+        }
+        self.unknown_indices.retain(|&idx| movable(idx));
+        self.friend_indices.retain(|&idx| movable(idx));
+        self
+    }
+}
+
+/// 单个模拟退火实例的运行状态
+///
+/// 实例可以分段推进，便于多个实例在固定间隔同步，保证结果可复现
+struct AnnealingState {
+    current: Solution,
+    current_cost: f64,
+    best: Solution,
+    best_cost: f64,
+    initial_temp: f64,
+    temperature: f64,
+    cooling_rate: f64,
+    rng: StdRng,
+    accept_count: usize,
+    iterations_since_improvement: usize,
+    instance_id: usize,
+    /// 已执行的迭代次数
+    iteration: usize,
+    reheats: usize,
+    /// 轨迹记录间隔，0 表示不记录
+    trace_interval: usize,
+    /// 当前记录间隔内被接受的交换次数
+    window_accepts: usize,
+    trace: Vec<TracePoint>,
+}
+
+impl AnnealingState {
+    fn new(
+        initial: Solution,
+        student_count: usize,
+        mut initial_temp: f64,
+        cooling_rate: f64,
+        rng: StdRng,
+        params: &OptimizationParams,
+    ) -> Self {
+        let current_cost = initial.calculate_cost(params);
+
+        // 根据问题规模调整初始温度
+        if student_count > 2000 {
+            initial_temp *= 3.0;
+        } else if student_count > 1000 {
+            initial_temp *= 2.0;
+        }
+
+        Self {
+            best: initial.clone(),
+            best_cost: current_cost,
+            current: initial,
+            current_cost,
+            initial_temp,
+            temperature: initial_temp,
+            cooling_rate,
+            rng,
+            accept_count: 0,
+            iterations_since_improvement: 0,
+            instance_id: 0,
+            iteration: 0,
+            reheats: 0,
+            trace_interval: 0,
+            window_accepts: 0,
+            trace: Vec::new(),
+        }
+    }
+
+    /// 开启轨迹记录
+    fn with_trace(mut self, instance_id: usize, trace_interval: usize) -> Self {
+        self.instance_id = instance_id;
+        self.trace_interval = trace_interval;
+        self
+    }
+
+    /// 记录当前状态的轨迹采样点
+    fn record_trace(&mut self, params: &OptimizationParams) {
+        self.trace.push(TracePoint {
+            instance: self.instance_id,
+            iteration: self.iteration,
+            temperature: self.temperature,
+            current_cost: self.current_cost,
+            best_cost: self.best_cost,
+            acceptance_rate: self.window_accepts as f64 / self.trace_interval as f64,
+            reheats: self.reheats,
+            constraints: self.current.constraint_flags(params),
+        });
+        self.window_accepts = 0;
+    }
+}
+
+/// 为每个并行实例派生独立的随机种子
+#[inline]
+fn instance_seed(seed: u64, instance_id: usize) -> u64 {
+    seed.wrapping_add((instance_id as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15))
+}
+
 /// 模拟退火算法
-#[allow(clippy::too_many_arguments)]
+///
+/// 将实例推进 `iterations` 次迭代，返回是否已找到良好解
 fn simulated_annealing(
-    initial: &Solution,
-    students: &[Student],
-    subject_order: &[String],
-    max_iterations: usize,
-    mut initial_temp: f64,
-    cooling_rate: f64,
-    found_solution: CancellationToken,
-    cancel_token: CancellationToken,
+    state: &mut AnnealingState,
+    ctx: &SearchContext,
+    iterations: usize,
     params: &OptimizationParams,
-) -> Solution {
-    let mut current = initial.clone();
-    let mut best = current.clone();
-    let mut current_cost = current.calculate_cost(params);
-    let mut best_cost = current_cost;
-
-    // 根据问题规模调整初始温度
-    if students.len() > 2000 {
-        initial_temp *= 3.0;
-    } else if students.len() > 1000 {
-        initial_temp *= 2.0;
-    }
-
-    let mut temperature = initial_temp;
-    let mut rng = rng();
-    let mut accept_count = 0;
-    let mut iterations_since_improvement = 0;
-
-    // 按性别分组索引
-    let mut male_indices = Vec::new();
-    let mut female_indices = Vec::new();
-    for (idx, student) in students.iter().enumerate() {
-        if student.gender == Gender::Male {
-            male_indices.push(idx);
-        } else {
-            female_indices.push(idx);
+) -> bool {
+    for _ in 0..iterations {
+        annealing_step(state, ctx, params);
+        state.iteration += 1;
+
+        if state.trace_interval > 0 && state.iteration.is_multiple_of(state.trace_interval) {
+            state.record_trace(params);
         }
     }
 
-    for iteration in 0..max_iterations {
-        // 每1000次检查是否其他线程已找到解或被取消
-        if iteration % 1000 == 0 && (found_solution.is_cancelled() || cancel_token.is_cancelled()) {
-            break;
+    // 如果找到非常好的解（可能满足所有约束），标记
+    state.best_cost < params.good_solution_threshold
+}
+
+/// 好友志愿引导交换的概率
+const FRIEND_SWAP_RATE: f64 = 0.2;
+/// 好友志愿引导交换时在目标班级中寻找交换对象的尝试次数
+const FRIEND_SWAP_TRIES: usize = 16;
+
+/// 好友志愿引导的交换：随机选一条志愿，把其中一方与另一方所在班级的同性别学生交换
+///
+/// 两人已同班或找不到可交换的学生时返回 None
+fn friend_swap(
+    ctx: &SearchContext,
+    solution: &Solution,
+    rng: &mut StdRng,
+) -> Option<(usize, usize)> {
+    let index = ctx.preferences.as_ref()?;
+    let idx1 = ctx.friend_indices[rng.random_range(0..ctx.friend_indices.len())];
+    let links = &index.links[idx1];
+    let target = solution.assignments[links[rng.random_range(0..links.len())].other];
+    if target == solution.assignments[idx1] {
+        return None;
+    }
+
+    let pool = match ctx.table.primary_category(idx1) {
+        Some(value) => &ctx.category_indices[value],
+        None => &ctx.unknown_indices,
+    };
+    (0..FRIEND_SWAP_TRIES)
+        .map(|_| pool[rng.random_range(0..pool.len())])
+        .find(|&idx2| solution.assignments[idx2] == target)
+        .map(|idx2| (idx1, idx2))
+}
+
+/// 模拟退火的单次迭代：随机交换两名学生并按 Metropolis 准则决定是否接受
+fn annealing_step(state: &mut AnnealingState, ctx: &SearchContext, params: &OptimizationParams) {
+    let table = ctx.table;
+    let category_indices = &ctx.category_indices;
+    let unknown_indices = &ctx.unknown_indices;
+    let rng = &mut state.rng;
+
+    // 有好友志愿时按一定概率尝试把学生换到好友所在的班级
+    let friend_pair = if !ctx.friend_indices.is_empty() && rng.random_bool(FRIEND_SWAP_RATE) {
+        friend_swap(ctx, &state.current, rng)
+    } else {
+        None
+    };
+
+    // 40% 概率同性别交换（优化分数），60% 概率跨性别交换（优化性别比例）
+    let same_gender_swap = rng.random::<f64>() < 0.4;
+
+    let (idx1, idx2) = if let Some(pair) = friend_pair {
+        pair
+    } else if !unknown_indices.is_empty()
+        && rng.random_bool(unknown_indices.len() as f64 / table.len() as f64)
+    {
+        // 性别未知的学生按其人数占比与任意可交换的学生交换
+        let i1 = unknown_indices[rng.random_range(0..unknown_indices.len())];
+        let movable = category_indices.iter().map(Vec::len).sum::<usize>() + unknown_indices.len();
+        let mut pick = rng.random_range(0..movable);
+        let i2 = category_indices
+            .iter()
+            .chain(std::iter::once(unknown_indices))
+            .find_map(|list| {
+                let found = list.get(pick).copied();
+                pick = pick.saturating_sub(list.len());
+                found
+            })
+            .expect("pick 小于可交换学生总数");
+        (i1, i2)
+    } else if same_gender_swap {
+        // 同性别交换：随机选择同性别的两个学生，所选性别不足两人时取第一个足够的性别
+        if category_indices.is_empty() {
+            return;
         }
+        let preferred = rng.random_range(0..category_indices.len());
+        let Some(indices) = std::iter::once(&category_indices[preferred])
+            .chain(category_indices)
+            .find(|indices| indices.len() >= 2)
+        else {
+            return;
+        };
 
-        // 40% 概率同性别交换（优化分数），60% 概率跨性别交换（优化性别比例）
-        let same_gender_swap = rng.random::<f64>() < 0.4;
+        let i1 = indices[rng.random_range(0..indices.len())];
+        let i2 = indices[rng.random_range(0..indices.len())];
+        (i1, i2)
+    } else {
+        // 跨性别交换：随机选择两个不同性别的学生
+        let present = || {
+            category_indices
+                .iter()
+                .filter(|indices| !indices.is_empty())
+        };
+        let count = present().count();
+        if count < 2 {
+            return;
+        }
 
-        let (idx1, idx2) = if same_gender_swap {
-            // 同性别交换：随机选择同性别的两个学生
-            let use_male = rng.random_bool(0.5);
-            let indices = if use_male && male_indices.len() >= 2 {
-                &male_indices
-            } else if !use_male && female_indices.len() >= 2 {
-                &female_indices
-            } else if male_indices.len() >= 2 {
-                &male_indices
-            } else if female_indices.len() >= 2 {
-                &female_indices
-            } else {
-                continue;
-            };
+        let first = rng.random_range(0..count);
+        let second = (first + rng.random_range(1..count)) % count;
+        let (list1, list2) = (
+            present().nth(first).expect("first 小于非空性别数"),
+            present().nth(second).expect("second 小于非空性别数"),
+        );
+        (
+            list1[rng.random_range(0..list1.len())],
+            list2[rng.random_range(0..list2.len())],
+        )
+    };
 
-            if indices.len() < 2 {
-                continue;
-            }
+    let current = &mut state.current;
+    if idx1 == idx2 || current.assignments[idx1] == current.assignments[idx2] {
+        return;
+    }
 
-            let i1 = indices[rng.random_range(0..indices.len())];
-            let i2 = indices[rng.random_range(0..indices.len())];
-            (i1, i2)
+    // 交换并计算新代价
+    current.swap_students(idx1, idx2, table);
+    let new_cost = current.calculate_cost(params);
+    let delta = new_cost - state.current_cost;
+
+    // Metropolis 准则
+    if delta < 0.0 || rng.random::<f64>() < (-delta / state.temperature).exp() {
+        state.current_cost = new_cost;
+        state.accept_count += 1;
+        state.window_accepts += 1;
+
+        if new_cost < state.best_cost {
+            state.best = current.clone();
+            state.best_cost = new_cost;
+            state.iterations_since_improvement = 0;
         } else {
-            // 跨性别交换：随机选择一男一女
-            if male_indices.is_empty() || female_indices.is_empty() {
-                continue;
-            }
+            state.iterations_since_improvement += 1;
+        }
+    } else {
+        // 拒绝交换，恢复
+        current.swap_students(idx1, idx2, table);
+        state.iterations_since_improvement += 1;
+    }
+
+    // 自适应冷却：如果长时间没有改进，重新加热
+    if state.iterations_since_improvement > params.reheat_after_iterations
+        && state.accept_count < params.reheat_min_accept_count
+    {
+        state.temperature = state.initial_temp * params.reheat_temperature_factor;
+        state.iterations_since_improvement = 0;
+        state.accept_count = 0;
+        state.reheats += 1;
+    } else {
+        state.temperature *= state.cooling_rate;
+    }
+}
 
-            let male_idx = male_indices[rng.random_range(0..male_indices.len())];
-            let female_idx = female_indices[rng.random_range(0..female_indices.len())];
-            (male_idx, female_idx)
+/// 并行搜索的运行设置
+struct SearchPlan<'a> {
+    num_classes: usize,
+    total_iterations: usize,
+    num_instances: usize,
+    seed: u64,
+    time_budget: Option<Duration>,
+    /// 热启动的初始解，None 时使用 LPT 构造初始解
+    initial: Option<&'a Solution>,
+    /// 轨迹记录间隔，0 表示不记录
+    trace_interval: usize,
+    alternatives: Option<AlternativeConfig>,
+    pareto: bool,
+}
+
+/// 并行搜索的结果
+struct SearchOutcome {
+    solution: Solution,
+    stop_reason: StopReason,
+    /// 每个实例执行的迭代次数
+    iterations: usize,
+    winning_instance: usize,
+    trace: Vec<TracePoint>,
+    /// 备选方案（不含 `solution`），按代价升序
+    alternatives: Vec<Solution>,
+    /// 帕累托前沿，按总分最大差值升序
+    pareto_front: Vec<Solution>,
+}
+
+impl SearchOutcome {
+    /// 无需搜索时直接采用给定的解
+    fn trivial(solution: Solution) -> Self {
+        Self {
+            solution,
+            stop_reason: StopReason::Converged,
+            iterations: 0,
+            winning_instance: 0,
+            trace: Vec::new(),
+            alternatives: Vec::new(),
+            pareto_front: Vec::new(),
+        }
+    }
+}
+
+/// 帕累托前沿最多保留的解数
+const PARETO_CAPACITY: usize = 50;
+
+/// 多目标模式下各实例的软约束权重倍数（总分、性别、单科），使实例分散到前沿的不同位置
+const PARETO_WEIGHTS: [[f64; 3]; 7] = [
+    [1.0, 1.0, 1.0],
+    [10.0, 1.0, 1.0],
+    [1.0, 10.0, 1.0],
+    [1.0, 1.0, 10.0],
+    [10.0, 10.0, 1.0],
+    [10.0, 1.0, 10.0],
+    [1.0, 10.0, 10.0],
+];
+
+/// 某个实例使用的参数：多目标模式下按实例编号调整软约束权重
+fn instance_params(
+    params: &OptimizationParams,
+    instance_id: usize,
+    pareto: bool,
+) -> OptimizationParams {
+    let mut params = params.clone();
+    if pareto {
+        let [score, gender, subject] = PARETO_WEIGHTS[instance_id % PARETO_WEIGHTS.len()];
+        params.total_variance_weight *= score;
+        params.gender_variance_weight *= gender;
+        params.subject_variance_weight *= subject;
+    }
+    params
+}
+
+/// 帕累托前沿存档：保留在三个目标上互不支配的解
+///
+/// 超出容量时移除与其他解最接近（按各目标当前范围归一化）的解，使前沿分布尽量均匀
+#[derive(Default)]
+struct ParetoArchive {
+    entries: Vec<([f64; 3], Solution)>,
+}
+
+impl ParetoArchive {
+    /// `a` 是否支配 `b`：各目标都不差且至少一项更好
+    fn dominates(a: &[f64; 3], b: &[f64; 3]) -> bool {
+        a.iter().zip(b).all(|(x, y)| x <= y) && a.iter().zip(b).any(|(x, y)| x < y)
+    }
+
+    fn offer(&mut self, solution: &Solution, params: &OptimizationParams) {
+        // 人数不达标的解不参与比较
+        if !solution.constraint_flags(params).class_size {
+            return;
+        }
+
+        let objectives = solution.objectives();
+        if self
+            .entries
+            .iter()
+            .any(|(other, _)| *other == objectives || Self::dominates(other, &objectives))
+        {
+            return;
+        }
+
+        self.entries
+            .retain(|(other, _)| !Self::dominates(&objectives, other));
+        self.entries.push((objectives, solution.clone()));
+        if self.entries.len() > PARETO_CAPACITY {
+            self.remove_most_crowded();
+        }
+    }
+
+    fn remove_most_crowded(&mut self) {
+        let scales: Vec<f64> = (0..3)
+            .map(|k| value_range(self.entries.iter().map(|(o, _)| o[k])).max(f64::EPSILON))
+            .collect();
+        let distance = |a: &[f64; 3], b: &[f64; 3]| {
+            (0..3)
+                .map(|k| ((a[k] - b[k]) / scales[k]).powi(2))
+                .sum::<f64>()
         };
 
-        if idx1 == idx2 || current.assignments[idx1] == current.assignments[idx2] {
-            continue;
+        let crowded = (0..self.entries.len())
+            .map(|i| {
+                let nearest = (0..self.entries.len())
+                    .filter(|&j| j != i)
+                    .map(|j| distance(&self.entries[i].0, &self.entries[j].0))
+                    .fold(f64::INFINITY, f64::min);
+                (i, nearest)
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(i, _)| i);
+        if let Some(i) = crowded {
+            self.entries.remove(i);
+        }
+    }
+
+    fn into_front(mut self) -> Vec<Solution> {
+        self.entries.sort_by(|(a, _), (b, _)| a[0].total_cmp(&b[0]));
+        self.entries
+            .into_iter()
+            .map(|(_, solution)| solution)
+            .collect()
+    }
+}
+
+/// 多样化候选解存档
+///
+/// 保留代价最低、两两差异人数不小于 `min_distance` 的至多 `count` 个解。
+/// 新解与已有解过于相似时，只有比所有相似解都更优才会替换它们。
+struct DiverseArchive {
+    config: AlternativeConfig,
+    /// 按代价升序
+    entries: Vec<(f64, Solution)>,
+}
+
+impl DiverseArchive {
+    fn new(config: AlternativeConfig) -> Self {
+        Self {
+            config,
+            entries: Vec::with_capacity(config.count + 1),
         }
+    }
 
-        // 交换并计算新代价
-        current.swap_students(idx1, idx2, students, subject_order);
-        let new_cost = current.calculate_cost(params);
-        let delta = new_cost - current_cost;
+    fn offer(&mut self, solution: &Solution, cost: f64) {
+        let full = self.entries.len() >= self.config.count;
+        if full && self.entries.last().is_some_and(|(worst, _)| cost >= *worst) {
+            return;
+        }
+
+        let similar: Vec<usize> = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, entry))| {
+                assignment_distance(&entry.assignments, &solution.assignments)
+                    < self.config.min_distance
+            })
+            .map(|(i, _)| i)
+            .collect();
+        if similar.iter().any(|&i| self.entries[i].0 <= cost) {
+            return;
+        }
+
+        for &i in similar.iter().rev() {
+            self.entries.remove(i);
+        }
+        let position = self.entries.partition_point(|(c, _)| *c <= cost);
+        self.entries.insert(position, (cost, solution.clone()));
+        self.entries.truncate(self.config.count);
+    }
+
+    /// 除 `best` 以外的备选方案
+    fn into_alternatives(self, best: &Solution) -> Vec<Solution> {
+        let config = self.config;
+        self.entries
+            .into_iter()
+            .map(|(_, solution)| solution)
+            .filter(|solution| {
+                assignment_distance(&solution.assignments, &best.assignments)
+                    >= config.min_distance.max(1)
+            })
+            .take(config.count.saturating_sub(1))
+            .collect()
+    }
+}
+
+/// 并行多实例搜索
+///
+/// 各实例每 `SYNC_INTERVAL` 次迭代同步一次，早停只发生在同步点上，
+/// 因此结果只取决于种子和实例数，而与线程调度无关（设置时间预算时除外）
+fn parallel_search(
+    ctx: &SearchContext,
+    plan: &SearchPlan,
+    cancel_token: CancellationToken,
+    params: &OptimizationParams,
+) -> SearchOutcome {
+    let start = Instant::now();
+    let table = ctx.table;
+    // 每个实例使用全部迭代次数，不除以实例数
+    let iterations_per_instance = plan.total_iterations;
+    let time_budget = plan.time_budget;
+
+    let instance_params: Vec<OptimizationParams> = (0..plan.num_instances)
+        .map(|instance_id| instance_params(params, instance_id, plan.pareto))
+        .collect();
+
+    // 不同实例使用略微不同的参数以增加多样性
+    let mut instances: Vec<AnnealingState> = (0..plan.num_instances)
+        .into_par_iter()
+        .map(|instance_id| {
+            let mut temp = params.initial_temperature
+                + (instance_id as f64 * params.temperature_diversity_delta);
+            let mut rng = StdRng::seed_from_u64(instance_seed(plan.seed, instance_id));
+            let initial = match plan.initial {
+                // 热启动时从重新加热温度开始，避免破坏已有解
+                Some(solution) => {
+                    temp *= params.reheat_temperature_factor;
+                    solution.clone()
+                }
+                // 0 号实例按策略确定性地构造，其余实例加入随机扰动
+                None => create_initial_solution(
+                    table,
+                    plan.num_classes,
+                    params.seeding_strategy,
+                    &mut rng,
+                    if instance_id == 0 {
+                        0.0
+                    } else {
+                        INITIAL_JITTER
+                    },
+                ),
+            };
+            AnnealingState::new(
+                initial
+                    .with_name_groups(ctx.name_groups.clone())
+                    .with_preferences(ctx.preferences.clone())
+                    .with_objectives(ctx.objectives),
+                table.len(),
+                temp,
+                params.cooling_rate,
+                rng,
+                &instance_params[instance_id],
+            )
+            .with_trace(instance_id, plan.trace_interval)
+        })
+        .collect();
 
-        // Metropolis 准则
-        if delta < 0.0 || rng.random::<f64>() < (-delta / temperature).exp() {
-            current_cost = new_cost;
-            accept_count += 1;
+    let mut archive = plan.alternatives.map(DiverseArchive::new);
+    let mut front = plan.pareto.then(ParetoArchive::default);
+    // 多目标模式下各实例权重不同，比较时统一按 `params` 重新计算代价
+    let base_cost = |solution: &Solution, cached: f64| {
+        if plan.pareto {
+            solution.calculate_cost(params)
+        } else {
+            cached
+        }
+    };
 
-            if new_cost < best_cost {
-                best = current.clone();
-                best_cost = new_cost;
-                iterations_since_improvement = 0;
+    // 分段并行推进所有实例
+    let mut iterations_done = 0;
+    let mut best_cost = f64::INFINITY;
+    let mut last_improvement = 0;
+    let stop_reason = loop {
+        if cancel_token.is_cancelled() {
+            break StopReason::Cancelled;
+        }
+        if iterations_done >= iterations_per_instance {
+            break StopReason::IterationLimit;
+        }
+        if time_budget.is_some_and(|budget| start.elapsed() >= budget) {
+            break StopReason::TimeBudget;
+        }
 
-                // 如果找到非常好的解（可能满足所有约束），标记
-                if best_cost < params.good_solution_threshold {
-                    found_solution.cancel();
-                }
-            } else {
-                iterations_since_improvement += 1;
+        let chunk = SYNC_INTERVAL.min(iterations_per_instance - iterations_done);
+        let found: Vec<bool> = instances
+            .par_iter_mut()
+            .zip(&instance_params)
+            .map(|(state, params)| simulated_annealing(state, ctx, chunk, params))
+            .collect();
+        iterations_done += chunk;
+
+        // 在同步点上按实例顺序收集候选解，保证结果与线程调度无关
+        if let Some(archive) = &mut archive {
+            for state in &instances {
+                archive.offer(&state.best, base_cost(&state.best, state.best_cost));
+                archive.offer(
+                    &state.current,
+                    base_cost(&state.current, state.current_cost),
+                );
+            }
+        }
+        if let Some(front) = &mut front {
+            for state in &instances {
+                front.offer(&state.best, params);
+                front.offer(&state.current, params);
             }
-        } else {
-            // 拒绝交换，恢复
-            current.swap_students(idx1, idx2, students, subject_order);
-            iterations_since_improvement += 1;
         }
 
-        // 自适应冷却：如果长时间没有改进，重新加热
-        if iterations_since_improvement > params.reheat_after_iterations
-            && accept_count < params.reheat_min_accept_count
+        if found.into_iter().any(|f| f) {
+            break StopReason::TargetReached;
+        }
+
+        // 收敛检测：硬约束全部满足且在窗口内没有改进
+        let (round_best, round_cost) = best_instance(&instances, base_cost);
+        if round_cost < best_cost {
+            best_cost = round_cost;
+            last_improvement = iterations_done;
+        } else if params.plateau_window > 0
+            && iterations_done - last_improvement >= params.plateau_window
+            && instances[round_best].best.meets_hard_constraints(params)
         {
-            temperature = initial_temp * params.reheat_temperature_factor;
-            iterations_since_improvement = 0;
-            accept_count = 0;
-        } else {
-            temperature *= cooling_rate;
+            break StopReason::Converged;
         }
+    };
+
+    let (winning_instance, _) = best_instance(&instances, base_cost);
+    let solution = instances[winning_instance].best.clone();
+    let alternatives = archive
+        .map(|archive| archive.into_alternatives(&solution))
+        .unwrap_or_default();
+    let pareto_front = front.map(ParetoArchive::into_front).unwrap_or_default();
+
+    let mut trace: Vec<TracePoint> = instances
+        .iter_mut()
+        .flat_map(|state| std::mem::take(&mut state.trace))
+        .collect();
+    trace.sort_by_key(|point| (point.iteration, point.instance));
+
+    SearchOutcome {
+        solution,
+        stop_reason,
+        iterations: iterations_done,
+        winning_instance,
+        trace,
+        alternatives,
+        pareto_front,
     }
+}
 
-    best
+/// 代价最低的实例编号及其代价（代价相同时取编号最小的实例）
+///
+/// 代价含自定义目标项的返回值，用 `total_cmp` 比较，目标项返回 NaN 时该实例排在最后而不会中断分班
+fn best_instance(
+    instances: &[AnnealingState],
+    cost: impl Fn(&Solution, f64) -> f64,
+) -> (usize, f64) {
+    instances
+        .iter()
+        .map(|state| cost(&state.best, state.best_cost))
+        .enumerate()
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .unwrap()
 }
 
-/// 并行多实例搜索
-fn parallel_search(
-    students: &[Student],
-    num_classes: usize,
-    subject_order: &[String],
-    total_iterations: usize,
+/// 把搜索结果整理为通用分组结果
+fn finish(
+    table: &FeatureTable,
+    outcome: SearchOutcome,
+    seed: u64,
     num_instances: usize,
-    cancel_token: CancellationToken,
+    start: Instant,
     params: &OptimizationParams,
-) -> Solution {
-    let found_solution = CancellationToken::new();
-    // 每个实例使用全部迭代次数，不除以实例数
-    let iterations_per_instance = total_iterations;
+) -> Partition {
+    let elapsed = start.elapsed();
+    let build = |solution: &Solution, trace: Vec<TracePoint>| {
+        // 重新统计一次，避免增量更新累积的浮点误差影响报告的代价
+        let num_groups = solution.class_stats.len();
+        let solution = Solution::from_assignments(&solution.assignments, num_groups, table)
+            .with_reference(solution.reference.clone())
+            .with_name_groups(solution.name_groups.clone())
+            .with_preferences(solution.preferences.clone())
+            .with_objectives(&solution.objectives);
+
+        Partition {
+            cost: solution.cost_breakdown(params),
+            constraints: solution.constraint_flags(params),
+            moved: solution.reference.as_ref().map(|_| solution.moves),
+            objective_costs: solution.objective_costs(),
+            assignments: solution.assignments,
+            num_groups,
+            iterations: outcome.iterations,
+            num_instances,
+            elapsed,
+            winning_instance: outcome.winning_instance,
+            seed,
+            stop_reason: outcome.stop_reason,
+            trace,
+            alternatives: Vec::new(),
+            pareto_front: Vec::new(),
+            warnings: Vec::new(),
+        }
+    };
 
-    // 并行运行多个实例
-    let solutions: Vec<Solution> = (0..num_instances)
-        .into_par_iter()
-        .map(|instance_id| {
-            let initial = create_initial_solution(students, num_classes, subject_order);
-            // 不同实例使用略微不同的参数以增加多样性
-            let temp = params.initial_temperature
-                + (instance_id as f64 * params.temperature_diversity_delta);
-            let cooling = params.cooling_rate;
-            simulated_annealing(
-                &initial,
-                students,
-                subject_order,
-                iterations_per_instance,
-                temp,
-                cooling,
-                found_solution.clone(),
-                cancel_token.clone(),
-                params,
-            )
-        })
+    let mut result = build(&outcome.solution, outcome.trace.clone());
+    result.alternatives = outcome
+        .alternatives
+        .iter()
+        .map(|solution| build(solution, Vec::new()))
         .collect();
-
-    // 返回最优解
-    solutions
-        .into_iter()
-        .min_by(|a, b| {
-            a.calculate_cost(params)
-                .partial_cmp(&b.calculate_cost(params))
-                .unwrap()
-        })
-        .unwrap()
+    result.pareto_front = outcome
+        .pareto_front
+        .iter()
+        .map(|solution| build(solution, Vec::new()))
+        .collect();
+    result
 }
 
-/// 分班主函数
-pub fn divide(students: &[Student], config: DivideConfig) -> Vec<Class> {
-    divide_with_cancel(students, config, CancellationToken::new())
+/// 检查热启动相关的设置是否与项目数、组数匹配，不匹配的设置忽略并返回提示
+///
+/// 这些都是公开字段，调用方可能传入与本次数据不符的值（例如学生名单变动后沿用旧结果），
+/// 此时按没有热启动处理，而不是中断分班
+pub(crate) fn checked_warm_start(
+    mut config: DivideConfig,
+    len: usize,
+) -> (DivideConfig, Vec<String>) {
+    let mut warnings = Vec::new();
+    if let Some(reference) = &config.reference_assignments
+        && reference.len() != len
+    {
+        warnings.push(format!(
+            "原分班有 {} 人，与本次 {} 人不匹配，已忽略原分班",
+            reference.len(),
+            len
+        ));
+        config.reference_assignments = None;
+    }
+    if let Some(initial) = &config.initial_assignments
+        && (initial.len() != len || initial.iter().any(|&c| c >= config.num_classes))
+    {
+        warnings.push(format!(
+            "初始分配与本次 {} 人、{} 个班不匹配，已忽略并重新分班",
+            len, config.num_classes
+        ));
+        config.initial_assignments = None;
+    }
+    if config.active_classes.is_some() && config.initial_assignments.is_none() {
+        warnings.push("部分重新优化需要有效的初始分配，已改为调整全部班级".to_string());
+        config.active_classes = None;
+    }
+    (config, warnings)
 }
 
-/// 分班主函数（支持取消）
-pub fn divide_with_cancel(
-    students: &[Student],
-    config: DivideConfig,
+/// 在特征表上执行均衡分组搜索，分班与通用分组共用
+///
+/// 热启动设置须已通过 [`checked_warm_start`] 检查
+pub(crate) fn search(
+    table: &FeatureTable,
+    config: &DivideConfig,
     cancel_token: CancellationToken,
-) -> Vec<Class> {
+    extras: SearchExtras,
+) -> Partition {
+    let start = Instant::now();
     let num_classes = config.num_classes;
-    let max_iterations = config.max_iterations;
     let params = &config.optimization_params;
+    let seed = config.seed.unwrap_or_else(rand::random);
+    let len = table.len();
 
-    if students.is_empty() || num_classes == 0 {
-        return vec![];
-    }
-
-    if students.len() < num_classes {
-        return students
-            .iter()
-            .enumerate()
-            .map(|(i, s)| {
-                let mut class = Class::new(i);
-                class.add_student(s.clone());
-                class
-            })
-            .collect();
-    }
-
-    // 获取科目顺序
-    let subject_order: Vec<String> = if let Some(first_student) = students.first() {
-        first_student.scores.keys().cloned().collect()
-    } else {
-        vec![]
-    };
-
-    // 智能调整并行实例数（基于 CPU 核心数和数据规模）
+    // 并行实例数：指定种子时固定，否则按 CPU 核心数和数据规模调整
     let num_instances = if let Some(instances) = params.num_parallel_instances {
-        instances
+        instances.max(1)
+    } else if config.seed.is_some() {
+        SEEDED_PARALLEL_INSTANCES
     } else {
         let num_cpus = num_cpus::get();
-        if students.len() > 2000 {
+        if len > 2000 {
             num_cpus.min(16)
-        } else if students.len() > 1000 {
+        } else if len > 1000 {
             num_cpus.min(12)
-        } else if students.len() > 500 {
+        } else if len > 500 {
             num_cpus.min(8)
         } else {
             4
         }
     };
 
-    // 智能调整迭代次数：保持足够的迭代次数以满足约束
-    let adjusted_iterations = if students.len() > 3000 {
-        // 超大规模：使用更多并行实例，每个实例适度迭代
-        max_iterations.max(500000)
-    } else if students.len() > 1000 {
-        // 大规模：平衡迭代次数和并行度
-        max_iterations.max(400000)
+    if len == 0 || num_classes == 0 {
+        let empty = FeatureTable::default();
+        let outcome = SearchOutcome::trivial(Solution::new(0, &empty));
+        return finish(&empty, outcome, seed, num_instances, start, params);
+    }
+
+    if len < num_classes {
+        // 项目数少于组数：每个项目单独一组
+        let assignments: Vec<usize> = (0..len).collect();
+        let solution = Solution::from_assignments(&assignments, len, table);
+        let outcome = SearchOutcome::trivial(solution);
+        return finish(table, outcome, seed, num_instances, start, params);
+    }
+
+    // 设置了时间预算时以时间为准，否则按调用方给定的迭代次数执行
+    let iterations = if config.time_budget.is_some() {
+        usize::MAX
     } else {
-        // 中小规模：使用较多迭代
-        max_iterations.max(300000)
+        config.max_iterations
+    };
+
+    let reference: Option<Arc<[Option<usize>]>> =
+        config.reference_assignments.as_deref().map(|reference| {
+            assert!(reference.len() == len, "原分班与学生数不匹配");
+            Arc::from(reference)
+        });
+    let initial = match (&config.initial_assignments, &reference) {
+        (Some(assignments), _) => {
+            assert!(
+                assignments.len() == len && assignments.iter().all(|&c| c < num_classes),
+                "初始分配与学生数或班级数不匹配"
+            );
+            Some(
+                Solution::from_assignments(assignments, num_classes, table)
+                    .with_reference(reference.clone()),
+            )
+        }
+        (None, Some(reference)) => Some(create_reference_solution(table, num_classes, reference)),
+        (None, None) => None,
     };
 
-    let solution = parallel_search(
-        students,
+    let mut ctx = SearchContext::new(table, extras).with_objectives(&config.objectives);
+    if let Some(active_classes) = &config.active_classes {
+        let initial = config
+            .initial_assignments
+            .as_ref()
+            .expect("部分重新优化需要提供初始分配");
+        ctx = ctx.restrict_to(|idx| active_classes.contains(&initial[idx]));
+    }
+
+    let plan = SearchPlan {
         num_classes,
-        &subject_order,
-        adjusted_iterations,
+        total_iterations: iterations,
         num_instances,
-        cancel_token,
-        params,
-    );
+        seed,
+        time_budget: config.time_budget,
+        initial: initial.as_ref(),
+        trace_interval: config.trace_interval.unwrap_or(0),
+        alternatives: config.alternatives,
+        pareto: config.pareto,
+    };
+
+    let outcome = parallel_search(&ctx, &plan, cancel_token, params);
+    let mut partition = finish(table, outcome, seed, num_instances, start, params);
+    if config.seed.is_some() && config.time_budget.is_some() {
+        partition.warnings.push(format!(
+            "按时间预算停止，相同种子在其他机器上不一定得到相同结果；复现时请取消时间预算，\
+             按 {} 次迭代、{} 个并行实例运行",
+            partition.iterations, num_instances
+        ));
+    }
+    partition
+}
+
+/// 学生作为分组项目：数值特征为总分与各科成绩，类别特征为性别
+struct StudentFeatures<'a> {
+    student: &'a Student,
+    subject_order: &'a [String],
+}
+
+impl Partitionable for StudentFeatures<'_> {
+    fn numeric_features(&self) -> Vec<f64> {
+        std::iter::once(self.student.total_score)
+            .chain(
+                self.subject_order
+                    .iter()
+                    .map(|subject| self.student.scores.get(subject).copied().unwrap_or(0.0)),
+            )
+            .collect()
+    }
+
+    fn categorical_features(&self) -> Vec<Option<usize>> {
+        let gender = match self.student.gender {
+            Gender::Male => Some(0),
+            Gender::Female => Some(1),
+            Gender::Unknown => None,
+        };
+        vec![gender]
+    }
+}
+
+/// 学生的特征表，科目按第一个学生的科目排序（保证同一种子下结果稳定）
+fn student_table(students: &[Student]) -> FeatureTable {
+    let mut subject_order: Vec<String> = students
+        .first()
+        .map(|student| student.scores.keys().cloned().collect())
+        .unwrap_or_default();
+    subject_order.sort();
+
+    let items: Vec<StudentFeatures> = students
+        .iter()
+        .map(|student| StudentFeatures {
+            student,
+            subject_order: &subject_order,
+        })
+        .collect();
+    let mut table = FeatureTable::new(&items).expect("每名学生的特征个数相同");
+    // 性别固定为男、女两类，即使只有一种性别
+    table.category_sizes = vec![2];
+    table
+}
+
+/// 分班主函数
+pub fn divide(students: &[Student], config: DivideConfig) -> DivideResult {
+    divide_with_cancel(students, config, CancellationToken::new())
+}
 
-    solution.to_classes(students)
+/// 分班主函数（支持取消）
+pub fn divide_with_cancel(
+    students: &[Student],
+    config: DivideConfig,
+    cancel_token: CancellationToken,
+) -> DivideResult {
+    let (config, warnings) = checked_warm_start(config, students.len());
+    let table = student_table(students);
+    let extras = SearchExtras::for_students(students, &config.optimization_params);
+    let mut partition = search(&table, &config, cancel_token, extras);
+    partition.warnings.splice(0..0, warnings);
+    DivideResult::from_partition(students, partition, &config.optimization_params)
 }
 
 /// 验证约束条件（使用默认阈值）
@@ -866,11 +2842,13 @@ pub fn validate_constraints_with_params(
             max_gender_ratio_diff: 0.0,
             max_class_size_diff: 0,
             subject_max_diffs: vec![],
+            name_constraints_met: true,
+            duplicate_names: vec![],
         };
     }
 
     // 获取所有科目
-    let subjects: Vec<String> = if let Some(first_class) = classes.first() {
+    let mut subjects: Vec<String> = if let Some(first_class) = classes.first() {
         if let Some(first_student) = first_class.students.first() {
             first_student.scores.keys().cloned().collect()
         } else {
@@ -879,6 +2857,7 @@ pub fn validate_constraints_with_params(
     } else {
         vec![]
     };
+    subjects.sort();
 
     // 计算总分约束
     let total_avgs: Vec<f64> = classes.iter().map(|c| c.avg_total_score()).collect();
@@ -917,6 +2896,25 @@ pub fn validate_constraints_with_params(
         subject_max_diffs.push((subject.clone(), diff));
     }
 
+    // 检查重名学生同班
+    let duplicate_names: Vec<(usize, Vec<String>)> = match params.duplicate_names {
+        Some(mode) => classes
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, class)| {
+                let groups = duplicate_name_groups(&class.students, mode);
+                (!groups.is_empty()).then(|| {
+                    let labels = groups
+                        .iter()
+                        .map(|group| describe_group(&class.students, group))
+                        .collect();
+                    (idx, labels)
+                })
+            })
+            .collect(),
+        None => Vec::new(),
+    };
+
     ConstraintValidation {
         score_constraints_met,
         gender_constraints_met,
@@ -925,5 +2923,678 @@ pub fn validate_constraints_with_params(
         max_gender_ratio_diff,
         max_class_size_diff,
         subject_max_diffs,
+        name_constraints_met: duplicate_names.is_empty(),
+        duplicate_names,
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::core::objective::SpreadObjective;
+    use crate::core::preferences::Preference;
+    use std::collections::HashMap;
+
+    /// 测试用学生：三分之一为女生，语文、数学成绩分散且互不相同
+    pub(crate) fn sample_students(count: usize) -> Vec<Student> {
+        (0..count)
+            .map(|i| {
+                let gender = if i % 3 == 0 {
+                    Gender::Female
+                } else {
+                    Gender::Male
+                };
+                let mut scores = HashMap::new();
+                scores.insert("语文".to_string(), 60.0 + ((i * 37) % 41) as f64 + 0.3);
+                scores.insert("数学".to_string(), 55.0 + ((i * 53) % 45) as f64 + 0.7);
+                Student::new(format!("学生{}", i), gender, scores)
+            })
+            .collect()
+    }
+
+    /// 测试用班级：由 [`sample_students`] 组成，编号为 `id`
+    pub(crate) fn sample_class(id: usize, count: usize) -> Class {
+        let mut class = Class::new(id);
+        for student in sample_students(count) {
+            class.add_student(student);
+        }
+        class
+    }
+
+    #[test]
+    fn test_seeded_search_is_reproducible_across_thread_counts() {
+        let students = sample_students(90);
+        let table = student_table(&students);
+        let params = OptimizationParams::default();
+
+        let run = |threads: usize| {
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap()
+                .install(|| {
+                    let ctx = SearchContext::new(&table, SearchExtras::default());
+                    let plan = SearchPlan {
+                        num_classes: 3,
+                        total_iterations: 5000,
+                        num_instances: 4,
+                        seed: 42,
+                        time_budget: None,
+                        initial: None,
+                        trace_interval: 0,
+                        alternatives: None,
+                        pareto: false,
+                    };
+                    parallel_search(&ctx, &plan, CancellationToken::new(), &params)
+                        .solution
+                        .assignments
+                })
+        };
+
+        let single = run(1);
+        assert_eq!(single, run(1));
+        assert_eq!(single, run(4));
+    }
+
+    #[test]
+    fn test_time_budget_run_replays_with_recorded_iterations() {
+        let students = sample_students(90);
+        let timed = divide(
+            &students,
+            DivideConfig::new(3)
+                .with_seed(Some(42))
+                .with_time_budget(Some(Duration::from_millis(30))),
+        );
+        assert_eq!(timed.num_instances, SEEDED_PARALLEL_INSTANCES);
+        assert_eq!(timed.warnings.len(), 1);
+
+        let replay = divide(
+            &students,
+            DivideConfig::new(3)
+                .with_seed(Some(42))
+                .with_iterations(timed.iterations),
+        );
+        assert_eq!(replay.assignments, timed.assignments);
+        assert!(replay.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_stop_reason_reports_budget_and_cancel() {
+        let students = sample_students(30);
+
+        let config = DivideConfig::new(3).with_time_budget(Some(Duration::ZERO));
+        let result = divide_with_cancel(&students, config, CancellationToken::new());
+        assert_eq!(result.stop_reason, StopReason::TimeBudget);
+        assert_eq!(result.classes.len(), 3);
+
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let result = divide_with_cancel(&students, DivideConfig::new(3), cancel);
+        assert_eq!(result.stop_reason, StopReason::Cancelled);
+        assert!(result.is_partial());
+        assert_eq!(result.assignments.len(), students.len());
+        assert_eq!(
+            result
+                .classes
+                .iter()
+                .map(|c| c.students.len())
+                .sum::<usize>(),
+            students.len()
+        );
+    }
+
+    #[test]
+    fn test_stop_reason_reports_convergence_and_target() {
+        let students = sample_students(60);
+        let limit = 1_000_000;
+
+        // 不设目标代价：硬约束满足后 2000 次迭代没有改进即停止
+        let params = OptimizationParams {
+            num_parallel_instances: Some(1),
+            good_solution_threshold: 0.0,
+            plateau_window: 2_000,
+            ..Default::default()
+        };
+        let result = divide(
+            &students,
+            DivideConfig::new(3)
+                .with_optimization_params(params)
+                .with_seed(Some(1))
+                .with_iterations(limit),
+        );
+        assert_eq!(result.stop_reason, StopReason::Converged);
+        assert!(result.iterations < limit);
+        assert_eq!(result.cost.hard_penalty(), 0.0);
+
+        // 关闭收敛检测：代价低于目标即停止
+        let params = OptimizationParams {
+            num_parallel_instances: Some(1),
+            good_solution_threshold: 1_000.0,
+            plateau_window: 0,
+            ..Default::default()
+        };
+        let result = divide(
+            &students,
+            DivideConfig::new(3)
+                .with_optimization_params(params)
+                .with_seed(Some(1))
+                .with_iterations(limit),
+        );
+        assert_eq!(result.stop_reason, StopReason::TargetReached);
+        assert!(result.iterations < limit);
+        assert!(result.cost.total() < 1_000.0);
+    }
+
+    #[test]
+    fn test_warm_start_never_worsens_initial_assignment() {
+        let students = sample_students(60);
+        let params = OptimizationParams::default();
+        let first = divide_with_cancel(
+            &students,
+            DivideConfig::new(3)
+                .with_seed(Some(7))
+                .with_time_budget(Some(Duration::from_millis(50))),
+            CancellationToken::new(),
+        );
+
+        let resumed = divide_with_cancel(
+            &students,
+            DivideConfig::new(3)
+                .with_seed(Some(8))
+                .with_time_budget(Some(Duration::from_millis(50)))
+                .with_initial_assignments(Some(first.assignments.clone())),
+            CancellationToken::new(),
+        );
+
+        let table = student_table(&students);
+        let cost = |assignments: &[usize]| {
+            Solution::from_assignments(assignments, 3, &table).calculate_cost(&params)
+        };
+        assert!(cost(&resumed.assignments) <= cost(&first.assignments));
+    }
+
+    #[test]
+    fn test_unknown_gender_is_kept_and_ignored_in_gender_ratio() {
+        let students: Vec<Student> = sample_students(60)
+            .into_iter()
+            .enumerate()
+            .map(|(i, mut student)| {
+                if i % 5 == 0 {
+                    student.gender = Gender::Unknown;
+                }
+                student
+            })
+            .collect();
+
+        let result = divide(
+            &students,
+            DivideConfig::new(3)
+                .with_seed(Some(5))
+                .with_iterations(20_000),
+        );
+
+        let assigned: usize = result.classes.iter().map(|c| c.students.len()).sum();
+        assert_eq!(assigned, students.len());
+        for class in &result.classes {
+            let known = class.male_count() + class.female_count();
+            assert_eq!(known + class.unknown_gender_count(), class.students.len());
+            assert_eq!(
+                class.gender_ratio(),
+                class.male_count() as f64 / known as f64
+            );
+        }
+        assert!(result.validation.gender_constraints_met);
+
+        // 没有已知性别的班级视为男女各半
+        let mut unknown_only = Class::new(0);
+        for mut student in sample_students(3) {
+            student.gender = Gender::Unknown;
+            unknown_only.add_student(student);
+        }
+        assert_eq!(unknown_only.gender_ratio(), 0.5);
+    }
+
+    #[test]
+    fn test_duplicate_names_are_kept_apart() {
+        let mut students = sample_students(60);
+        students[0].name = "张伟".to_string();
+        students[3].name = "张伟".to_string();
+        students[6].name = "章伟".to_string();
+        students[9].name = "李娜".to_string();
+        students[12].name = "李娜".to_string();
+        let params = OptimizationParams {
+            duplicate_names: Some(NameMatch::Pinyin),
+            ..Default::default()
+        };
+
+        let result = divide(
+            &students,
+            DivideConfig::new(3)
+                .with_optimization_params(params.clone())
+                .with_seed(Some(9))
+                .with_iterations(20_000),
+        );
+
+        assert!(result.validation.name_constraints_met);
+        assert_eq!(result.cost.duplicate_name_penalty, 0.0);
+
+        // 四个同名学生无法分到三个班而不重名，验证结果应标出
+        students[15].name = "张伟".to_string();
+        let result = divide(
+            &students,
+            DivideConfig::new(3)
+                .with_optimization_params(params)
+                .with_seed(Some(9))
+                .with_iterations(20_000),
+        );
+        assert!(!result.validation.name_constraints_met);
+        assert!(!result.validation.duplicate_names.is_empty());
+        assert!(result.cost.duplicate_name_penalty > 0.0);
+    }
+
+    #[test]
+    fn test_friend_preferences_are_tracked_and_honoured() {
+        // 学生两两互填志愿
+        let students: Vec<Student> = sample_students(60)
+            .into_iter()
+            .enumerate()
+            .map(|(i, student)| {
+                student.with_preferences(vec![Preference::new(format!("学生{}", i ^ 1), 1.0)])
+            })
+            .collect();
+        let table = student_table(&students);
+        let index = PreferenceIndex::new(&students).map(Arc::new);
+
+        // 增量更新与重新统计一致
+        let assignments: Vec<usize> = (0..students.len()).map(|i| i % 3).collect();
+        let mut solution =
+            Solution::from_assignments(&assignments, 3, &table).with_preferences(index.clone());
+        assert_eq!(solution.unmet_students, 60);
+        for (a, b) in [(0, 1), (2, 4), (1, 5), (7, 6)] {
+            solution.swap_students(a, b, &table);
+        }
+        let fresh =
+            Solution::from_assignments(&solution.assignments, 3, &table).with_preferences(index);
+        assert_eq!(solution.unmet_weight, fresh.unmet_weight);
+        assert_eq!(solution.satisfied_counts, fresh.satisfied_counts);
+        assert_eq!(solution.unmet_students, fresh.unmet_students);
+
+        let params = OptimizationParams {
+            num_parallel_instances: Some(2),
+            initial_temperature: 20.0,
+            temperature_diversity_delta: 0.0,
+            ..Default::default()
+        };
+        let result = divide(
+            &students,
+            DivideConfig::new(3)
+                .with_optimization_params(params)
+                .with_seed(Some(5))
+                .with_iterations(30_000),
+        );
+        let report = result.preferences.as_ref().unwrap();
+        assert_eq!(report.requests, 60);
+        assert!(report.satisfaction_rate() > 0.8);
+        assert_eq!(
+            result.cost.preference_cost,
+            (report.requests - report.satisfied) as f64 * 2.0
+                + report.unmet_students.len() as f64 * 10.0
+        );
+    }
+
+    #[test]
+    fn test_custom_objective_is_updated_and_optimized() {
+        let students = sample_students(60);
+        let table = student_table(&students);
+        let flags: Vec<bool> = (0..students.len()).map(|i| i < 12).collect();
+        let objectives: Vec<Box<dyn Objective>> =
+            vec![Box::new(SpreadObjective::new("班干部分散", flags, 100.0))];
+
+        // 增量更新与重新统计一致
+        let assignments: Vec<usize> = (0..students.len()).map(|i| i % 3).collect();
+        let mut solution =
+            Solution::from_assignments(&assignments, 3, &table).with_objectives(&objectives);
+        for (a, b) in [(0, 1), (2, 4), (1, 5), (7, 30)] {
+            solution.swap_students(a, b, &table);
+        }
+        let fresh = Solution::from_assignments(&solution.assignments, 3, &table)
+            .with_objectives(&objectives);
+        assert_eq!(solution.objective_costs(), fresh.objective_costs());
+
+        let params = OptimizationParams {
+            num_parallel_instances: Some(2),
+            initial_temperature: 20.0,
+            temperature_diversity_delta: 0.0,
+            ..Default::default()
+        };
+        let result = divide(
+            &students,
+            DivideConfig::new(3)
+                .with_optimization_params(params)
+                .with_seed(Some(6))
+                .with_iterations(30_000)
+                .with_objective(SpreadObjective::new(
+                    "班干部分散",
+                    (0..students.len()).map(|i| i < 12).collect(),
+                    100.0,
+                )),
+        );
+        for class_id in 0..3 {
+            let count = (0..12)
+                .filter(|&i| result.assignments[i] == class_id)
+                .count();
+            assert_eq!(count, 4);
+        }
+        assert_eq!(
+            result.objective_costs,
+            vec![("班干部分散".to_string(), 0.0)]
+        );
+        assert_eq!(result.cost.custom_cost, 0.0);
+    }
+
+    #[test]
+    fn test_mismatched_warm_start_is_ignored_with_warning() {
+        let students = sample_students(30);
+        let params = OptimizationParams {
+            num_parallel_instances: Some(1),
+            ..Default::default()
+        };
+        let result = divide(
+            &students,
+            DivideConfig::new(3)
+                .with_optimization_params(params.clone())
+                .with_seed(Some(1))
+                .with_iterations(2_000)
+                .with_initial_assignments(Some(vec![0; 20]))
+                .with_active_classes(Some(vec![0])),
+        );
+        assert_eq!(result.assignments.len(), 30);
+        assert_eq!(result.warnings.len(), 2);
+        assert!(result.summary_items().iter().any(|(key, _)| key == "提示"));
+
+        let result = divide(
+            &students,
+            DivideConfig::new(3)
+                .with_optimization_params(params)
+                .with_seed(Some(1))
+                .with_iterations(2_000)
+                .with_initial_assignments(Some(vec![5; 30]))
+                .with_reference_assignments(Some(vec![Some(0); 29])),
+        );
+        assert_eq!(result.warnings.len(), 2);
+        assert_eq!(result.moved, None);
+    }
+
+    #[test]
+    fn test_max_iterations_is_honoured() {
+        let students = sample_students(200);
+        let params = OptimizationParams {
+            num_parallel_instances: Some(1),
+            ..Default::default()
+        };
+        let result = divide(
+            &students,
+            DivideConfig::new(4)
+                .with_optimization_params(params)
+                .with_seed(Some(1))
+                .with_iterations(2_000),
+        );
+        assert!(result.iterations > 0 && result.iterations <= 2_000);
+    }
+
+    #[test]
+    fn test_nan_objective_does_not_abort_division() {
+        let students = sample_students(30);
+        let params = OptimizationParams {
+            num_parallel_instances: Some(2),
+            ..Default::default()
+        };
+        let result = divide(
+            &students,
+            DivideConfig::new(3)
+                .with_optimization_params(params)
+                .with_seed(Some(2))
+                .with_time_budget(Some(Duration::from_millis(100)))
+                .with_objective(SpreadObjective::new("无效", vec![true; 30], f64::NAN)),
+        );
+        assert_eq!(result.assignments.len(), 30);
+        assert!(result.cost.custom_cost.is_nan());
+    }
+
+    #[test]
+    fn test_divide_result_reports_cost_and_metadata() {
+        let students = sample_students(45);
+        let params = OptimizationParams {
+            num_parallel_instances: Some(2),
+            ..Default::default()
+        };
+        let result = divide(
+            &students,
+            DivideConfig::new(3)
+                .with_optimization_params(params.clone())
+                .with_seed(Some(11))
+                .with_time_budget(Some(Duration::from_millis(30))),
+        );
+
+        assert_eq!(result.seed, 11);
+        assert!(result.winning_instance < 2);
+        assert_eq!(result.iterations % SYNC_INTERVAL, 0);
+
+        let table = student_table(&students);
+        let solution = Solution::from_assignments(&result.assignments, 3, &table);
+        assert_eq!(result.cost, solution.cost_breakdown(&params));
+        assert_eq!(result.cost.total(), solution.calculate_cost(&params));
+    }
+
+    #[test]
+    fn test_trace_records_every_interval_per_instance() {
+        let students = sample_students(40);
+        let params = OptimizationParams {
+            num_parallel_instances: Some(2),
+            plateau_window: 0,
+            ..Default::default()
+        };
+        let config = DivideConfig::new(4)
+            .with_optimization_params(params)
+            .with_seed(Some(5))
+            .with_time_budget(Some(Duration::from_millis(30)));
+
+        let untraced = divide(&students, config.clone());
+        assert!(untraced.trace.is_empty());
+
+        let result = divide(&students, config.with_trace_interval(Some(250)));
+        assert_eq!(result.trace.len(), result.iterations / 250 * 2);
+        for pair in result.trace.chunks(2) {
+            assert_eq!(pair[0].iteration, pair[1].iteration);
+            assert_eq!((pair[0].instance, pair[1].instance), (0, 1));
+        }
+        for point in &result.trace {
+            assert!(point.iteration.is_multiple_of(250));
+            assert!(point.best_cost <= point.current_cost);
+            assert!((0.0..=1.0).contains(&point.acceptance_rate));
+        }
+    }
+
+    #[test]
+    fn test_reference_mode_keeps_students_in_place() {
+        let students = sample_students(63);
+        // 原分班 3 个班，新增 3 名学生，班级数改为 4
+        let mut reference: Vec<Option<usize>> = (0..60).map(|i| Some(i % 3)).collect();
+        reference.extend([None; 3]);
+        let params = OptimizationParams {
+            num_parallel_instances: Some(2),
+            ..Default::default()
+        };
+
+        let result = divide(
+            &students,
+            DivideConfig::new(4)
+                .with_optimization_params(params.clone())
+                .with_seed(Some(3))
+                .with_iterations(30_000)
+                .with_reference_assignments(Some(reference.clone())),
+        );
+
+        let moved = reference
+            .iter()
+            .zip(&result.assignments)
+            .filter(|(original, class_id)| original.is_some_and(|c| c != **class_id))
+            .count();
+        assert_eq!(result.moved, Some(moved));
+        // 新班级 15 人中至少 12 人来自原班级
+        assert!(moved >= 12);
+        assert_eq!(result.cost.move_cost, moved as f64 * params.move_penalty);
+
+        let sizes: Vec<usize> = result.classes.iter().map(|c| c.students.len()).collect();
+        assert!(sizes.iter().max().unwrap() - sizes.iter().min().unwrap() <= 1);
+    }
+
+    #[test]
+    fn test_active_classes_leave_other_classes_frozen() {
+        let students = sample_students(80);
+        let initial: Vec<usize> = (0..80).map(|i| (i / 7) % 4).collect();
+        let params = OptimizationParams {
+            num_parallel_instances: Some(2),
+            ..Default::default()
+        };
+
+        let result = divide(
+            &students,
+            DivideConfig::new(4)
+                .with_optimization_params(params)
+                .with_seed(Some(9))
+                .with_time_budget(Some(Duration::from_millis(100)))
+                .with_initial_assignments(Some(initial.clone()))
+                .with_active_classes(Some(vec![1, 2])),
+        );
+
+        for (before, after) in initial.iter().zip(&result.assignments) {
+            if [1, 2].contains(before) {
+                assert!([1, 2].contains(after));
+            } else {
+                assert_eq!(before, after);
+            }
+        }
+    }
+
+    #[test]
+    fn test_seeding_strategies_balance_class_sizes_and_gender() {
+        let students = sample_students(103);
+        let table = student_table(&students);
+
+        for strategy in SeedingStrategy::ALL {
+            for jitter in [0.0, INITIAL_JITTER] {
+                let mut rng = StdRng::seed_from_u64(1);
+                let solution = create_initial_solution(&table, 4, strategy, &mut rng, jitter);
+
+                let sizes: Vec<usize> = solution
+                    .class_stats
+                    .iter()
+                    .map(|s| s.student_count)
+                    .collect();
+                assert_eq!(sizes.iter().sum::<usize>(), 103, "{:?}", strategy);
+                assert!(
+                    sizes.iter().max().unwrap() - sizes.iter().min().unwrap() <= 1,
+                    "{:?}",
+                    strategy
+                );
+
+                if strategy == SeedingStrategy::Snake {
+                    let males: Vec<usize> = solution
+                        .class_stats
+                        .iter()
+                        .map(|s| s.category_counts[0][0])
+                        .collect();
+                    assert!(males.iter().max().unwrap() - males.iter().min().unwrap() <= 1);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_assignment_distance_ignores_class_labels() {
+        let a = [0, 0, 1, 1, 2, 2];
+        let relabelled = [2, 2, 0, 0, 1, 1];
+        let one_swap = [2, 0, 0, 2, 1, 1];
+        assert_eq!(assignment_distance(&a, &relabelled), 0);
+        assert_eq!(assignment_distance(&a, &one_swap), 2);
+        // 班级编号不必连续，人数不同时多出的学生计为差异
+        assert_eq!(assignment_distance(&[0, 5, 5], &[7, 0, 0]), 0);
+        assert_eq!(assignment_distance(&[0, 0], &[1, 1, 2]), 1);
+        assert_eq!(assignment_distance(&[], &[]), 0);
+    }
+
+    #[test]
+    fn test_alternatives_are_diverse_and_no_better_than_best() {
+        let students = sample_students(90);
+        let params = OptimizationParams {
+            num_parallel_instances: Some(4),
+            ..Default::default()
+        };
+        let alternatives = AlternativeConfig {
+            count: 3,
+            min_distance: 8,
+        };
+        let config = DivideConfig::new(3)
+            .with_optimization_params(params)
+            .with_seed(Some(11))
+            .with_iterations(20_000)
+            .with_alternatives(Some(alternatives));
+
+        let result = divide(&students, config);
+
+        assert!(!result.alternatives.is_empty());
+        assert!(result.alternatives.len() < alternatives.count);
+        let mut all: Vec<&DivideResult> = vec![&result];
+        all.extend(&result.alternatives);
+        for (i, a) in all.iter().enumerate() {
+            for b in &all[i + 1..] {
+                assert!(
+                    assignment_distance(&a.assignments, &b.assignments)
+                        >= alternatives.min_distance
+                );
+                assert!(b.cost.total() >= a.cost.total() - 1e-6 * a.cost.total().abs());
+            }
+        }
+    }
+
+    #[test]
+    fn test_pareto_front_is_mutually_non_dominated() {
+        let students = sample_students(90);
+        let params = OptimizationParams {
+            num_parallel_instances: Some(4),
+            ..Default::default()
+        };
+        let config = DivideConfig::new(3)
+            .with_optimization_params(params)
+            .with_seed(Some(5))
+            .with_iterations(20_000)
+            .with_pareto(true);
+
+        let result = divide(&students, config);
+
+        assert!(!result.pareto_front.is_empty());
+        assert!(result.pareto_front.len() <= PARETO_CAPACITY);
+        let objectives: Vec<[f64; 3]> = result
+            .pareto_front
+            .iter()
+            .map(|r| {
+                let v = &r.validation;
+                let subject = v
+                    .subject_max_diffs
+                    .iter()
+                    .map(|(_, d)| *d)
+                    .fold(0.0, f64::max);
+                [v.max_score_diff, v.max_gender_ratio_diff, subject]
+            })
+            .collect();
+        for (i, a) in objectives.iter().enumerate() {
+            for (j, b) in objectives.iter().enumerate() {
+                if i != j {
+                    assert!(!ParetoArchive::dominates(a, b), "{:?} 支配 {:?}", a, b);
+                }
+            }
+        }
+        assert!(objectives.windows(2).all(|w| w[0][0] <= w[1][0]));
     }
 }
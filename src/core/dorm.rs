@@ -0,0 +1,592 @@
+use super::model::{Class, Gender, GenderAliases};
+use std::collections::BTreeSet;
+
+/// 宿舍清单中的一个房间
+#[derive(Debug, Clone, PartialEq)]
+pub struct DormRoom {
+    /// 楼栋（如“1号楼”）
+    pub building: String,
+    /// 楼层（如“3”）
+    pub floor: String,
+    /// 房间号（如“305”）
+    pub number: String,
+    /// 床位数
+    pub capacity: usize,
+    /// 限定入住的性别，None 表示男女均可（分配后只住一种性别）
+    pub gender: Option<Gender>,
+}
+
+impl DormRoom {
+    pub fn new(number: impl Into<String>, capacity: usize) -> Self {
+        Self {
+            building: String::new(),
+            floor: String::new(),
+            number: number.into(),
+            capacity,
+            gender: None,
+        }
+    }
+
+    pub fn with_building(mut self, building: impl Into<String>) -> Self {
+        self.building = building.into();
+        self
+    }
+
+    pub fn with_floor(mut self, floor: impl Into<String>) -> Self {
+        self.floor = floor.into();
+        self
+    }
+
+    pub fn with_gender(mut self, gender: Option<Gender>) -> Self {
+        self.gender = gender;
+        self
+    }
+
+    /// 房间名称，如“1号楼 3层 305”
+    pub fn label(&self) -> String {
+        let mut parts = Vec::with_capacity(3);
+        if !self.building.is_empty() {
+            parts.push(self.building.clone());
+        }
+        if !self.floor.is_empty() {
+            if self.floor.ends_with(['层', '楼']) {
+                parts.push(self.floor.clone());
+            } else {
+                parts.push(format!("{}层", self.floor));
+            }
+        }
+        parts.push(self.number.clone());
+        parts.join(" ")
+    }
+
+    /// 所在的楼栋与楼层
+    fn location(&self) -> Location<'_> {
+        (&self.building, &self.floor)
+    }
+
+    /// 与某个位置的远近：同楼层为 0，同楼栋不同楼层为 1，不同楼栋为 2
+    fn distance(&self, home: Option<Location>) -> u8 {
+        match home {
+            Some((building, floor)) if self.building == building => u8::from(self.floor != floor),
+            Some(_) => 2,
+            None => 0,
+        }
+    }
+}
+
+/// 楼栋与楼层
+type Location<'a> = (&'a str, &'a str);
+
+/// 表头关键字：楼栋、楼层、房间号、床位数、性别
+const BUILDING_HEADERS: [&str; 3] = ["楼栋", "宿舍楼", "楼号"];
+const FLOOR_HEADERS: [&str; 1] = ["楼层"];
+const NUMBER_HEADERS: [&str; 3] = ["房间", "宿舍号", "寝室"];
+const CAPACITY_HEADERS: [&str; 4] = ["床位", "容量", "人数", "可住"];
+const GENDER_HEADERS: [&str; 1] = ["性别"];
+
+/// 解析宿舍清单：按表头识别楼栋、楼层、房间号、床位数与性别列，
+/// 其中房间号与床位数为必需列；空行与床位数为 0 的房间忽略
+pub fn parse_room_inventory(
+    headers: &[String],
+    rows: &[Vec<String>],
+) -> anyhow::Result<Vec<DormRoom>> {
+    let find = |keywords: &[&str]| {
+        headers
+            .iter()
+            .position(|header| keywords.iter().any(|keyword| header.contains(keyword)))
+    };
+    let Some(number_col) = find(&NUMBER_HEADERS) else {
+        anyhow::bail!("宿舍清单缺少“房间号”列");
+    };
+    let Some(capacity_col) = find(&CAPACITY_HEADERS) else {
+        anyhow::bail!("宿舍清单缺少“床位数”列");
+    };
+    let building_col = find(&BUILDING_HEADERS);
+    let floor_col = find(&FLOOR_HEADERS);
+    let gender_col = find(&GENDER_HEADERS);
+    let aliases = GenderAliases::default();
+
+    let mut rooms = Vec::new();
+    for (idx, row) in rows.iter().enumerate() {
+        let cell = |col: Option<usize>| {
+            col.and_then(|col| row.get(col))
+                .map(|value| value.trim().to_string())
+                .unwrap_or_default()
+        };
+        let number = cell(Some(number_col));
+        if number.is_empty() {
+            continue;
+        }
+        let capacity_text = cell(Some(capacity_col));
+        let capacity = capacity_text
+            .parse::<f64>()
+            .ok()
+            .filter(|value| *value >= 0.0 && value.fract() == 0.0)
+            .map(|value| value as usize)
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "第 {} 行房间 {} 的床位数“{}”无效",
+                    idx + 2,
+                    number,
+                    capacity_text
+                )
+            })?;
+        if capacity == 0 {
+            continue;
+        }
+        let gender = match aliases.parse(&cell(gender_col)) {
+            Gender::Unknown => None,
+            gender => Some(gender),
+        };
+        rooms.push(
+            DormRoom::new(number, capacity)
+                .with_building(cell(building_col))
+                .with_floor(cell(floor_col))
+                .with_gender(gender),
+        );
+    }
+    if rooms.is_empty() {
+        anyhow::bail!("宿舍清单中没有可用的房间");
+    }
+    Ok(rooms)
+}
+
+/// 入住的学生：`class` 为班级在分班结果中的索引，`student` 为学生在该班中的索引
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Resident {
+    pub class: usize,
+    pub student: usize,
+}
+
+/// 一个房间的分配结果：`residents[i]` 住 i + 1 号床
+#[derive(Debug, Clone)]
+pub struct DormAssignment {
+    pub room: DormRoom,
+    /// 实际入住的性别
+    pub gender: Gender,
+    pub residents: Vec<Resident>,
+}
+
+impl DormAssignment {
+    /// 房间内的班级（分班结果中的索引）
+    pub fn classes(&self) -> BTreeSet<usize> {
+        self.residents.iter().map(|r| r.class).collect()
+    }
+}
+
+/// 宿舍分配结果
+#[derive(Debug, Clone)]
+pub struct DormPlan {
+    /// 已入住的房间，按宿舍清单顺序
+    pub rooms: Vec<DormAssignment>,
+    /// 因床位不足或性别未知而未能分配的学生
+    pub unassigned: Vec<Resident>,
+}
+
+impl DormPlan {
+    /// 已分配的学生人数
+    pub fn assigned_count(&self) -> usize {
+        self.rooms.iter().map(|room| room.residents.len()).sum()
+    }
+
+    /// 住有不止一个班级学生的房间数
+    pub fn mixed_rooms(&self) -> usize {
+        self.rooms
+            .iter()
+            .filter(|room| room.classes().len() > 1)
+            .count()
+    }
+}
+
+/// 分配中的房间：在宿舍清单中的索引与已入住的学生
+struct OpenRoom {
+    index: usize,
+    residents: Vec<Resident>,
+}
+
+/// 为两种性别划分未限定性别的房间，返回每个房间分给的性别（限定性别的房间保持不变）
+///
+/// 各性别先补足限定房间之外缺少的床位，多出的床位按人数比例分配；床位不够时按缺口比例分配。
+/// 男生取清单中前若干间或后若干间，其余归女生，使同一楼栋、楼层的房间尽量只住一种性别。
+fn reserve_shared_rooms(rooms: &[DormRoom], demand: [usize; 2]) -> Vec<Option<Gender>> {
+    let restricted = [Gender::Male, Gender::Female].map(|gender| {
+        rooms
+            .iter()
+            .filter(|room| room.gender == Some(gender))
+            .map(|room| room.capacity)
+            .sum::<usize>()
+    });
+    let need = [0, 1].map(|g| demand[g].saturating_sub(restricted[g]));
+    let shared: Vec<usize> = (0..rooms.len())
+        .filter(|&idx| rooms[idx].gender.is_none())
+        .collect();
+    let mut prefix = vec![0];
+    for &idx in &shared {
+        prefix.push(prefix[prefix.len() - 1] + rooms[idx].capacity);
+    }
+    let total = prefix[shared.len()];
+
+    // 男生的目标床位数
+    let target = if need[0] + need[1] > total {
+        total as f64 * need[0] as f64 / (need[0] + need[1]) as f64
+    } else {
+        let share = if demand[0] + demand[1] == 0 {
+            0.5
+        } else {
+            demand[0] as f64 / (demand[0] + demand[1]) as f64
+        };
+        need[0] as f64 + (total - need[0] - need[1]) as f64 * share
+    };
+
+    // 选两种性别合计缺口最小、男生床位最接近目标的划分
+    let mut best = (usize::MAX, f64::INFINITY, true, 0);
+    for males_first in [true, false] {
+        for count in 0..=shared.len() {
+            let male = if males_first {
+                prefix[count]
+            } else {
+                total - prefix[shared.len() - count]
+            };
+            let shortfall = need[0].saturating_sub(male) + need[1].saturating_sub(total - male);
+            let gap = (male as f64 - target).abs();
+            if shortfall < best.0 || (shortfall == best.0 && gap < best.1) {
+                best = (shortfall, gap, males_first, count);
+            }
+        }
+    }
+
+    let (_, _, males_first, count) = best;
+    let mut genders: Vec<Option<Gender>> = rooms.iter().map(|room| room.gender).collect();
+    for (pos, &idx) in shared.iter().enumerate() {
+        let male = if males_first {
+            pos < count
+        } else {
+            pos >= shared.len() - count
+        };
+        genders[idx] = Some(if male { Gender::Male } else { Gender::Female });
+    }
+    genders
+}
+
+/// 一个班的主位置：可用床位能容纳全班的第一个楼层，没有时取可用床位最多的楼层
+fn home_location<'a>(
+    rooms: &'a [DormRoom],
+    available: &[usize],
+    size: usize,
+) -> Option<Location<'a>> {
+    let mut locations: Vec<(Location, usize)> = Vec::new();
+    for &idx in available {
+        let location = rooms[idx].location();
+        match locations.iter_mut().find(|(other, _)| *other == location) {
+            Some((_, capacity)) => *capacity += rooms[idx].capacity,
+            None => locations.push((location, rooms[idx].capacity)),
+        }
+    }
+    locations
+        .iter()
+        .find(|(_, capacity)| *capacity >= size)
+        .or_else(|| locations.iter().rev().max_by_key(|(_, capacity)| *capacity))
+        .map(|(location, _)| *location)
+}
+
+/// 安置各班整间入住后剩下的零散学生，返回没有床位的学生
+///
+/// 人数多的先放，优先放进离本班主位置近、能整组容纳且剩余床位最少的已入住房间，
+/// 其次新开一间能整组容纳的最小房间，都不行时拆开放进剩余床位最多的房间。
+fn place_leftovers(
+    rooms: &[DormRoom],
+    available: &mut Vec<usize>,
+    opened: &mut Vec<OpenRoom>,
+    mut leftovers: Vec<(Option<Location>, Vec<Resident>)>,
+) -> Vec<Resident> {
+    leftovers.sort_by_key(|(_, group)| std::cmp::Reverse(group.len()));
+    let mut unassigned = Vec::new();
+    for (home, mut group) in leftovers {
+        while !group.is_empty() {
+            let free = |room: &OpenRoom| rooms[room.index].capacity - room.residents.len();
+            let distance = |idx: usize| rooms[idx].distance(home);
+            let best_opened = opened
+                .iter()
+                .enumerate()
+                .filter(|(_, room)| free(room) >= group.len())
+                .min_by_key(|(_, room)| (distance(room.index), free(room)))
+                .map(|(pos, _)| pos);
+            let target = match best_opened {
+                Some(pos) => pos,
+                None => {
+                    let pick = available
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, idx)| rooms[**idx].capacity >= group.len())
+                        .min_by_key(|(_, idx)| (distance(**idx), rooms[**idx].capacity))
+                        .or_else(|| {
+                            available.iter().enumerate().min_by_key(|(_, idx)| {
+                                (distance(**idx), std::cmp::Reverse(rooms[**idx].capacity))
+                            })
+                        })
+                        .map(|(pos, _)| pos);
+                    match pick {
+                        Some(pos) => {
+                            opened.push(OpenRoom {
+                                index: available.remove(pos),
+                                residents: Vec::new(),
+                            });
+                            opened.len() - 1
+                        }
+                        None => match opened
+                            .iter()
+                            .enumerate()
+                            .filter(|(_, room)| free(room) > 0)
+                            .min_by_key(|(_, room)| {
+                                (distance(room.index), std::cmp::Reverse(free(room)))
+                            }) {
+                            Some((pos, _)) => pos,
+                            None => {
+                                unassigned.append(&mut group);
+                                break;
+                            }
+                        },
+                    }
+                }
+            };
+            let room = &mut opened[target];
+            let take = (rooms[room.index].capacity - room.residents.len()).min(group.len());
+            room.residents.extend(group.drain(..take));
+        }
+    }
+    unassigned
+}
+
+/// 按性别分配宿舍，尽量让每个房间只住一个班、每个班住在同一楼栋的同一楼层
+///
+/// 未限定性别的房间先按两种性别在限定房间之外的床位缺口与人数划分，男生、女生再分别处理。每个班选定一个主位置（能容纳全班的第一个楼层），从近到远整间入住，
+/// 剩余的零散学生放进离主位置近、剩余床位最合适的房间（放不下时拆开），使混住的班级尽量少。
+/// 划给另一性别但没有用上的房间最后留给床位仍不足的性别。
+pub fn allocate_dorms(classes: &[Class], rooms: &[DormRoom]) -> anyhow::Result<DormPlan> {
+    if rooms.is_empty() {
+        anyhow::bail!("宿舍清单中没有可用的房间");
+    }
+
+    let genders = [Gender::Male, Gender::Female];
+    // 各班该性别的学生
+    let residents_of = |gender: Gender| -> Vec<Vec<Resident>> {
+        classes
+            .iter()
+            .enumerate()
+            .map(|(class, c)| {
+                c.students
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, s)| s.gender == gender)
+                    .map(|(student, _)| Resident { class, student })
+                    .collect()
+            })
+            .collect()
+    };
+    let demand = genders.map(|gender| residents_of(gender).iter().map(Vec::len).sum());
+    let reserved = reserve_shared_rooms(rooms, demand);
+
+    let mut opened: [Vec<OpenRoom>; 2] = Default::default();
+    let mut short: [Vec<Resident>; 2] = Default::default();
+    for (g, gender) in genders.into_iter().enumerate() {
+        let mut available: Vec<usize> = (0..rooms.len())
+            .filter(|&idx| reserved[idx] == Some(gender))
+            .collect();
+
+        let mut leftovers = Vec::new();
+        for mut group in residents_of(gender) {
+            if group.is_empty() {
+                continue;
+            }
+            // 整间入住：离主位置由近到远取出床位数不超过剩余人数的房间
+            let home = home_location(rooms, &available, group.len());
+            while let Some(pos) = (0..available.len())
+                .filter(|&pos| rooms[available[pos]].capacity <= group.len())
+                .min_by_key(|&pos| rooms[available[pos]].distance(home))
+            {
+                let index = available.remove(pos);
+                let rest = group.split_off(rooms[index].capacity);
+                opened[g].push(OpenRoom {
+                    index,
+                    residents: std::mem::replace(&mut group, rest),
+                });
+            }
+            if !group.is_empty() {
+                leftovers.push((home, group));
+            }
+        }
+        short[g] = place_leftovers(rooms, &mut available, &mut opened[g], leftovers);
+    }
+
+    // 床位不足的性别再使用划给另一性别但没有用上的房间
+    let used: BTreeSet<usize> = opened.iter().flatten().map(|room| room.index).collect();
+    let mut spare: Vec<usize> = (0..rooms.len())
+        .filter(|&idx| rooms[idx].gender.is_none() && !used.contains(&idx))
+        .collect();
+    let mut unassigned = Vec::new();
+    for g in 0..genders.len() {
+        let waiting = std::mem::take(&mut short[g]);
+        let leftovers = waiting
+            .chunk_by(|a, b| a.class == b.class)
+            .map(|group| (None, group.to_vec()))
+            .collect();
+        unassigned.extend(place_leftovers(
+            rooms,
+            &mut spare,
+            &mut opened[g],
+            leftovers,
+        ));
+    }
+
+    // 性别未知的学生无法确定宿舍
+    for (class, c) in classes.iter().enumerate() {
+        for (student, s) in c.students.iter().enumerate() {
+            if s.gender == Gender::Unknown {
+                unassigned.push(Resident { class, student });
+            }
+        }
+    }
+
+    let mut assignments: Vec<Option<DormAssignment>> = vec![None; rooms.len()];
+    for (gender, rooms_of_gender) in genders.into_iter().zip(opened) {
+        for room in rooms_of_gender {
+            assignments[room.index] = Some(DormAssignment {
+                room: rooms[room.index].clone(),
+                gender,
+                residents: room.residents,
+            });
+        }
+    }
+    let mut rooms: Vec<DormAssignment> = assignments.into_iter().flatten().collect();
+    for room in &mut rooms {
+        room.residents.sort_by_key(|r| (r.class, r.student));
+    }
+    Ok(DormPlan { rooms, unassigned })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::algorithm::tests::sample_class;
+
+    /// 前 `males` 名为男生、其余为女生的班级
+    fn class_with(id: usize, males: usize, females: usize) -> Class {
+        let mut class = sample_class(id, males + females);
+        for (i, student) in class.students.iter_mut().enumerate() {
+            student.gender = if i < males {
+                Gender::Male
+            } else {
+                Gender::Female
+            };
+        }
+        class
+    }
+
+    #[test]
+    fn test_parse_room_inventory() {
+        let headers = ["楼栋", "楼层", "房间号", "床位数", "性别"]
+            .map(String::from)
+            .to_vec();
+        let rows = vec![
+            vec!["1号楼", "3", "305", "6", "男"],
+            vec!["1号楼", "3", "306", "0", "男"],
+            vec!["2号楼", "1", "101", "4", ""],
+            vec!["", "", "", "", ""],
+        ]
+        .into_iter()
+        .map(|row| row.into_iter().map(String::from).collect())
+        .collect::<Vec<Vec<String>>>();
+
+        let rooms = parse_room_inventory(&headers, &rows).unwrap();
+        assert_eq!(rooms.len(), 2);
+        assert_eq!(rooms[0].label(), "1号楼 3层 305");
+        assert_eq!(rooms[0].gender, Some(Gender::Male));
+        assert_eq!(rooms[1].gender, None);
+
+        let bad = vec![
+            vec!["1号楼", "3", "307", "六", "男"]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+        ];
+        assert!(parse_room_inventory(&headers, &bad).is_err());
+        assert!(parse_room_inventory(&headers[..2], &rows).is_err());
+    }
+
+    #[test]
+    fn test_allocate_dorms_keeps_classmates_together() {
+        let classes = vec![
+            class_with(0, 8, 5),
+            class_with(1, 9, 4),
+            class_with(2, 3, 0),
+        ];
+        let mut rooms: Vec<DormRoom> = (0..4)
+            .map(|i| DormRoom::new(format!("男{}", i), 4).with_gender(Some(Gender::Male)))
+            .collect();
+        rooms.extend(
+            (0..3).map(|i| DormRoom::new(format!("女{}", i), 4).with_gender(Some(Gender::Female))),
+        );
+        rooms.extend((0..3).map(|i| DormRoom::new(format!("通用{}", i), 6)));
+
+        let plan = allocate_dorms(&classes, &rooms).unwrap();
+        assert!(plan.unassigned.is_empty());
+        assert_eq!(plan.assigned_count(), 29);
+        for room in &plan.rooms {
+            assert!(room.residents.len() <= room.room.capacity);
+            assert!(room.room.gender.is_none_or(|g| g == room.gender));
+            for r in &room.residents {
+                assert_eq!(classes[r.class].students[r.student].gender, room.gender);
+            }
+        }
+        // 男生：1、2 班各整住两间，2 班剩下的 1 人与 3 班 3 人合住；女生各班整间入住后剩 1 人单住
+        assert_eq!(plan.mixed_rooms(), 1);
+
+        let mut crowded = classes.clone();
+        crowded.push(class_with(3, 10, 0));
+        let plan = allocate_dorms(&crowded, &rooms[..7]).unwrap();
+        assert_eq!(plan.unassigned.len(), 30 - 16);
+    }
+
+    #[test]
+    fn test_shared_rooms_are_reserved_for_both_genders() {
+        // 男生先处理也不会占用女生需要的房间
+        let classes = vec![class_with(0, 8, 4)];
+        let rooms = vec![DormRoom::new("101", 4), DormRoom::new("102", 8)];
+
+        let plan = allocate_dorms(&classes, &rooms).unwrap();
+        assert!(plan.unassigned.is_empty());
+        assert_eq!(plan.rooms[0].gender, Gender::Female);
+        assert_eq!(plan.rooms[1].gender, Gender::Male);
+    }
+
+    #[test]
+    fn test_classes_stay_on_one_floor() {
+        let classes = vec![class_with(0, 8, 0), class_with(1, 6, 0)];
+        let room = |building: &str, floor: &str, number: &str| {
+            DormRoom::new(number, 4)
+                .with_building(building)
+                .with_floor(floor)
+                .with_gender(Some(Gender::Male))
+        };
+        // 清单不按楼层排序：按顺序整间入住会把 1 班拆到两栋楼
+        let rooms = vec![
+            room("1号楼", "1", "101"),
+            room("2号楼", "1", "201"),
+            room("2号楼", "1", "202"),
+            room("1号楼", "1", "102"),
+            room("2号楼", "2", "203"),
+        ];
+
+        let plan = allocate_dorms(&classes, &rooms).unwrap();
+        assert!(plan.unassigned.is_empty());
+        for class in 0..classes.len() {
+            let locations: BTreeSet<(&str, &str)> = plan
+                .rooms
+                .iter()
+                .filter(|room| room.classes().contains(&class))
+                .map(|room| room.room.location())
+                .collect();
+            assert_eq!(locations.len(), 1);
+        }
+    }
+}
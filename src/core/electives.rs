@@ -0,0 +1,381 @@
+use super::algorithm::{DivideConfig, DivideResult};
+use super::model::{Class, Student};
+use super::tiers::divide_groups;
+use std::collections::HashMap;
+use tokio_util::sync::CancellationToken;
+
+/// 可以同班的一组选科组合及分给它的班级
+#[derive(Debug, Clone)]
+pub struct CombinationGroup {
+    /// 组内的选科组合，按人数从多到少排列
+    pub combinations: Vec<String>,
+    /// 组内学生在输入中的索引
+    pub student_indices: Vec<usize>,
+    pub num_classes: usize,
+}
+
+impl CombinationGroup {
+    /// 组合的显示文本，如“物化生/物化地”
+    pub fn label(&self) -> String {
+        self.combinations.join("/")
+    }
+}
+
+/// 按选科组合组班的结果
+#[derive(Debug, Clone)]
+pub struct CombinationResult {
+    /// 各组合组的划分、第一个班的全局索引及组内分班结果
+    pub groups: Vec<(CombinationGroup, usize, DivideResult)>,
+    /// 所有组的班级，编号跨组连续，并带有本班的选科组合
+    pub classes: Vec<Class>,
+    /// 每个学生所在的全局班级索引（与输入学生顺序一致）
+    pub assignments: Vec<usize>,
+}
+
+impl CombinationResult {
+    /// 是否有组在取消时只得到部分结果
+    pub fn is_partial(&self) -> bool {
+        self.groups.iter().any(|(_, _, result)| result.is_partial())
+    }
+
+    /// 用于界面摘要与导出的运行信息
+    pub fn summary_items(&self) -> Vec<(String, String)> {
+        let mut items = vec![
+            ("学生总数".to_string(), self.assignments.len().to_string()),
+            ("班级数量".to_string(), self.classes.len().to_string()),
+        ];
+        for (group, first_class, result) in &self.groups {
+            items.push((
+                group.label(),
+                format!(
+                    "{}人，{}个班（{}~{}班），总分最大差值 {:.2}分",
+                    group.student_indices.len(),
+                    group.num_classes,
+                    first_class + 1,
+                    first_class + group.num_classes,
+                    result.validation.max_score_diff
+                ),
+            ));
+        }
+        if let Some((_, _, first)) = self.groups.first() {
+            items.push(("随机种子".to_string(), first.seed.to_string()));
+        }
+        items
+    }
+}
+
+/// 解析兼容组合设置：每行一组可以同班的组合，组合之间用空白、逗号或顿号分隔
+pub fn parse_compatibility(text: &str) -> Vec<Vec<String>> {
+    text.lines()
+        .map(|line| {
+            line.split(|c: char| c.is_whitespace() || ",，、;；".contains(c))
+                .map(super::io::normalize_combination)
+                .filter(|code| !code.is_empty())
+                .collect::<Vec<_>>()
+        })
+        .filter(|group| !group.is_empty())
+        .collect()
+}
+
+/// 兼容关系中从 `from` 到 `to` 的最短串联路径（含两端）
+fn compatibility_chain(neighbours: &[Vec<usize>], from: usize, to: usize) -> Vec<usize> {
+    let mut previous = vec![None; neighbours.len()];
+    let mut queue = std::collections::VecDeque::from([from]);
+    previous[from] = Some(from);
+    while let Some(current) = queue.pop_front() {
+        if current == to {
+            break;
+        }
+        for &next in &neighbours[current] {
+            if previous[next].is_none() {
+                previous[next] = Some(current);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    let mut chain = vec![to];
+    let mut current = to;
+    while let Some(prev) = previous[current].filter(|&prev| prev != current) {
+        chain.push(prev);
+        current = prev;
+    }
+    chain.reverse();
+    chain
+}
+
+/// 按组内人数分配班级：每组至少一个班，其余班级依次分给当前平均班额最大的组
+fn allocate_classes(sizes: &[usize], num_classes: usize) -> Vec<usize> {
+    let mut classes = vec![1; sizes.len()];
+    for _ in sizes.len()..num_classes {
+        let largest = (0..sizes.len())
+            .max_by(|&a, &b| {
+                let average = |i: usize| sizes[i] as f64 / classes[i] as f64;
+                average(a).total_cmp(&average(b)).then(b.cmp(&a))
+            })
+            .expect("至少有一个组合组");
+        classes[largest] += 1;
+    }
+    classes
+}
+
+/// 把学生按兼容的选科组合分组，并按人数分配班级
+///
+/// `compatible` 中每一项是一组两两可以同班的组合，同一组合出现在多项中时这些项合并为一组，
+/// 但合并后组内的组合必须两两出现在同一项中，否则报错（不会因为 A—B、B—C 兼容就让 A 与 C 同班）；
+/// 未列出的组合单独成组。组按人数从多到少排列。
+pub fn plan_combination_groups(
+    students: &[Student],
+    compatible: &[Vec<String>],
+    num_classes: usize,
+) -> anyhow::Result<Vec<CombinationGroup>> {
+    let missing = students.iter().filter(|s| s.combination.is_none()).count();
+    if missing > 0 {
+        anyhow::bail!("有 {} 名学生没有选科组合", missing);
+    }
+
+    // 各组合的学生
+    let mut by_combination: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (idx, student) in students.iter().enumerate() {
+        if let Some(combination) = &student.combination {
+            by_combination.entry(combination).or_default().push(idx);
+        }
+    }
+
+    // 并查集合并兼容的组合
+    let mut codes: Vec<&str> = by_combination.keys().copied().collect();
+    codes.sort_unstable();
+    let mut parent: Vec<usize> = (0..codes.len()).collect();
+    fn find(parent: &mut [usize], i: usize) -> usize {
+        if parent[i] != i {
+            parent[i] = find(parent, parent[i]);
+        }
+        parent[i]
+    }
+    // 同一项内的组合两两兼容
+    let mut neighbours: Vec<Vec<usize>> = vec![Vec::new(); codes.len()];
+    for line in compatible {
+        let members: Vec<usize> = line
+            .iter()
+            .filter_map(|code| codes.iter().position(|c| c == code))
+            .collect();
+        for pair in members.windows(2) {
+            let (a, b) = (find(&mut parent, pair[0]), find(&mut parent, pair[1]));
+            parent[a.max(b)] = a.min(b);
+        }
+        for &a in &members {
+            for &b in &members {
+                if a != b && !neighbours[a].contains(&b) {
+                    neighbours[a].push(b);
+                }
+            }
+        }
+    }
+
+    // 合并后的组必须两两兼容，否则说明是经由其他组合串联起来的
+    for a in 0..codes.len() {
+        for b in a + 1..codes.len() {
+            if find(&mut parent, a) == find(&mut parent, b) && !neighbours[a].contains(&b) {
+                let chain: Vec<&str> = compatibility_chain(&neighbours, a, b)
+                    .into_iter()
+                    .map(|i| codes[i])
+                    .collect();
+                anyhow::bail!(
+                    "选科组合“{}”与“{}”未声明兼容，却经由 {} 串联到同一组；\
+                     请把可以同班的组合写在同一行",
+                    codes[a],
+                    codes[b],
+                    chain.join("—")
+                );
+            }
+        }
+    }
+
+    let mut grouped: Vec<Vec<&str>> = Vec::new();
+    let mut roots: Vec<usize> = Vec::new();
+    for (i, &code) in codes.iter().enumerate() {
+        let root = find(&mut parent, i);
+        match roots.iter().position(|&r| r == root) {
+            Some(pos) => grouped[pos].push(code),
+            None => {
+                roots.push(root);
+                grouped.push(vec![code]);
+            }
+        }
+    }
+
+    let mut groups: Vec<CombinationGroup> = grouped
+        .into_iter()
+        .map(|mut combinations| {
+            combinations.sort_by_key(|code| std::cmp::Reverse(by_combination[code].len()));
+            let mut student_indices: Vec<usize> = combinations
+                .iter()
+                .flat_map(|code| by_combination[code].iter().copied())
+                .collect();
+            student_indices.sort_unstable();
+            CombinationGroup {
+                combinations: combinations.into_iter().map(String::from).collect(),
+                student_indices,
+                num_classes: 0,
+            }
+        })
+        .collect();
+    groups.sort_by_key(|group| std::cmp::Reverse(group.student_indices.len()));
+
+    if num_classes < groups.len() {
+        anyhow::bail!(
+            "共有 {} 个不能同班的选科组合组，班级数至少为 {}",
+            groups.len(),
+            groups.len()
+        );
+    }
+    if num_classes > students.len() {
+        anyhow::bail!("班级数不能超过学生数");
+    }
+
+    let sizes: Vec<usize> = groups.iter().map(|g| g.student_indices.len()).collect();
+    for (group, classes) in groups.iter_mut().zip(allocate_classes(&sizes, num_classes)) {
+        group.num_classes = classes;
+    }
+    Ok(groups)
+}
+
+/// 按选科组合组班
+///
+/// 先按 [`plan_combination_groups`] 分组并分配 `config.num_classes` 个班，再在每组内部独立均衡。
+/// 班级编号跨组连续，每个班级标注本班实际包含的选科组合。
+pub fn divide_by_combination(
+    students: &[Student],
+    compatible: &[Vec<String>],
+    config: &DivideConfig,
+    cancel_token: CancellationToken,
+) -> anyhow::Result<CombinationResult> {
+    let groups = plan_combination_groups(students, compatible, config.num_classes)?;
+    let indices: Vec<Vec<usize>> = groups.iter().map(|g| g.student_indices.clone()).collect();
+    let configs = groups.iter().map(|group| DivideConfig {
+        num_classes: group.num_classes,
+        ..config.clone()
+    });
+    let (results, mut classes, assignments) =
+        divide_groups(students, &indices, configs, cancel_token);
+
+    // 本班包含的组合，按人数从多到少
+    for class in &mut classes {
+        let mut counts: Vec<(&str, usize)> = Vec::new();
+        for student in &class.students {
+            let code = student.combination.as_deref().unwrap_or_default();
+            match counts.iter_mut().find(|(c, _)| *c == code) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((code, 1)),
+            }
+        }
+        counts.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+        let label = counts
+            .iter()
+            .map(|(code, _)| *code)
+            .collect::<Vec<_>>()
+            .join("/");
+        class.combination = Some(label);
+    }
+
+    Ok(CombinationResult {
+        groups: groups
+            .into_iter()
+            .zip(results)
+            .map(|(group, (first_class, result))| (group, first_class, result))
+            .collect(),
+        classes,
+        assignments,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::algorithm::{OptimizationParams, tests::sample_students};
+
+    /// 依次为各选科组合生成指定人数的学生
+    fn students_with(combinations: &[(&str, usize)]) -> Vec<Student> {
+        let codes: Vec<&str> = combinations
+            .iter()
+            .flat_map(|&(code, count)| std::iter::repeat_n(code, count))
+            .collect();
+        sample_students(codes.len())
+            .into_iter()
+            .zip(codes)
+            .map(|(student, code)| student.with_combination(Some(code.to_string())))
+            .collect()
+    }
+
+    #[test]
+    fn test_compatible_combinations_share_classes_allocated_by_size() {
+        let students = students_with(&[("物化生", 90), ("物化地", 30), ("历政地", 40)]);
+        let compatible = parse_compatibility("物化生，物化地\n");
+
+        let groups = plan_combination_groups(&students, &compatible, 4).unwrap();
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].label(), "物化生/物化地");
+        assert_eq!(groups[0].num_classes, 3);
+        assert_eq!(groups[1].label(), "历政地");
+        assert_eq!(groups[1].num_classes, 1);
+    }
+
+    #[test]
+    fn test_classes_only_contain_compatible_combinations() {
+        let students = students_with(&[("物化生", 50), ("历政地", 20), ("历政生", 20)]);
+        let compatible = parse_compatibility("历政地 历政生");
+        let config = DivideConfig::new(4)
+            .with_optimization_params(OptimizationParams {
+                num_parallel_instances: Some(1),
+                ..Default::default()
+            })
+            .with_seed(Some(1))
+            .with_iterations(5_000);
+
+        let result =
+            divide_by_combination(&students, &compatible, &config, CancellationToken::new())
+                .unwrap();
+
+        assert_eq!(result.classes.len(), 4);
+        for class in &result.classes {
+            let label = class.combination.as_deref().unwrap();
+            let has_science = class
+                .students
+                .iter()
+                .any(|s| s.combination.as_deref() == Some("物化生"));
+            let has_history = class
+                .students
+                .iter()
+                .any(|s| s.combination.as_deref() != Some("物化生"));
+            assert!(!(has_science && has_history), "不兼容的组合同班: {}", label);
+        }
+        assert_eq!(result.classes[0].combination.as_deref(), Some("物化生"));
+    }
+
+    #[test]
+    fn test_chained_compatibility_is_rejected() {
+        let students = students_with(&[("物化生", 30), ("物化地", 30), ("历政地", 30)]);
+        let compatible = parse_compatibility("物化生 物化地\n物化地 历政地");
+
+        let error = plan_combination_groups(&students, &compatible, 3)
+            .unwrap_err()
+            .to_string();
+        assert!(
+            error.contains("物化生—物化地—历政地") || error.contains("历政地—物化地—物化生"),
+            "{}",
+            error
+        );
+
+        // 写在同一行则三者两两兼容
+        let compatible = parse_compatibility("物化生 物化地 历政地");
+        let groups = plan_combination_groups(&students, &compatible, 3).unwrap();
+        assert_eq!(groups.len(), 1);
+    }
+
+    #[test]
+    fn test_too_few_classes_for_groups_is_rejected() {
+        let students = students_with(&[("物化生", 10), ("历政地", 10), ("物化地", 10)]);
+        assert!(plan_combination_groups(&students, &[], 2).is_err());
+    }
+}
@@ -0,0 +1,338 @@
+use super::model::Class;
+use rand::{SeedableRng, rngs::StdRng, seq::SliceRandom};
+
+/// 默认每个考场的人数
+pub const DEFAULT_EXAM_ROOM_CAPACITY: usize = 30;
+/// 默认每个考场的列数
+pub const DEFAULT_EXAM_ROOM_COLUMNS: usize = 5;
+
+/// 考生进入考场的顺序
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExamOrder {
+    /// 按总分排名：排名决定考生进入哪个考场（第 1 考场为总分最高的考生），
+    /// 考场内的座位号为避开同班相邻会重新调整，不按排名
+    #[default]
+    Ranking,
+    /// 全年级随机打乱
+    Shuffle,
+}
+
+impl ExamOrder {
+    pub const ALL: [ExamOrder; 2] = [ExamOrder::Ranking, ExamOrder::Shuffle];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ExamOrder::Ranking => "按成绩排名",
+            ExamOrder::Shuffle => "随机打乱",
+        }
+    }
+}
+
+/// 考场安排的配置
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExamConfig {
+    /// 未单独指定容量的考场的人数
+    pub room_capacity: usize,
+    /// 前几个考场各自的容量（如大教室），其余考场按 `room_capacity` 开设
+    pub capacities: Vec<usize>,
+    /// 每个考场的列数，座位号按列从前往后编排
+    pub columns: usize,
+    pub order: ExamOrder,
+    /// 随机打乱时使用的随机种子
+    pub seed: u64,
+}
+
+impl Default for ExamConfig {
+    fn default() -> Self {
+        Self::new(DEFAULT_EXAM_ROOM_CAPACITY)
+    }
+}
+
+impl ExamConfig {
+    pub fn new(room_capacity: usize) -> Self {
+        Self {
+            room_capacity,
+            capacities: Vec::new(),
+            columns: DEFAULT_EXAM_ROOM_COLUMNS,
+            order: ExamOrder::default(),
+            seed: 0,
+        }
+    }
+
+    pub fn with_capacities(mut self, capacities: Vec<usize>) -> Self {
+        self.capacities = capacities;
+        self
+    }
+
+    pub fn with_columns(mut self, columns: usize) -> Self {
+        self.columns = columns;
+        self
+    }
+
+    pub fn with_order(mut self, order: ExamOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// 容纳 `total` 名考生所需的各考场容量：先用指定容量的考场，不够时按默认人数补开
+    fn room_capacities(&self, total: usize) -> anyhow::Result<Vec<usize>> {
+        if self.columns == 0 {
+            anyhow::bail!("考场的列数必须大于 0");
+        }
+        if self.capacities.contains(&0) {
+            anyhow::bail!("考场人数必须大于 0");
+        }
+
+        let mut capacities = Vec::new();
+        let mut remaining = total;
+        for &capacity in &self.capacities {
+            if remaining == 0 {
+                break;
+            }
+            capacities.push(capacity);
+            remaining = remaining.saturating_sub(capacity);
+        }
+        if remaining > 0 {
+            if self.room_capacity == 0 {
+                anyhow::bail!("考场人数必须大于 0");
+            }
+            capacities.extend(std::iter::repeat_n(
+                self.room_capacity,
+                remaining.div_ceil(self.room_capacity),
+            ));
+        }
+        Ok(capacities)
+    }
+}
+
+/// 考场中的一个座位：`class` 为班级在分班结果中的索引，`student` 为学生在该班中的索引
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExamSeat {
+    pub class: usize,
+    pub student: usize,
+}
+
+/// 一个考场：`seats[i]` 为座位号 i + 1 的考生
+#[derive(Debug, Clone)]
+pub struct ExamRoom {
+    /// 考场号（从 1 开始）
+    pub number: usize,
+    pub capacity: usize,
+    pub columns: usize,
+    pub seats: Vec<ExamSeat>,
+}
+
+impl ExamRoom {
+    /// 排数
+    pub fn rows(&self) -> usize {
+        self.capacity.div_ceil(self.columns)
+    }
+
+    /// 座位（从 0 开始）所在的排和列：座位号按列编排，第 1 列从前往后坐满后再到第 2 列
+    pub fn position(&self, seat: usize) -> (usize, usize) {
+        let rows = self.rows();
+        (seat % rows, seat / rows)
+    }
+
+    /// 考号：两位考场号加两位座位号，如第 3 考场 12 号为“0312”
+    pub fn exam_number(&self, seat: usize) -> String {
+        format!("{:02}{:02}", self.number, seat + 1)
+    }
+
+    /// 座位前面与左边的座位（已编排在它之前的邻座）
+    fn earlier_neighbours(&self, seat: usize) -> impl Iterator<Item = usize> {
+        let rows = self.rows();
+        let up = (!seat.is_multiple_of(rows)).then(|| seat - 1);
+        let left = seat.checked_sub(rows);
+        up.into_iter().chain(left)
+    }
+
+    /// 前后左右相邻的座位
+    fn neighbours(&self, seat: usize, occupied: usize) -> Vec<usize> {
+        let rows = self.rows();
+        let mut result: Vec<usize> = self.earlier_neighbours(seat).collect();
+        if seat % rows + 1 < rows && seat + 1 < occupied {
+            result.push(seat + 1);
+        }
+        if seat + rows < occupied {
+            result.push(seat + rows);
+        }
+        result
+    }
+
+    /// 与同班同学前后左右相邻的座位数
+    fn conflicts(&self) -> usize {
+        let occupied = self.seats.len();
+        (0..occupied)
+            .map(|seat| {
+                self.earlier_neighbours(seat)
+                    .filter(|&other| self.seats[other].class == self.seats[seat].class)
+                    .count()
+            })
+            .sum()
+    }
+}
+
+/// 考场安排结果
+#[derive(Debug, Clone)]
+pub struct ExamPlan {
+    pub rooms: Vec<ExamRoom>,
+    /// 无法避免的同班相邻对数
+    pub conflicts: usize,
+}
+
+impl ExamPlan {
+    /// 考生人数
+    pub fn student_count(&self) -> usize {
+        self.rooms.iter().map(|room| room.seats.len()).sum()
+    }
+}
+
+/// 把分班结果中的全体学生编入考场
+///
+/// 按配置的顺序依次填满各考场；考场内按座位号逐个就座，优先选择与前面、左边的邻座
+/// 不同班的考生，最后再通过交换座位消除剩余的同班相邻。
+pub fn arrange_exam_rooms(classes: &[Class], config: &ExamConfig) -> anyhow::Result<ExamPlan> {
+    let mut students: Vec<ExamSeat> = classes
+        .iter()
+        .enumerate()
+        .flat_map(|(class, c)| {
+            (0..c.students.len()).map(move |student| ExamSeat { class, student })
+        })
+        .collect();
+    if students.is_empty() {
+        anyhow::bail!("没有需要安排考场的学生");
+    }
+
+    match config.order {
+        ExamOrder::Ranking => students.sort_by(|a, b| {
+            let score = |seat: &ExamSeat| classes[seat.class].students[seat.student].total_score;
+            score(b).total_cmp(&score(a))
+        }),
+        ExamOrder::Shuffle => students.shuffle(&mut StdRng::seed_from_u64(config.seed)),
+    }
+
+    let mut rooms = Vec::new();
+    let mut rest = students.as_slice();
+    for (idx, capacity) in config.room_capacities(rest.len())?.into_iter().enumerate() {
+        let (members, remaining) = rest.split_at(capacity.min(rest.len()));
+        rest = remaining;
+        let mut room = ExamRoom {
+            number: idx + 1,
+            capacity,
+            columns: config.columns,
+            seats: Vec::with_capacity(members.len()),
+        };
+        seat_members(&mut room, members);
+        rooms.push(room);
+    }
+
+    let conflicts = rooms.iter().map(ExamRoom::conflicts).sum();
+    Ok(ExamPlan { rooms, conflicts })
+}
+
+/// 考场内就座：按顺序优先选择不与已就座邻座同班的考生，再交换座位修复剩余冲突
+fn seat_members(room: &mut ExamRoom, members: &[ExamSeat]) {
+    let mut waiting = members.to_vec();
+    for seat in 0..members.len() {
+        let pick = waiting
+            .iter()
+            .position(|candidate| {
+                room.earlier_neighbours(seat)
+                    .all(|other| room.seats[other].class != candidate.class)
+            })
+            .unwrap_or(0);
+        room.seats.push(waiting.remove(pick));
+    }
+
+    let occupied = room.seats.len();
+    let seat_conflicts = |room: &ExamRoom, seat: usize| {
+        room.neighbours(seat, occupied)
+            .into_iter()
+            .filter(|&other| room.seats[other].class == room.seats[seat].class)
+            .count()
+    };
+    loop {
+        let mut improved = false;
+        for a in 0..occupied {
+            if seat_conflicts(room, a) == 0 {
+                continue;
+            }
+            for b in 0..occupied {
+                if room.seats[a].class == room.seats[b].class {
+                    continue;
+                }
+                let before = seat_conflicts(room, a) + seat_conflicts(room, b);
+                room.seats.swap(a, b);
+                if seat_conflicts(room, a) + seat_conflicts(room, b) < before {
+                    improved = true;
+                    break;
+                }
+                room.seats.swap(a, b);
+            }
+        }
+        if !improved {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::algorithm::tests::sample_class;
+
+    #[test]
+    fn test_room_capacities() {
+        let config = ExamConfig::new(30).with_capacities(vec![40]);
+        assert_eq!(config.room_capacities(100).unwrap(), vec![40, 30, 30]);
+        assert_eq!(config.room_capacities(35).unwrap(), vec![40]);
+        assert!(ExamConfig::new(0).room_capacities(10).is_err());
+        assert!(
+            ExamConfig::new(30)
+                .with_columns(0)
+                .room_capacities(10)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_arrange_exam_rooms() {
+        let classes: Vec<Class> = (0..4).map(|id| sample_class(id, 20)).collect();
+        let plan = arrange_exam_rooms(&classes, &ExamConfig::new(30)).unwrap();
+        assert_eq!(plan.rooms.len(), 3);
+        assert_eq!(plan.student_count(), 80);
+        assert_eq!(plan.conflicts, 0);
+        assert_eq!(plan.rooms[2].seats.len(), 20);
+        assert_eq!(plan.rooms[1].exam_number(4), "0205");
+        assert_eq!(plan.rooms[0].position(7), (1, 1));
+
+        // 按排名时前面的考场分数更高
+        let score = |seat: &ExamSeat| classes[seat.class].students[seat.student].total_score;
+        let min_first = plan.rooms[0]
+            .seats
+            .iter()
+            .map(score)
+            .fold(f64::MAX, f64::min);
+        let max_last = plan.rooms[2]
+            .seats
+            .iter()
+            .map(score)
+            .fold(f64::MIN, f64::max);
+        assert!(min_first >= max_last);
+
+        // 随机打乱由种子决定
+        let config = ExamConfig::new(30)
+            .with_order(ExamOrder::Shuffle)
+            .with_seed(7);
+        let a = arrange_exam_rooms(&classes, &config).unwrap();
+        let b = arrange_exam_rooms(&classes, &config).unwrap();
+        assert_eq!(a.rooms[0].seats, b.rooms[0].seats);
+        assert_eq!(a.conflicts, 0);
+    }
+}
@@ -0,0 +1,414 @@
+use super::algorithm::{DivideConfig, DivideResult, OptimizationParams, divide_with_cancel};
+use super::model::Student;
+use rayon::prelude::*;
+use std::{ops::RangeInclusive, time::Duration};
+use tokio_util::sync::CancellationToken;
+
+/// 判断是否满足阈值时的浮点容差
+const EPSILON: f64 = 1e-9;
+
+/// 可自动收紧的约束阈值
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum Threshold {
+    /// 总分平均分最大差值
+    Score,
+    /// 单科平均分最大差值
+    Subject,
+    /// 男生比例最大差值
+    Gender,
+}
+
+impl Threshold {
+    pub const ALL: [Threshold; 3] = [Threshold::Score, Threshold::Subject, Threshold::Gender];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Threshold::Score => "总分最大差值",
+            Threshold::Subject => "单科最大差值",
+            Threshold::Gender => "性别比例最大差值",
+        }
+    }
+
+    /// 阈值的显示文本
+    pub fn format(&self, value: f64) -> String {
+        match self {
+            Threshold::Score | Threshold::Subject => format!("{:.2}分", value),
+            Threshold::Gender => format!("{:.1}%", value * 100.0),
+        }
+    }
+
+    fn get(&self, params: &OptimizationParams) -> f64 {
+        match self {
+            Threshold::Score => params.max_score_diff,
+            Threshold::Subject => params.max_subject_score_diff,
+            Threshold::Gender => params.max_gender_ratio_diff,
+        }
+    }
+
+    fn set(&self, params: &mut OptimizationParams, value: f64) {
+        match self {
+            Threshold::Score => params.max_score_diff = value,
+            Threshold::Subject => params.max_subject_score_diff = value,
+            Threshold::Gender => params.max_gender_ratio_diff = value,
+        }
+    }
+
+    /// 分班结果在该项上实际达到的最大差值
+    fn achieved(&self, result: &DivideResult) -> f64 {
+        let validation = &result.validation;
+        match self {
+            Threshold::Score => validation.max_score_diff,
+            Threshold::Subject => validation
+                .subject_max_diffs
+                .iter()
+                .map(|(_, diff)| *diff)
+                .fold(0.0, f64::max),
+            Threshold::Gender => validation.max_gender_ratio_diff,
+        }
+    }
+}
+
+/// 自动收紧配置
+#[derive(Debug, Clone)]
+pub struct TightenConfig {
+    /// 收紧顺序，排在前面的阈值优先收紧
+    pub priority: Vec<Threshold>,
+    /// 每个阈值的二分次数
+    pub bisection_steps: usize,
+}
+
+impl Default for TightenConfig {
+    fn default() -> Self {
+        Self {
+            priority: Threshold::ALL.to_vec(),
+            bisection_steps: 6,
+        }
+    }
+}
+
+/// 一次尝试的记录
+#[derive(Debug, Clone, Copy)]
+pub struct TightenStep {
+    pub threshold: Threshold,
+    /// 尝试的阈值
+    pub value: f64,
+    /// 是否找到满足全部阈值的分班
+    pub feasible: bool,
+}
+
+/// 自动收紧结果
+#[derive(Debug, Clone)]
+pub struct TightenResult {
+    /// 收紧后的参数（阈值为本次找到的最严组合）
+    pub params: OptimizationParams,
+    /// 满足上述阈值的分班结果
+    pub result: DivideResult,
+    /// 各次二分尝试
+    pub steps: Vec<TightenStep>,
+}
+
+impl TightenResult {
+    /// 用于界面摘要与导出的收紧信息
+    pub fn summary_items(&self) -> Vec<(String, String)> {
+        let mut items: Vec<(String, String)> = Threshold::ALL
+            .iter()
+            .map(|threshold| {
+                (
+                    format!("可达{}", threshold.label()),
+                    threshold.format(threshold.get(&self.params)),
+                )
+            })
+            .collect();
+        items.push((
+            "收紧尝试次数".to_string(),
+            format!(
+                "{}（成功 {} 次）",
+                self.steps.len(),
+                self.steps.iter().filter(|step| step.feasible).count()
+            ),
+        ));
+        items
+    }
+}
+
+/// 结果是否满足参数中的全部硬约束阈值
+fn meets_thresholds(result: &DivideResult, params: &OptimizationParams) -> bool {
+    Threshold::ALL
+        .iter()
+        .all(|threshold| threshold.achieved(result) <= threshold.get(params) + EPSILON)
+        && result.validation.max_class_size_diff <= params.max_class_size_diff
+}
+
+/// 自动收紧阈值
+///
+/// 先按 `config` 分班一次，把各阈值设为实际达到的差值；再按优先级对每个阈值
+/// 在 [0, 当前值] 上二分：能分出满足全部阈值的结果就收紧到实际达到的差值，否则放宽下界。
+/// 每次分班使用 `config` 的时间预算或迭代次数，种子依次递增。
+/// 取消时返回已找到的最严组合。
+pub fn auto_tighten(
+    students: &[Student],
+    config: DivideConfig,
+    tighten: &TightenConfig,
+    cancel_token: CancellationToken,
+) -> TightenResult {
+    let base_seed = config.seed.unwrap_or_else(rand::random);
+    let mut run_index = 0u64;
+    let mut run = |params: &OptimizationParams| {
+        let run_config = config
+            .clone()
+            .with_optimization_params(params.clone())
+            .with_seed(Some(base_seed.wrapping_add(run_index)));
+        run_index += 1;
+        divide_with_cancel(students, run_config, cancel_token.clone())
+    };
+
+    // 首次分班：实际达到的差值即为可达阈值
+    let mut params = config.optimization_params.clone();
+    let mut best = run(&params);
+    for threshold in Threshold::ALL {
+        threshold.set(&mut params, threshold.achieved(&best));
+    }
+
+    let mut steps = Vec::new();
+    'priority: for &threshold in &tighten.priority {
+        let mut low = 0.0;
+        let mut high = threshold.get(&params);
+
+        for _ in 0..tighten.bisection_steps {
+            if cancel_token.is_cancelled() {
+                break 'priority;
+            }
+
+            let mid = (low + high) / 2.0;
+            let mut trial = params.clone();
+            threshold.set(&mut trial, mid);
+            let result = run(&trial);
+            // 取消时得到的结果不可靠，不作为判定依据
+            if result.is_partial() {
+                break 'priority;
+            }
+
+            let feasible = meets_thresholds(&result, &trial);
+            steps.push(TightenStep {
+                threshold,
+                value: mid,
+                feasible,
+            });
+
+            if feasible {
+                high = threshold.achieved(&result).min(mid);
+                threshold.set(&mut params, high);
+                best = result;
+            } else {
+                low = mid;
+            }
+        }
+    }
+
+    TightenResult {
+        params,
+        result: best,
+        steps,
+    }
+}
+
+/// 班级数量扫描配置
+#[derive(Debug, Clone)]
+pub struct SweepConfig {
+    /// 要比较的班级数量范围
+    pub class_counts: RangeInclusive<usize>,
+    /// 每个班级数量的分班时间预算
+    pub time_budget: Duration,
+    /// 班级人数下限（政策要求）
+    pub min_class_size: Option<usize>,
+    /// 班级人数上限（政策要求）
+    pub max_class_size: Option<usize>,
+}
+
+impl Default for SweepConfig {
+    fn default() -> Self {
+        Self {
+            class_counts: 2..=12,
+            time_budget: Duration::from_secs(3),
+            min_class_size: None,
+            max_class_size: None,
+        }
+    }
+}
+
+/// 某个班级数量的分班概况
+#[derive(Debug, Clone)]
+pub struct SweepEntry {
+    pub num_classes: usize,
+    /// 最小班级人数
+    pub min_class_size: usize,
+    /// 最大班级人数
+    pub max_class_size: usize,
+    /// 可达总分最大差值
+    pub max_score_diff: f64,
+    /// 可达单科最大差值
+    pub max_subject_diff: f64,
+    /// 可达男生比例最大差值
+    pub max_gender_ratio_diff: f64,
+    /// 是否满足全部硬约束
+    pub constraints_met: bool,
+    /// 班级人数是否符合政策
+    pub size_policy_met: bool,
+    /// 总代价
+    pub cost: f64,
+}
+
+impl SweepEntry {
+    fn from_result(num_classes: usize, result: &DivideResult, sweep: &SweepConfig) -> Self {
+        let sizes = result.classes.iter().map(|class| class.students.len());
+        let min_class_size = sizes.clone().min().unwrap_or(0);
+        let max_class_size = sizes.max().unwrap_or(0);
+        let size_policy_met = sweep.min_class_size.is_none_or(|min| min_class_size >= min)
+            && sweep.max_class_size.is_none_or(|max| max_class_size <= max);
+
+        Self {
+            num_classes,
+            min_class_size,
+            max_class_size,
+            max_score_diff: Threshold::Score.achieved(result),
+            max_subject_diff: Threshold::Subject.achieved(result),
+            max_gender_ratio_diff: Threshold::Gender.achieved(result),
+            constraints_met: result.cost.hard_penalty() == 0.0,
+            size_policy_met,
+            cost: result.cost.total(),
+        }
+    }
+}
+
+/// 班级数量扫描结果
+#[derive(Debug, Clone)]
+pub struct SweepResult {
+    /// 按班级数量升序排列的各方案
+    pub entries: Vec<SweepEntry>,
+    /// 推荐的班级数量
+    pub recommended: Option<usize>,
+}
+
+/// 推荐班级数量
+///
+/// 在人数符合政策的方案中，优先取满足全部硬约束且班级数最少者（少开班节省师资）；
+/// 若都不满足硬约束，取总分差值最小者。
+fn recommend(entries: &[SweepEntry]) -> Option<usize> {
+    let candidates = entries.iter().filter(|entry| entry.size_policy_met);
+    candidates
+        .clone()
+        .filter(|entry| entry.constraints_met)
+        .min_by_key(|entry| entry.num_classes)
+        .or_else(|| candidates.min_by(|a, b| a.max_score_diff.total_cmp(&b.max_score_diff)))
+        .map(|entry| entry.num_classes)
+}
+
+/// 扫描班级数量
+///
+/// 对 `sweep.class_counts` 中每个班级数量并行分班一次（使用相同种子和 `sweep.time_budget`），
+/// 汇总各方案的班级人数、可达差值与约束满足情况，并按人数政策给出推荐。
+/// 初始分配与局部优化班级只对原班级数量有效，扫描时忽略。
+pub fn sweep_class_counts(
+    students: &[Student],
+    config: DivideConfig,
+    sweep: &SweepConfig,
+    cancel_token: CancellationToken,
+) -> SweepResult {
+    let seed = config.seed.unwrap_or_else(rand::random);
+    let mut entries: Vec<SweepEntry> = sweep
+        .class_counts
+        .clone()
+        .into_par_iter()
+        .filter(|&num_classes| num_classes >= 2 && num_classes <= students.len())
+        .map(|num_classes| {
+            let run_config = DivideConfig {
+                num_classes,
+                ..config.clone()
+            }
+            .with_seed(Some(seed))
+            .with_time_budget(Some(sweep.time_budget))
+            .with_trace_interval(None)
+            .with_initial_assignments(None)
+            .with_active_classes(None)
+            .with_alternatives(None)
+            .with_pareto(false);
+            let result = divide_with_cancel(students, run_config, cancel_token.clone());
+            SweepEntry::from_result(num_classes, &result, sweep)
+        })
+        .collect();
+    entries.sort_by_key(|entry| entry.num_classes);
+
+    let recommended = recommend(&entries);
+    SweepResult {
+        entries,
+        recommended,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::algorithm::tests::sample_students;
+    use std::time::Duration;
+
+    #[test]
+    fn test_auto_tighten_returns_division_meeting_tightened_thresholds() {
+        let students = sample_students(60);
+        let params = OptimizationParams {
+            num_parallel_instances: Some(2),
+            ..Default::default()
+        };
+        let config = DivideConfig::new(3)
+            .with_optimization_params(params)
+            .with_seed(Some(4))
+            .with_time_budget(Some(Duration::from_millis(30)));
+        let tighten = TightenConfig {
+            priority: vec![Threshold::Gender, Threshold::Score],
+            bisection_steps: 2,
+        };
+
+        let result = auto_tighten(&students, config, &tighten, CancellationToken::new());
+
+        assert_eq!(result.steps.len(), 4);
+        assert!(meets_thresholds(&result.result, &result.params));
+        // 未参与收紧的阈值保持为首次分班实际达到的差值
+        assert!(
+            result
+                .steps
+                .iter()
+                .all(|s| s.threshold != Threshold::Subject)
+        );
+    }
+
+    #[test]
+    fn test_sweep_recommends_fewest_classes_within_size_policy() {
+        let students = sample_students(60);
+        let params = OptimizationParams {
+            num_parallel_instances: Some(1),
+            ..Default::default()
+        };
+        let config = DivideConfig::new(2)
+            .with_optimization_params(params)
+            .with_seed(Some(9));
+        let sweep = SweepConfig {
+            class_counts: 2..=5,
+            time_budget: Duration::from_millis(30),
+            min_class_size: Some(12),
+            max_class_size: Some(20),
+        };
+
+        let result = sweep_class_counts(&students, config, &sweep, CancellationToken::new());
+
+        let counts: Vec<usize> = result.entries.iter().map(|e| e.num_classes).collect();
+        assert_eq!(counts, vec![2, 3, 4, 5]);
+        let sizes: Vec<(usize, usize)> = result
+            .entries
+            .iter()
+            .map(|e| (e.min_class_size, e.max_class_size))
+            .collect();
+        assert_eq!(sizes, vec![(30, 30), (20, 20), (15, 15), (12, 12)]);
+        // 2 班每班 30 人超出上限，推荐落在 3~5 班之间
+        assert!(!result.entries[0].size_policy_met);
+        assert!(matches!(result.recommended, Some(3..=5)));
+    }
+}
@@ -0,0 +1,124 @@
+use super::algorithm::{DivideConfig, DivideResult, OptimizationParams, divide};
+use super::model::{Class, Student};
+use std::time::Duration;
+
+/// 小组数的建议范围
+pub const MIN_STUDY_GROUPS: usize = 6;
+pub const MAX_STUDY_GROUPS: usize = 8;
+/// 每个小组的建议人数
+const STUDENTS_PER_GROUP: usize = 6;
+/// 每个小组至少的人数
+const MIN_GROUP_SIZE: usize = 2;
+/// 分小组的默认时间预算（一个班只有几十人，很快就能收敛）
+const STUDY_GROUP_TIME_BUDGET: Duration = Duration::from_secs(1);
+
+/// 按班级人数建议的小组数：每组 6 人左右，限制在 6~8 组，人数太少时减少组数
+pub fn recommended_group_count(class_size: usize) -> usize {
+    ((class_size + STUDENTS_PER_GROUP / 2) / STUDENTS_PER_GROUP)
+        .clamp(MIN_STUDY_GROUPS, MAX_STUDY_GROUPS)
+        .min(class_size / MIN_GROUP_SIZE)
+        .max(1)
+}
+
+/// 分小组的默认配置：小组预设参数与 1 秒的时间预算
+pub fn study_group_config(num_groups: usize) -> DivideConfig {
+    DivideConfig::new(num_groups)
+        .with_optimization_params(OptimizationParams::small_groups())
+        .with_time_budget(Some(STUDY_GROUP_TIME_BUDGET))
+}
+
+impl Class {
+    /// 把本班分成 `config.num_classes` 个总分、各科与性别均衡的学习小组
+    ///
+    /// 复用分班算法，结果写入 `study_groups`；返回的结果中每个“班级”即一个小组，
+    /// 可用于查看各组的均衡情况。通常配合 [`study_group_config`] 使用。
+    pub fn divide_into_groups(&mut self, config: DivideConfig) -> anyhow::Result<DivideResult> {
+        let num_groups = config.num_classes;
+        if num_groups == 0 {
+            anyhow::bail!("小组数必须大于 0");
+        }
+        if self.students.len() < num_groups * MIN_GROUP_SIZE {
+            anyhow::bail!(
+                "班级 {} 只有 {} 人，不足以分成 {} 个小组（每组至少 {} 人）",
+                self.id + 1,
+                self.students.len(),
+                num_groups,
+                MIN_GROUP_SIZE
+            );
+        }
+
+        let result = divide(&self.students, config);
+        self.study_groups = Some(result.assignments.clone());
+        Ok(result)
+    }
+
+    /// 小组数，未分小组时为 0
+    pub fn study_group_count(&self) -> usize {
+        self.study_groups
+            .as_ref()
+            .and_then(|groups| groups.iter().max())
+            .map_or(0, |&max| max + 1)
+    }
+
+    /// 各小组的学生，未分小组时为空
+    pub fn study_group_members(&self) -> Vec<Vec<&Student>> {
+        let mut members = vec![Vec::new(); self.study_group_count()];
+        if let Some(groups) = &self.study_groups {
+            for (student, &group) in self.students.iter().zip(groups) {
+                members[group].push(student);
+            }
+        }
+        members
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{algorithm::tests::sample_class, model::Gender};
+
+    #[test]
+    fn test_recommended_group_count() {
+        assert_eq!(recommended_group_count(45), 8);
+        assert_eq!(recommended_group_count(40), 7);
+        assert_eq!(recommended_group_count(30), 6);
+        assert_eq!(recommended_group_count(60), 8);
+        assert_eq!(recommended_group_count(7), 3);
+    }
+
+    #[test]
+    fn test_divide_class_into_balanced_groups() {
+        let mut class = sample_class(2, 45);
+        let params = OptimizationParams {
+            num_parallel_instances: Some(1),
+            ..OptimizationParams::small_groups()
+        };
+        let result = class
+            .divide_into_groups(
+                study_group_config(7)
+                    .with_optimization_params(params)
+                    .with_seed(Some(4))
+                    .with_time_budget(None)
+                    .with_iterations(20_000),
+            )
+            .unwrap();
+
+        let v = &result.validation;
+        assert!(
+            v.score_constraints_met && v.gender_constraints_met && v.class_size_constraints_met
+        );
+        assert_eq!(class.study_group_count(), 7);
+        let members = class.study_group_members();
+        for group in &members {
+            assert!((6..=7).contains(&group.len()));
+            // 45 人中 15 名女生，每组 2~3 名
+            let females = group.iter().filter(|s| s.gender == Gender::Female).count();
+            assert!((2..=3).contains(&females));
+        }
+        assert_eq!(members.iter().map(Vec::len).sum::<usize>(), 45);
+
+        let mut small = sample_class(2, 9);
+        assert!(small.divide_into_groups(study_group_config(6)).is_err());
+        assert!(small.study_groups.is_none());
+    }
+}
@@ -14,6 +14,15 @@ pub struct HistoryRecord {
     pub format: String, // "xlsx" or "csv"
     #[serde(default)]
     pub optimization_params: OptimizationParams,
+    /// 分班使用的随机种子（旧记录没有此字段）
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// 分班使用的并行实例数，与种子、迭代次数一起用于复现
+    #[serde(default)]
+    pub num_instances: Option<usize>,
+    /// 每个实例实际执行的迭代次数（按时间预算停止时也是实际次数）
+    #[serde(default)]
+    pub iterations: Option<usize>,
 }
 
 impl HistoryRecord {
@@ -24,6 +33,7 @@ impl HistoryRecord {
         num_students: usize,
         format: String,
         optimization_params: OptimizationParams,
+        seed: Option<u64>,
     ) -> Self {
         let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
         Self {
@@ -34,8 +44,18 @@ impl HistoryRecord {
             num_students,
             format,
             optimization_params,
+            seed,
+            num_instances: None,
+            iterations: None,
         }
     }
+
+    /// 记录并行实例数与实际迭代次数，复现时配合 `DivideConfig::with_iterations` 使用
+    pub fn with_run(mut self, num_instances: Option<usize>, iterations: Option<usize>) -> Self {
+        self.num_instances = num_instances;
+        self.iterations = iterations;
+        self
+    }
 }
 
 pub struct HistoryManager {
@@ -0,0 +1,157 @@
+use super::model::Gender;
+use chrono::NaiveDate;
+
+/// 校验码计算中前 17 位的权重
+const CHECK_WEIGHTS: [u32; 17] = [7, 9, 10, 5, 8, 4, 2, 1, 6, 3, 7, 9, 10, 5, 8, 4, 2];
+/// 加权和除以 11 的余数对应的校验码
+const CHECK_CODES: [char; 11] = ['1', '0', 'X', '9', '8', '7', '6', '5', '4', '3', '2'];
+
+/// 解析后的 18 位身份证号
+#[derive(Debug, Clone, PartialEq)]
+pub struct NationalId {
+    /// 规范化后的号码（去掉空白，末位 x 改为大写）
+    pub number: String,
+    /// 由第 17 位得出的性别：奇数为男，偶数为女
+    pub gender: Gender,
+    /// 由第 7~14 位得出的出生日期
+    pub birth_date: NaiveDate,
+}
+
+/// 解析并校验身份证号
+pub fn parse_national_id(value: &str) -> anyhow::Result<NationalId> {
+    let number: String = value
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    let chars: Vec<char> = number.chars().collect();
+    if chars.len() != 18 {
+        anyhow::bail!("应为 18 位，实际为 {} 位", chars.len());
+    }
+
+    let digits: Vec<u32> = chars[..17]
+        .iter()
+        .map(|c| c.to_digit(10))
+        .collect::<Option<_>>()
+        .ok_or_else(|| anyhow::anyhow!("前 17 位应全为数字"))?;
+    let sum: u32 = digits.iter().zip(CHECK_WEIGHTS).map(|(d, w)| d * w).sum();
+    let expected = CHECK_CODES[(sum % 11) as usize];
+    if chars[17] != expected {
+        anyhow::bail!("校验位应为 {}，实际为 {}", expected, chars[17]);
+    }
+
+    let field = |range: std::ops::Range<usize>| {
+        digits[range]
+            .iter()
+            .fold(0, |value, digit| value * 10 + digit)
+    };
+    let birth_date = NaiveDate::from_ymd_opt(field(6..10) as i32, field(10..12), field(12..14))
+        .ok_or_else(|| anyhow::anyhow!("出生日期 {} 无效", &number[6..14]))?;
+    let gender = if digits[16] % 2 == 1 {
+        Gender::Male
+    } else {
+        Gender::Female
+    };
+
+    Ok(NationalId {
+        number,
+        gender,
+        birth_date,
+    })
+}
+
+/// 身份证号核对发现的问题
+#[derive(Debug, Clone, PartialEq)]
+pub enum IdentityIssue {
+    /// 号码无法解析或校验位错误
+    Invalid(String),
+    /// 性别列与身份证号推出的性别不一致
+    GenderMismatch { declared: Gender, derived: Gender },
+}
+
+impl IdentityIssue {
+    /// 问题描述，用于界面提示与导出
+    pub fn describe(&self) -> String {
+        match self {
+            IdentityIssue::Invalid(reason) => format!("身份证号无效：{}", reason),
+            IdentityIssue::GenderMismatch { declared, derived } => format!(
+                "性别填写为“{}”，身份证号为“{}”",
+                declared.label(),
+                derived.label()
+            ),
+        }
+    }
+}
+
+/// 核对性别列与身份证号
+///
+/// 身份证号有效时以其推出的性别为准，与填写的性别不一致则记为问题；
+/// 号码无效时保留填写的性别。返回采用的性别、解析结果与发现的问题。
+pub fn reconcile_gender(
+    declared: Gender,
+    national_id: &str,
+) -> (Gender, Option<NationalId>, Option<IdentityIssue>) {
+    match parse_national_id(national_id) {
+        Ok(id) => {
+            let issue = (declared != Gender::Unknown && declared != id.gender).then_some(
+                IdentityIssue::GenderMismatch {
+                    declared,
+                    derived: id.gender,
+                },
+            );
+            (id.gender, Some(id), issue)
+        }
+        Err(e) => (declared, None, Some(IdentityIssue::Invalid(e.to_string()))),
+    }
+}
+
+/// 到 `on` 为止的年龄（岁，含小数）
+pub fn age_on(birth_date: NaiveDate, on: NaiveDate) -> f64 {
+    (on - birth_date).num_days() as f64 / 365.2425
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_national_id() {
+        let id = parse_national_id(" 11010519491231002x ").unwrap();
+        assert_eq!(id.number, "11010519491231002X");
+        assert_eq!(id.gender, Gender::Female);
+        assert_eq!(
+            id.birth_date,
+            NaiveDate::from_ymd_opt(1949, 12, 31).unwrap()
+        );
+
+        let id = parse_national_id("440524188001010014").unwrap();
+        assert_eq!(id.gender, Gender::Male);
+
+        assert!(parse_national_id("110105194912310021").is_err());
+        assert!(parse_national_id("1101051949123100").is_err());
+        assert!(parse_national_id("110105194913310021").is_err());
+    }
+
+    #[test]
+    fn test_reconcile_gender_flags_mismatch() {
+        let (gender, id, issue) = reconcile_gender(Gender::Male, "11010519491231002X");
+        assert_eq!(gender, Gender::Female);
+        assert!(id.is_some());
+        assert_eq!(
+            issue,
+            Some(IdentityIssue::GenderMismatch {
+                declared: Gender::Male,
+                derived: Gender::Female
+            })
+        );
+
+        let (gender, _, issue) = reconcile_gender(Gender::Unknown, "440524188001010014");
+        assert_eq!(gender, Gender::Male);
+        assert!(issue.is_none());
+
+        let (gender, id, issue) = reconcile_gender(Gender::Female, "123");
+        assert_eq!(gender, Gender::Female);
+        assert!(id.is_none());
+        assert!(matches!(issue, Some(IdentityIssue::Invalid(_))));
+    }
+}
@@ -1,9 +1,19 @@
-use super::model::{Class, Gender, Student};
+use super::algorithm::TracePoint;
+use super::dorm::{DormPlan, Resident};
+use super::exam::{ExamPlan, ExamRoom};
+use super::identity::{IdentityIssue, reconcile_gender};
+use super::model::{Class, Gender, GenderAliases, Student};
+use super::names::{NameMatch, describe_group, duplicate_name_groups, name_key};
+use super::preferences::{
+    format_preferences, parse_preferences, preference_report, resolve_requests,
+};
+use super::seating::SeatingChart;
 use calamine::{Data, DataType, Reader, open_workbook_auto};
+use chrono::NaiveDate;
 use csv::{Reader as CsvReader, Writer as CsvWriter};
 use fs_err as fs;
 use rayon::prelude::*;
-use rust_xlsxwriter::{Format, Workbook};
+use rust_xlsxwriter::{Format, FormatAlign, FormatBorder, Workbook};
 use std::collections::HashMap;
 
 /// Excel 列配置
@@ -13,14 +23,26 @@ pub struct ColumnConfig {
     pub name_column: usize,
     /// 学号所在列，None 表示没有学号列
     pub student_id_column: Option<usize>,
-    /// 性别所在列
-    pub gender_column: usize,
+    /// 性别所在列，None 表示只从身份证号推出性别
+    pub gender_column: Option<usize>,
     /// 总成绩所在列，None 表示自动计算
     pub total_score_column: Option<usize>,
     /// 单科成绩列（列名 -> 列索引
     pub subject_columns: HashMap<String, usize>,
     /// 需要保留的额外列（列名 -> 列索引）
     pub extra_columns: HashMap<String, usize>,
+    /// 原班级所在列，None 表示没有原班级列
+    pub original_class_column: Option<usize>,
+    /// 选科组合所在列，None 表示没有选科组合列
+    pub combination_column: Option<usize>,
+    /// 身份证号所在列，None 表示没有身份证号列
+    pub national_id_column: Option<usize>,
+    /// 好友志愿所在列，None 表示没有好友志愿列
+    pub preference_column: Option<usize>,
+    /// 身高所在列，None 表示没有身高列
+    pub height_column: Option<usize>,
+    /// 性别取值的别名表
+    pub gender_aliases: GenderAliases,
 }
 
 impl ColumnConfig {
@@ -39,6 +61,12 @@ pub struct ColumnConfigBuilder {
     total_score: Option<usize>,
     subject_score: HashMap<String, usize>,
     extra: HashMap<String, usize>,
+    original_class: Option<usize>,
+    combination: Option<usize>,
+    national_id: Option<usize>,
+    preference: Option<usize>,
+    height: Option<usize>,
+    gender_aliases: GenderAliases,
 }
 
 impl ColumnConfigBuilder {
@@ -72,14 +100,53 @@ impl ColumnConfigBuilder {
         self
     }
 
+    pub fn original_class(mut self, col: usize) -> Self {
+        self.original_class = Some(col);
+        self
+    }
+
+    pub fn combination(mut self, col: usize) -> Self {
+        self.combination = Some(col);
+        self
+    }
+
+    pub fn national_id(mut self, col: usize) -> Self {
+        self.national_id = Some(col);
+        self
+    }
+
+    pub fn preferences(mut self, col: usize) -> Self {
+        self.preference = Some(col);
+        self
+    }
+
+    pub fn height(mut self, col: usize) -> Self {
+        self.height = Some(col);
+        self
+    }
+
+    pub fn gender_aliases(mut self, aliases: GenderAliases) -> Self {
+        self.gender_aliases = aliases;
+        self
+    }
+
     pub fn build(self) -> anyhow::Result<ColumnConfig> {
         Ok(ColumnConfig {
             name_column: self.name.ok_or_else(|| anyhow::anyhow!("姓名列未指定"))?,
             student_id_column: self.id,
-            gender_column: self.gender.ok_or_else(|| anyhow::anyhow!("性别列未指定"))?,
+            gender_column: match (self.gender, self.national_id) {
+                (None, None) => anyhow::bail!("性别列未指定"),
+                (gender, _) => gender,
+            },
             total_score_column: self.total_score,
             subject_columns: self.subject_score,
             extra_columns: self.extra,
+            original_class_column: self.original_class,
+            combination_column: self.combination,
+            national_id_column: self.national_id,
+            preference_column: self.preference,
+            height_column: self.height,
+            gender_aliases: self.gender_aliases,
         })
     }
 }
@@ -115,9 +182,18 @@ pub fn read_from_excel(file_path: &str, config: &ColumnConfig) -> anyhow::Result
                 Some(format!("R{}", row_idx + 1))
             };
 
-            // 读取性别
-            let gender_str = get_cell_string(row, config.gender_column)?;
-            let gender = gender_str.parse::<Gender>().ok()?;
+            // 读取性别与身份证号
+            let (gender, national_id, birth_date, identity_issue) = read_identity(
+                config,
+                config
+                    .gender_column
+                    .and_then(|col| get_cell_string(row, col))
+                    .as_deref(),
+                config
+                    .national_id_column
+                    .and_then(|col| get_cell_string(row, col))
+                    .as_deref(),
+            );
 
             // 读取科目成绩
             let mut scores = HashMap::with_capacity(config.subject_columns.len());
@@ -130,7 +206,7 @@ pub fn read_from_excel(file_path: &str, config: &ColumnConfig) -> anyhow::Result
             let total_score = if let Some(col) = config.total_score_column {
                 get_cell_score(row, col)
             } else {
-                scores.values().sum()
+                Student::sum_scores(&scores)
             };
 
             // 读取额外字段
@@ -141,6 +217,32 @@ pub fn read_from_excel(file_path: &str, config: &ColumnConfig) -> anyhow::Result
                 }
             }
 
+            // 读取原班级
+            let original_class = config
+                .original_class_column
+                .and_then(|col| get_cell_string(row, col))
+                .and_then(|value| parse_class_number(&value));
+
+            // 读取选科组合
+            let combination = config
+                .combination_column
+                .and_then(|col| get_cell_string(row, col))
+                .map(|value| normalize_combination(&value))
+                .filter(|value| !value.is_empty());
+
+            // 读取好友志愿
+            let preferences = config
+                .preference_column
+                .and_then(|col| get_cell_string(row, col))
+                .map(|value| parse_preferences(&value))
+                .unwrap_or_default();
+
+            // 读取身高
+            let height = config
+                .height_column
+                .and_then(|col| get_cell_string(row, col))
+                .and_then(|value| parse_height(&value));
+
             Some(Student {
                 name,
                 id: student_id,
@@ -148,6 +250,13 @@ pub fn read_from_excel(file_path: &str, config: &ColumnConfig) -> anyhow::Result
                 scores,
                 total_score,
                 extra_fields,
+                original_class,
+                combination,
+                national_id,
+                birth_date,
+                identity_issue,
+                preferences,
+                height,
             })
         })
         .collect();
@@ -159,6 +268,32 @@ pub fn read_from_excel(file_path: &str, config: &ColumnConfig) -> anyhow::Result
     Ok(students)
 }
 
+/// 确定学生的性别并核对身份证号
+///
+/// 性别列空白或无法识别时记为未知；有身份证号时按 [`reconcile_gender`] 核对。
+/// 返回性别、身份证号、出生日期与核对问题。
+fn read_identity(
+    config: &ColumnConfig,
+    gender_value: Option<&str>,
+    national_id_value: Option<&str>,
+) -> (
+    Gender,
+    Option<String>,
+    Option<NaiveDate>,
+    Option<IdentityIssue>,
+) {
+    let declared = config
+        .gender_aliases
+        .parse(gender_value.unwrap_or_default());
+    match national_id_value.map(str::trim).filter(|v| !v.is_empty()) {
+        Some(value) => match reconcile_gender(declared, value) {
+            (gender, Some(id), issue) => (gender, Some(id.number), Some(id.birth_date), issue),
+            (gender, None, issue) => (gender, Some(value.to_string()), None, issue),
+        },
+        None => (declared, None, None, None),
+    }
+}
+
 // 辅助函数：从单元格读取字符串
 fn get_cell_string(row: &[Data], col: usize) -> Option<String> {
     if col < row.len() {
@@ -186,6 +321,32 @@ fn get_cell_score(row: &[Data], col: usize) -> f64 {
     }
 }
 
+/// 解析身高（如 "165"、"165.5cm"、"1.65米"），统一为厘米；无法解析或不为正时返回 None
+fn parse_height(value: &str) -> Option<f64> {
+    let number: String = value
+        .trim()
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '.')
+        .collect();
+    let height = number.parse::<f64>().ok().filter(|h| *h > 0.0)?;
+    // 小于 3 的数值视为以米为单位
+    Some(if height < 3.0 { height * 100.0 } else { height })
+}
+
+/// 解析班级编号（如 "3"、"3班"、"高一(3)班"），返回从 0 开始的班级索引
+fn parse_class_number(value: &str) -> Option<usize> {
+    let digits: String = value
+        .chars()
+        .rev()
+        .skip_while(|c| !c.is_ascii_digit())
+        .take_while(|c| c.is_ascii_digit())
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .collect();
+    digits.parse::<usize>().ok()?.checked_sub(1)
+}
+
 #[inline]
 fn parse_score(cell: &calamine::Data) -> f64 {
     cell.get_float().unwrap_or_else(|| {
@@ -206,12 +367,181 @@ fn has_real_student_ids(classes: &[Class]) -> bool {
     })
 }
 
+fn has_tiers(classes: &[Class]) -> bool {
+    classes.iter().any(|class| class.tier.is_some())
+}
+
+fn has_combinations(classes: &[Class]) -> bool {
+    classes.iter().any(|class| class.combination.is_some())
+}
+
+fn has_heights(classes: &[Class]) -> bool {
+    classes
+        .iter()
+        .any(|class| class.students.iter().any(|s| s.height.is_some()))
+}
+
+fn has_seating(classes: &[Class]) -> bool {
+    classes.iter().any(|class| class.seating.is_some())
+}
+
+/// 学生的座位（如“第 3 排第 4 列”），本班未排座位时为空
+fn seat_label(class: &Class, student_idx: usize) -> String {
+    class
+        .seating
+        .as_ref()
+        .and_then(|chart| chart.seat_of(student_idx))
+        .map(|(row, col)| format!("第 {} 排第 {} 列", row + 1, col + 1))
+        .unwrap_or_default()
+}
+
+/// 写入一个班级的座位表：讲台在上，第 1 排离讲台最近，过道留出空列
+fn write_seating_sheet(
+    workbook: &mut Workbook,
+    class: &Class,
+    chart: &SeatingChart,
+    header_format: &Format,
+) -> anyhow::Result<()> {
+    let sheet = workbook.add_worksheet();
+    sheet.set_name(format!("{}班座位", class.id + 1))?;
+    let seat_format = Format::new()
+        .set_align(FormatAlign::Center)
+        .set_border(FormatBorder::Thin);
+
+    // 每个座位在表格中的列：第 0 列为排号，过道各占一列
+    let layout = &chart.layout;
+    let mut seat_columns = Vec::with_capacity(layout.columns);
+    let mut sheet_col = 1u16;
+    for col in 0..layout.columns {
+        seat_columns.push(sheet_col);
+        sheet.set_column_width(sheet_col, 10)?;
+        sheet_col += 1;
+        if layout.has_aisle_after(col) {
+            sheet.set_column_width(sheet_col, 3)?;
+            sheet_col += 1;
+        }
+    }
+    let last_col = sheet_col - 1;
+
+    let podium_format = header_format.clone().set_align(FormatAlign::Center);
+    if last_col > 1 {
+        sheet.merge_range(0, 1, 0, last_col, "讲台", &podium_format)?;
+    } else {
+        sheet.write_with_format(0, 1, "讲台", &podium_format)?;
+    }
+
+    for (row, seats) in chart.seats.iter().enumerate() {
+        let sheet_row = (row + 1) as u32;
+        sheet.write_with_format(sheet_row, 0, format!("第 {} 排", row + 1), header_format)?;
+        for (col, seat) in seats.iter().enumerate() {
+            let name = seat.map_or("", |idx| class.students[idx].name.as_str());
+            sheet.write_with_format(sheet_row, seat_columns[col], name, &seat_format)?;
+        }
+    }
+
+    // 未能满足的规则
+    let first_row = chart.seats.len() + 2;
+    for (idx, issue) in chart.issues.iter().enumerate() {
+        sheet.write_string((first_row + idx) as u32, 0, issue)?;
+    }
+    Ok(())
+}
+
+fn has_study_groups(classes: &[Class]) -> bool {
+    classes.iter().any(|class| class.study_groups.is_some())
+}
+
+/// 学生所在小组（从 1 开始），本班未分小组时为空
+fn study_group_label(class: &Class, student_idx: usize) -> String {
+    class
+        .study_groups
+        .as_ref()
+        .map(|groups| (groups[student_idx] + 1).to_string())
+        .unwrap_or_default()
+}
+
+/// 选科科目全称与简称
+const SUBJECT_ABBREVIATIONS: [(&str, &str); 8] = [
+    ("思想政治", "政"),
+    ("物理", "物"),
+    ("化学", "化"),
+    ("生物", "生"),
+    ("政治", "政"),
+    ("历史", "历"),
+    ("地理", "地"),
+    ("技术", "技"),
+];
+
+/// 规范化选科组合：科目全称改为简称并去掉空白和分隔符，
+/// 如“物理+化学+生物”“物 化 生”统一为“物化生”
+pub fn normalize_combination(value: &str) -> String {
+    let mut value = value.to_string();
+    for (full, short) in SUBJECT_ABBREVIATIONS {
+        value = value.replace(full, short);
+    }
+    value
+        .chars()
+        .filter(|c| !c.is_whitespace() && !"+＋、,，/|-".contains(*c))
+        .collect()
+}
+
+fn has_national_ids(classes: &[Class]) -> bool {
+    classes
+        .iter()
+        .any(|class| class.students.iter().any(|s| s.national_id.is_some()))
+}
+
+fn has_identity_issues(classes: &[Class]) -> bool {
+    classes
+        .iter()
+        .any(|class| class.students.iter().any(|s| s.identity_issue.is_some()))
+}
+
+fn has_original_classes(classes: &[Class]) -> bool {
+    classes
+        .iter()
+        .any(|class| class.students.iter().any(|s| s.original_class.is_some()))
+}
+
+fn birth_date_label(student: &Student) -> String {
+    student
+        .birth_date
+        .map(|date| date.format("%Y-%m-%d").to_string())
+        .unwrap_or_default()
+}
+
+fn identity_issue_label(student: &Student) -> String {
+    student
+        .identity_issue
+        .as_ref()
+        .map(IdentityIssue::describe)
+        .unwrap_or_default()
+}
+
+/// 志愿满足情况的显示文本，如“1/2”，未提出志愿为空
+fn satisfied_label(satisfied: Option<(usize, usize)>) -> String {
+    satisfied
+        .map(|(met, total)| format!("{}/{}", met, total))
+        .unwrap_or_default()
+}
+
+/// 原班级的显示文本（从 1 开始），没有原班级的学生为新生
+fn original_class_label(student: &Student) -> String {
+    student
+        .original_class
+        .map(|c| (c + 1).to_string())
+        .unwrap_or_else(|| "新生".to_string())
+}
+
 /// 导出分班结果到 Excel（带额外字段）
+///
+/// `run_info` 为运行信息（如随机种子）的键值对，非空时写入“分班信息”工作表
 pub fn export_to_excel(
     classes: &[Class],
     file_path: &str,
     subjects: &[&str],
     extra_field_names: &[&str],
+    run_info: &[(String, String)],
 ) -> anyhow::Result<()> {
     let mut workbook = Workbook::new();
 
@@ -221,9 +551,19 @@ pub fn export_to_excel(
         .set_background_color(rust_xlsxwriter::Color::RGB(0xD9E1F2));
 
     let score_format = Format::new().set_num_format("0.0");
+    let age_format = Format::new().set_num_format("0.00");
 
     // 检查是否有真实学号
     let has_student_id = has_real_student_ids(classes);
+    let has_original_class = has_original_classes(classes);
+    let has_tier = has_tiers(classes);
+    let has_combination = has_combinations(classes);
+    let has_study_group = has_study_groups(classes);
+    let has_height = has_heights(classes);
+    let has_seat = has_seating(classes);
+    let has_national_id = has_national_ids(classes);
+    let has_identity_issue = has_identity_issues(classes);
+    let preferences = preference_report(classes);
 
     // 工作表1: 分班结果详情
     let sheet = workbook.add_worksheet();
@@ -231,11 +571,40 @@ pub fn export_to_excel(
 
     // 写入表头
     let mut headers = vec!["班级"];
+    if has_tier {
+        headers.push("层次");
+    }
+    if has_combination {
+        headers.push("选科组合");
+    }
+    if has_study_group {
+        headers.push("小组");
+    }
+    if has_seat {
+        headers.push("座位");
+    }
     if has_student_id {
         headers.push("学号");
     }
     headers.push("姓名");
     headers.push("性别");
+    if has_height {
+        headers.push("身高");
+    }
+    if has_original_class {
+        headers.push("原班级");
+    }
+    if has_national_id {
+        headers.push("身份证号");
+        headers.push("出生日期");
+    }
+    if has_identity_issue {
+        headers.push("身份证核对");
+    }
+    if preferences.is_some() {
+        headers.push("好友志愿");
+        headers.push("志愿满足");
+    }
     headers.extend(extra_field_names.iter().copied());
     headers.extend(subjects.iter().copied());
     headers.push("总分");
@@ -246,12 +615,36 @@ pub fn export_to_excel(
 
     // 写入学生数据
     let mut row = 1u32;
-    for class in classes {
-        for student in &class.students {
+    for (class_idx, class) in classes.iter().enumerate() {
+        for (student_idx, student) in class.students.iter().enumerate() {
             let mut col = 0u16;
             sheet.write(row, col, (class.id + 1) as f64)?;
             col += 1;
 
+            // 层次（分层分班）
+            if has_tier {
+                sheet.write_string(row, col, class.tier.as_deref().unwrap_or(""))?;
+                col += 1;
+            }
+
+            // 选科组合（按学生本人的组合，兼容组合同班时各不相同）
+            if has_combination {
+                sheet.write_string(row, col, student.combination.as_deref().unwrap_or(""))?;
+                col += 1;
+            }
+
+            // 小组（班内分小组）
+            if has_study_group {
+                sheet.write_string(row, col, study_group_label(class, student_idx))?;
+                col += 1;
+            }
+
+            // 座位
+            if has_seat {
+                sheet.write_string(row, col, seat_label(class, student_idx))?;
+                col += 1;
+            }
+
             // 学号（仅当有真实学号时）
             if has_student_id {
                 let student_id = student.id.as_deref().unwrap_or("");
@@ -264,17 +657,44 @@ pub fn export_to_excel(
             col += 1;
 
             // 性别
-            sheet.write_string(
-                row,
-                col,
-                if student.gender == Gender::Male {
-                    "男"
-                } else {
-                    "女"
-                },
-            )?;
+            sheet.write_string(row, col, student.gender.label())?;
             col += 1;
 
+            // 身高
+            if has_height {
+                if let Some(height) = student.height {
+                    sheet.write(row, col, height)?;
+                }
+                col += 1;
+            }
+
+            // 原班级（微调模式）
+            if has_original_class {
+                sheet.write_string(row, col, original_class_label(student))?;
+                col += 1;
+            }
+
+            // 身份证号与出生日期
+            if has_national_id {
+                sheet.write_string(row, col, student.national_id.as_deref().unwrap_or(""))?;
+                col += 1;
+                sheet.write_string(row, col, birth_date_label(student))?;
+                col += 1;
+            }
+            if has_identity_issue {
+                sheet.write_string(row, col, identity_issue_label(student))?;
+                col += 1;
+            }
+
+            // 好友志愿及满足情况
+            if let Some(report) = &preferences {
+                let satisfied = report.satisfied_by_student[class_idx][student_idx];
+                sheet.write_string(row, col, format_preferences(&student.preferences))?;
+                col += 1;
+                sheet.write_string(row, col, satisfied_label(satisfied))?;
+                col += 1;
+            }
+
             // 额外字段
             for field_name in extra_field_names {
                 let value = student
@@ -313,6 +733,17 @@ pub fn export_to_excel(
     ];
     stat_headers.extend(subjects.iter().map(|s| format!("{}_平均", s)));
     stat_headers.push("总分平均".to_string());
+    if has_tier {
+        stat_headers.push("层次".to_string());
+    }
+    if has_combination {
+        stat_headers.push("选科组合".to_string());
+    }
+    let today = chrono::Local::now().date_naive();
+    let has_birth_date = classes.iter().any(|class| class.avg_age(today).is_some());
+    if has_birth_date {
+        stat_headers.push("平均年龄".to_string());
+    }
 
     for (col, header) in stat_headers.iter().enumerate() {
         stats_sheet.write_with_format(0, col as u16, header.as_str(), &header_format)?;
@@ -337,6 +768,311 @@ pub fn export_to_excel(
             col += 1;
         }
         stats_sheet.write_with_format(row, col, class.avg_total_score(), &score_format)?;
+        if has_tier {
+            col += 1;
+            stats_sheet.write_string(row, col, class.tier.as_deref().unwrap_or(""))?;
+        }
+        if has_combination {
+            col += 1;
+            stats_sheet.write_string(row, col, class.combination.as_deref().unwrap_or(""))?;
+        }
+        if has_birth_date {
+            col += 1;
+            if let Some(age) = class.avg_age(today) {
+                stats_sheet.write_with_format(row, col, age, &age_format)?;
+            }
+        }
+    }
+
+    // 小组统计：各班每个小组的人数、性别与平均分
+    if has_study_group {
+        let group_sheet = workbook.add_worksheet();
+        group_sheet.set_name("小组统计")?;
+        let group_headers = ["班级", "小组", "人数", "男生", "女生", "平均总分", "成员"];
+        for (col, header) in group_headers.iter().enumerate() {
+            group_sheet.write_with_format(0, col as u16, *header, &header_format)?;
+        }
+
+        let mut row = 1u32;
+        for class in classes {
+            for (group_idx, members) in class.study_group_members().iter().enumerate() {
+                let count = |gender: Gender| members.iter().filter(|s| s.gender == gender).count();
+                let avg = members.iter().map(|s| s.total_score).sum::<f64>() / members.len() as f64;
+                let names: Vec<&str> = members.iter().map(|s| s.name.as_str()).collect();
+                group_sheet.write(row, 0, (class.id + 1) as f64)?;
+                group_sheet.write(row, 1, (group_idx + 1) as f64)?;
+                group_sheet.write(row, 2, members.len() as f64)?;
+                group_sheet.write(row, 3, count(Gender::Male) as f64)?;
+                group_sheet.write(row, 4, count(Gender::Female) as f64)?;
+                group_sheet.write_with_format(row, 5, avg, &score_format)?;
+                group_sheet.write_string(row, 6, names.join("、"))?;
+                row += 1;
+            }
+        }
+    }
+
+    // 各班座位表
+    for class in classes {
+        if let Some(chart) = &class.seating {
+            write_seating_sheet(&mut workbook, class, chart, &header_format)?;
+        }
+    }
+
+    // 工作表3: 分班信息
+    if !run_info.is_empty() {
+        let info_sheet = workbook.add_worksheet();
+        info_sheet.set_name("分班信息")?;
+        info_sheet.write_with_format(0, 0, "项目", &header_format)?;
+        info_sheet.write_with_format(0, 1, "值", &header_format)?;
+
+        for (idx, (key, value)) in run_info.iter().enumerate() {
+            let row = (idx + 1) as u32;
+            info_sheet.write_string(row, 0, key)?;
+            info_sheet.write_string(row, 1, value)?;
+        }
+    }
+
+    workbook.save(file_path)?;
+    Ok(())
+}
+
+/// 导出考场安排：“考场安排”按班级列出每个考生的考场与座位，“座位表”为各考场的座位图，
+/// 另为每个考场生成一张贴在门口的考生名单
+pub fn export_exam_plan(classes: &[Class], plan: &ExamPlan, file_path: &str) -> anyhow::Result<()> {
+    let mut workbook = Workbook::new();
+    let header_format = Format::new()
+        .set_bold()
+        .set_background_color(rust_xlsxwriter::Color::RGB(0xD9E1F2));
+    let title_format = Format::new().set_bold().set_align(FormatAlign::Center);
+    let has_student_id = has_real_student_ids(classes);
+
+    // 工作表1: 考场安排，按班级、班内顺序排列
+    let mut entries: Vec<(&ExamRoom, usize)> = plan
+        .rooms
+        .iter()
+        .flat_map(|room| (0..room.seats.len()).map(move |seat| (room, seat)))
+        .collect();
+    entries.sort_by_key(|(room, seat)| {
+        let exam_seat = room.seats[*seat];
+        (classes[exam_seat.class].id, exam_seat.student)
+    });
+
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("考场安排")?;
+    let mut headers = vec!["考号", "姓名"];
+    if has_student_id {
+        headers.push("学号");
+    }
+    headers.extend(["班级", "考场", "座位号"]);
+    for (col, header) in headers.iter().enumerate() {
+        sheet.write_with_format(0, col as u16, *header, &header_format)?;
+    }
+    for (idx, (room, seat)) in entries.iter().enumerate() {
+        let row = (idx + 1) as u32;
+        let exam_seat = room.seats[*seat];
+        let class = &classes[exam_seat.class];
+        let student = &class.students[exam_seat.student];
+        let mut col = 0u16;
+        sheet.write_string(row, col, room.exam_number(*seat))?;
+        col += 1;
+        sheet.write_string(row, col, &student.name)?;
+        col += 1;
+        if has_student_id {
+            sheet.write_string(row, col, student.id.as_deref().unwrap_or(""))?;
+            col += 1;
+        }
+        sheet.write(row, col, (class.id + 1) as f64)?;
+        sheet.write(row, col + 1, room.number as f64)?;
+        sheet.write(row, col + 2, (*seat + 1) as f64)?;
+    }
+
+    // 工作表2: 座位表，各考场依次向下排列，讲台在上
+    let seat_format = Format::new()
+        .set_align(FormatAlign::Center)
+        .set_text_wrap()
+        .set_border(FormatBorder::Thin);
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("座位表")?;
+    let max_columns = plan
+        .rooms
+        .iter()
+        .map(|room| room.columns)
+        .max()
+        .unwrap_or(1);
+    for col in 0..max_columns {
+        sheet.set_column_width((col + 1) as u16, 14)?;
+    }
+    let mut row = 0u32;
+    for room in &plan.rooms {
+        let last_col = room.columns as u16;
+        let title = format!("第 {} 考场（{} 人）", room.number, room.seats.len());
+        let podium_format = header_format.clone().set_align(FormatAlign::Center);
+        if last_col > 1 {
+            sheet.merge_range(row, 0, row, last_col, &title, &title_format)?;
+            sheet.merge_range(row + 1, 1, row + 1, last_col, "讲台", &podium_format)?;
+        } else {
+            sheet.write_with_format(row, 0, &title, &title_format)?;
+            sheet.write_with_format(row + 1, 1, "讲台", &podium_format)?;
+        }
+        let grid_row = row + 2;
+        for r in 0..room.rows() {
+            sheet.write_with_format(
+                grid_row + r as u32,
+                0,
+                format!("第 {} 排", r + 1),
+                &header_format,
+            )?;
+        }
+        for (seat, exam_seat) in room.seats.iter().enumerate() {
+            let (r, c) = room.position(seat);
+            let class = &classes[exam_seat.class];
+            let text = format!(
+                "{} {}\n{}班",
+                seat + 1,
+                class.students[exam_seat.student].name,
+                class.id + 1
+            );
+            sheet.write_with_format(grid_row + r as u32, (c + 1) as u16, text, &seat_format)?;
+        }
+        row = grid_row + room.rows() as u32 + 1;
+    }
+    if plan.conflicts > 0 {
+        sheet.write_string(
+            row,
+            0,
+            format!("有 {} 对同班考生相邻，无法完全避开", plan.conflicts),
+        )?;
+    }
+
+    // 各考场门贴：座位号顺序的考生名单
+    for room in &plan.rooms {
+        let sheet = workbook.add_worksheet();
+        sheet.set_name(format!("第{}考场", room.number))?;
+        sheet.merge_range(
+            0,
+            0,
+            0,
+            3,
+            &format!("第 {} 考场考生名单（{} 人）", room.number, room.seats.len()),
+            &title_format,
+        )?;
+        for (col, header) in ["座位号", "考号", "姓名", "班级"].iter().enumerate() {
+            sheet.write_with_format(1, col as u16, *header, &header_format)?;
+        }
+        for (seat, exam_seat) in room.seats.iter().enumerate() {
+            let row = (seat + 2) as u32;
+            let class = &classes[exam_seat.class];
+            sheet.write(row, 0, (seat + 1) as f64)?;
+            sheet.write_string(row, 1, room.exam_number(seat))?;
+            sheet.write_string(row, 2, &class.students[exam_seat.student].name)?;
+            sheet.write(row, 3, (class.id + 1) as f64)?;
+        }
+    }
+
+    workbook.save(file_path)?;
+    Ok(())
+}
+
+/// 导出宿舍分配：“宿舍分配”按班级列出每个学生的宿舍与床位，“宿舍名单”按房间列出入住学生，
+/// 有未分配的学生时另附“未分配”表
+pub fn export_dorm_plan(classes: &[Class], plan: &DormPlan, file_path: &str) -> anyhow::Result<()> {
+    let mut workbook = Workbook::new();
+    let header_format = Format::new()
+        .set_bold()
+        .set_background_color(rust_xlsxwriter::Color::RGB(0xD9E1F2));
+    let title_format = Format::new().set_bold();
+    let has_student_id = has_real_student_ids(classes);
+    let student_of = |resident: &Resident| &classes[resident.class].students[resident.student];
+    let class_label = |class: usize| format!("{}班", classes[class].id + 1);
+
+    // 工作表1: 宿舍分配，按班级、班内顺序排列
+    let mut entries: Vec<(usize, usize)> = plan
+        .rooms
+        .iter()
+        .enumerate()
+        .flat_map(|(room, assignment)| (0..assignment.residents.len()).map(move |bed| (room, bed)))
+        .collect();
+    entries.sort_by_key(|&(room, bed)| {
+        let resident = plan.rooms[room].residents[bed];
+        (classes[resident.class].id, resident.student)
+    });
+
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("宿舍分配")?;
+    let mut headers = vec!["姓名"];
+    if has_student_id {
+        headers.push("学号");
+    }
+    headers.extend(["性别", "班级", "楼栋", "楼层", "房间号", "床位号"]);
+    for (col, header) in headers.iter().enumerate() {
+        sheet.write_with_format(0, col as u16, *header, &header_format)?;
+    }
+    for (idx, &(room, bed)) in entries.iter().enumerate() {
+        let row = (idx + 1) as u32;
+        let assignment = &plan.rooms[room];
+        let resident = assignment.residents[bed];
+        let student = student_of(&resident);
+        let mut col = 0u16;
+        sheet.write_string(row, col, &student.name)?;
+        col += 1;
+        if has_student_id {
+            sheet.write_string(row, col, student.id.as_deref().unwrap_or(""))?;
+            col += 1;
+        }
+        sheet.write_string(row, col, student.gender.label())?;
+        sheet.write(row, col + 1, (classes[resident.class].id + 1) as f64)?;
+        sheet.write_string(row, col + 2, &assignment.room.building)?;
+        sheet.write_string(row, col + 3, &assignment.room.floor)?;
+        sheet.write_string(row, col + 4, &assignment.room.number)?;
+        sheet.write(row, col + 5, (bed + 1) as f64)?;
+    }
+
+    // 工作表2: 宿舍名单，每个房间一块：房间信息一行，其后为各床位的学生
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("宿舍名单")?;
+    sheet.set_column_width(0, 24)?;
+    let mut row = 0u32;
+    for assignment in &plan.rooms {
+        let room_classes: Vec<String> = assignment.classes().into_iter().map(class_label).collect();
+        sheet.write_with_format(
+            row,
+            0,
+            format!(
+                "{}（{}，{}/{} 人，{}）",
+                assignment.room.label(),
+                assignment.gender.label(),
+                assignment.residents.len(),
+                assignment.room.capacity,
+                room_classes.join("、")
+            ),
+            &title_format,
+        )?;
+        for (col, header) in ["床位号", "姓名", "班级"].iter().enumerate() {
+            sheet.write_with_format(row + 1, col as u16, *header, &header_format)?;
+        }
+        for (bed, resident) in assignment.residents.iter().enumerate() {
+            let bed_row = row + 2 + bed as u32;
+            sheet.write(bed_row, 0, (bed + 1) as f64)?;
+            sheet.write_string(bed_row, 1, &student_of(resident).name)?;
+            sheet.write_string(bed_row, 2, class_label(resident.class))?;
+        }
+        row += assignment.residents.len() as u32 + 3;
+    }
+
+    // 工作表3: 未分配的学生
+    if !plan.unassigned.is_empty() {
+        let sheet = workbook.add_worksheet();
+        sheet.set_name("未分配")?;
+        for (col, header) in ["姓名", "性别", "班级"].iter().enumerate() {
+            sheet.write_with_format(0, col as u16, *header, &header_format)?;
+        }
+        for (idx, resident) in plan.unassigned.iter().enumerate() {
+            let row = (idx + 1) as u32;
+            let student = student_of(resident);
+            sheet.write_string(row, 0, &student.name)?;
+            sheet.write_string(row, 1, student.gender.label())?;
+            sheet.write(row, 2, (classes[resident.class].id + 1) as f64)?;
+        }
     }
 
     workbook.save(file_path)?;
@@ -371,9 +1107,12 @@ pub fn read_from_csv(file_path: &str, config: &ColumnConfig) -> anyhow::Result<V
                 Some(format!("R{}", row_idx + 1))
             };
 
-            // 读取性别
-            let gender_str = record.get(config.gender_column)?.trim();
-            let gender = gender_str.parse::<Gender>().ok()?;
+            // 读取性别与身份证号
+            let (gender, national_id, birth_date, identity_issue) = read_identity(
+                config,
+                config.gender_column.and_then(|col| record.get(col)),
+                config.national_id_column.and_then(|col| record.get(col)),
+            );
 
             // 读取科目成绩
             let mut scores = HashMap::with_capacity(config.subject_columns.len());
@@ -392,7 +1131,7 @@ pub fn read_from_csv(file_path: &str, config: &ColumnConfig) -> anyhow::Result<V
                     .and_then(|s| s.trim().parse::<f64>().ok())
                     .unwrap_or(0.0)
             } else {
-                scores.values().sum()
+                Student::sum_scores(&scores)
             };
 
             // 读取额外字段
@@ -403,6 +1142,32 @@ pub fn read_from_csv(file_path: &str, config: &ColumnConfig) -> anyhow::Result<V
                 }
             }
 
+            // 读取原班级
+            let original_class = config
+                .original_class_column
+                .and_then(|col| record.get(col))
+                .and_then(parse_class_number);
+
+            // 读取选科组合
+            let combination = config
+                .combination_column
+                .and_then(|col| record.get(col))
+                .map(normalize_combination)
+                .filter(|value| !value.is_empty());
+
+            // 读取好友志愿
+            let preferences = config
+                .preference_column
+                .and_then(|col| record.get(col))
+                .map(parse_preferences)
+                .unwrap_or_default();
+
+            // 读取身高
+            let height = config
+                .height_column
+                .and_then(|col| record.get(col))
+                .and_then(parse_height);
+
             Some(Student {
                 name,
                 id: student_id,
@@ -410,6 +1175,13 @@ pub fn read_from_csv(file_path: &str, config: &ColumnConfig) -> anyhow::Result<V
                 scores,
                 total_score,
                 extra_fields,
+                original_class,
+                combination,
+                national_id,
+                birth_date,
+                identity_issue,
+                preferences,
+                height,
             })
         })
         .collect();
@@ -421,6 +1193,83 @@ pub fn read_from_csv(file_path: &str, config: &ColumnConfig) -> anyhow::Result<V
     Ok(students)
 }
 
+/// 导入摘要：学生总数、各性别的人数、身份证号核对结果、重名情况及好友志愿
+pub fn import_summary(students: &[Student]) -> String {
+    let count = |gender: Gender| students.iter().filter(|s| s.gender == gender).count();
+    let mut summary = format!(
+        "共读取 {} 名学生：男 {} 人，女 {} 人，性别未知 {} 人",
+        students.len(),
+        count(Gender::Male),
+        count(Gender::Female),
+        count(Gender::Unknown)
+    );
+
+    let issues = |matches: fn(&IdentityIssue) -> bool| {
+        students
+            .iter()
+            .filter(|s| s.identity_issue.as_ref().is_some_and(matches))
+            .count()
+    };
+    if students.iter().any(|s| s.national_id.is_some()) {
+        summary.push_str(&format!(
+            "；身份证号无效 {} 人，性别与身份证号不符 {} 人",
+            issues(|issue| matches!(issue, IdentityIssue::Invalid(_))),
+            issues(|issue| matches!(issue, IdentityIssue::GenderMismatch { .. }))
+        ));
+    }
+
+    let exact = duplicate_name_groups(students, NameMatch::Exact);
+    // 同音组中只列出写法不同的，同名同姓已单独列出
+    let homophones: Vec<Vec<usize>> = duplicate_name_groups(students, NameMatch::Pinyin)
+        .into_iter()
+        .filter(|group| {
+            let first = name_key(&students[group[0]].name, NameMatch::Exact);
+            group
+                .iter()
+                .any(|&idx| name_key(&students[idx].name, NameMatch::Exact) != first)
+        })
+        .collect();
+    for (label, groups) in [("同名同姓", &exact), ("同音", &homophones)] {
+        if groups.is_empty() {
+            continue;
+        }
+        let shown: Vec<String> = groups
+            .iter()
+            .take(MAX_LISTED_NAME_GROUPS)
+            .map(|group| describe_group(students, group))
+            .collect();
+        let more = if groups.len() > MAX_LISTED_NAME_GROUPS {
+            " 等"
+        } else {
+            ""
+        };
+        summary.push_str(&format!(
+            "；{} {} 组：{}{}",
+            label,
+            groups.len(),
+            shown.join("、"),
+            more
+        ));
+    }
+
+    if students.iter().any(|s| !s.preferences.is_empty()) {
+        let refs: Vec<&Student> = students.iter().collect();
+        let (requests, unresolved) = resolve_requests(&refs);
+        summary.push_str(&format!("；好友志愿 {} 条", requests.len()));
+        if !unresolved.is_empty() {
+            summary.push_str(&format!(
+                "，{} 条找不到对应学生（如 {}）",
+                unresolved.len(),
+                unresolved[0]
+            ));
+        }
+    }
+    summary
+}
+
+/// 导入摘要中最多列出的重名组数
+const MAX_LISTED_NAME_GROUPS: usize = 5;
+
 /// 导出分班结果到 CSV（带额外字段）
 pub fn export_to_csv(
     classes: &[Class],
@@ -433,14 +1282,52 @@ pub fn export_to_csv(
 
     // 检查是否有真实学号
     let has_student_id = has_real_student_ids(classes);
+    let has_original_class = has_original_classes(classes);
+    let has_tier = has_tiers(classes);
+    let has_combination = has_combinations(classes);
+    let has_study_group = has_study_groups(classes);
+    let has_height = has_heights(classes);
+    let has_seat = has_seating(classes);
+    let has_national_id = has_national_ids(classes);
+    let has_identity_issue = has_identity_issues(classes);
+    let preferences = preference_report(classes);
 
     // 写入表头
     let mut headers = vec!["班级"];
+    if has_tier {
+        headers.push("层次");
+    }
+    if has_combination {
+        headers.push("选科组合");
+    }
+    if has_study_group {
+        headers.push("小组");
+    }
+    if has_seat {
+        headers.push("座位");
+    }
     if has_student_id {
         headers.push("学号");
     }
     headers.push("姓名");
     headers.push("性别");
+    if has_height {
+        headers.push("身高");
+    }
+    if has_original_class {
+        headers.push("原班级");
+    }
+    if has_national_id {
+        headers.push("身份证号");
+        headers.push("出生日期");
+    }
+    if has_identity_issue {
+        headers.push("身份证核对");
+    }
+    if preferences.is_some() {
+        headers.push("好友志愿");
+        headers.push("志愿满足");
+    }
     headers.extend(extra_field_names.iter().copied());
     headers.extend(subjects.iter().copied());
     headers.push("总分");
@@ -448,13 +1335,33 @@ pub fn export_to_csv(
     wtr.write_record(&headers)?;
 
     // 写入学生数据
-    for class in classes {
-        for student in &class.students {
+    for (class_idx, class) in classes.iter().enumerate() {
+        for (student_idx, student) in class.students.iter().enumerate() {
             let mut record = Vec::new();
 
             // 班级（从 1 开始）
             record.push((class.id + 1).to_string());
 
+            // 层次（分层分班）
+            if has_tier {
+                record.push(class.tier.clone().unwrap_or_default());
+            }
+
+            // 选科组合
+            if has_combination {
+                record.push(student.combination.clone().unwrap_or_default());
+            }
+
+            // 小组
+            if has_study_group {
+                record.push(study_group_label(class, student_idx));
+            }
+
+            // 座位
+            if has_seat {
+                record.push(seat_label(class, student_idx));
+            }
+
             // 学号（仅当有真实学号时）
             if has_student_id {
                 let student_id = student.id.as_deref().unwrap_or("");
@@ -465,11 +1372,33 @@ pub fn export_to_csv(
             record.push(student.name.clone());
 
             // 性别
-            record.push(if student.gender == Gender::Male {
-                "男".to_string()
-            } else {
-                "女".to_string()
-            });
+            record.push(student.gender.label().to_string());
+
+            // 身高
+            if has_height {
+                record.push(student.height.map(|h| h.to_string()).unwrap_or_default());
+            }
+
+            // 原班级（微调模式）
+            if has_original_class {
+                record.push(original_class_label(student));
+            }
+
+            // 身份证号与出生日期
+            if has_national_id {
+                record.push(student.national_id.clone().unwrap_or_default());
+                record.push(birth_date_label(student));
+            }
+            if has_identity_issue {
+                record.push(identity_issue_label(student));
+            }
+
+            // 好友志愿及满足情况
+            if let Some(report) = &preferences {
+                let satisfied = report.satisfied_by_student[class_idx][student_idx];
+                record.push(format_preferences(&student.preferences));
+                record.push(satisfied_label(satisfied));
+            }
 
             // 额外字段
             for field_name in extra_field_names {
@@ -498,6 +1427,55 @@ pub fn export_to_csv(
     Ok(())
 }
 
+/// 导出收敛轨迹到 CSV
+pub fn export_trace_to_csv(trace: &[TracePoint], file_path: &str) -> anyhow::Result<()> {
+    let file = fs::File::create(file_path)?;
+    let mut wtr = CsvWriter::from_writer(file);
+
+    wtr.write_record([
+        "实例",
+        "迭代次数",
+        "温度",
+        "当前代价",
+        "最优代价",
+        "接受率",
+        "重新加热次数",
+        "总分达标",
+        "单科达标",
+        "性别达标",
+        "人数达标",
+        "重名达标",
+    ])?;
+
+    let flag = |ok: bool| if ok { "是" } else { "否" }.to_string();
+    for point in trace {
+        wtr.write_record([
+            (point.instance + 1).to_string(),
+            point.iteration.to_string(),
+            format!("{:.6}", point.temperature),
+            format!("{:.6}", point.current_cost),
+            format!("{:.6}", point.best_cost),
+            format!("{:.4}", point.acceptance_rate),
+            point.reheats.to_string(),
+            flag(point.constraints.score),
+            flag(point.constraints.subject),
+            flag(point.constraints.gender),
+            flag(point.constraints.class_size),
+            flag(point.constraints.names),
+        ])?;
+    }
+
+    wtr.flush()?;
+    Ok(())
+}
+
+/// 导出收敛轨迹到 JSON
+pub fn export_trace_to_json(trace: &[TracePoint], file_path: &str) -> anyhow::Result<()> {
+    let content = serde_json::to_string_pretty(trace)?;
+    fs::write(file_path, content)?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -518,4 +1496,83 @@ mod tests {
         let empty_data = Data::Empty;
         assert_eq!(parse_score(&empty_data), 0.0);
     }
+
+    #[test]
+    fn test_normalize_combination() {
+        assert_eq!(normalize_combination("物化生"), "物化生");
+        assert_eq!(normalize_combination("物理+化学+生物"), "物化生");
+        assert_eq!(normalize_combination(" 历史、思想政治、地理 "), "历政地");
+        assert_eq!(normalize_combination("物 化 地"), "物化地");
+    }
+
+    #[test]
+    fn test_gender_aliases() {
+        let aliases = GenderAliases::default();
+        assert_eq!(aliases.parse("男"), Gender::Male);
+        assert_eq!(aliases.parse("\u{3000}男 "), Gender::Male);
+        assert_eq!(aliases.parse("男生"), Gender::Male);
+        assert_eq!(aliases.parse("m"), Gender::Male);
+        assert_eq!(aliases.parse("FEMALE"), Gender::Female);
+        assert_eq!(aliases.parse(""), Gender::Unknown);
+        assert_eq!(aliases.parse("其他"), Gender::Unknown);
+
+        let custom = GenderAliases {
+            male: vec!["1".to_string()],
+            female: vec!["2".to_string()],
+        };
+        assert_eq!(custom.parse("1"), Gender::Male);
+        assert_eq!(custom.parse("男"), Gender::Unknown);
+    }
+
+    #[test]
+    fn test_read_identity_without_gender_column() {
+        let config = ColumnConfig::builder()
+            .name(0)
+            .national_id(1)
+            .build()
+            .unwrap();
+        assert_eq!(config.gender_column, None);
+
+        let (gender, id, birth_date, issue) =
+            read_identity(&config, None, Some("11010519491231002x"));
+        assert_eq!(gender, Gender::Female);
+        assert_eq!(id.as_deref(), Some("11010519491231002X"));
+        assert_eq!(birth_date, NaiveDate::from_ymd_opt(1949, 12, 31));
+        assert!(issue.is_none());
+
+        let (gender, _, _, issue) = read_identity(&config, Some("男"), Some("11010519491231002X"));
+        assert_eq!(gender, Gender::Female);
+        assert!(matches!(issue, Some(IdentityIssue::GenderMismatch { .. })));
+
+        assert!(ColumnConfig::builder().name(0).build().is_err());
+    }
+
+    #[test]
+    fn test_import_summary_lists_duplicate_names() {
+        let students: Vec<Student> = ["张伟", "李娜", "张伟", "李纳", "王芳"]
+            .iter()
+            .map(|name| Student::new(name.to_string(), Gender::Female, HashMap::new()))
+            .collect();
+        let summary = import_summary(&students);
+        assert!(summary.contains("同名同姓 1 组：张伟×2"));
+        assert!(summary.contains("同音 1 组：李娜/李纳×2"));
+    }
+
+    #[test]
+    fn test_parse_class_number() {
+        assert_eq!(parse_class_number("3"), Some(2));
+        assert_eq!(parse_class_number("12班"), Some(11));
+        assert_eq!(parse_class_number("高一(3)班"), Some(2));
+        assert_eq!(parse_class_number("0"), None);
+        assert_eq!(parse_class_number("未分班"), None);
+    }
+
+    #[test]
+    fn test_parse_height() {
+        assert_eq!(parse_height("165"), Some(165.0));
+        assert_eq!(parse_height(" 172.5cm "), Some(172.5));
+        assert!((parse_height("1.65米").unwrap() - 165.0).abs() < 1e-9);
+        assert_eq!(parse_height("未测"), None);
+        assert_eq!(parse_height("0"), None);
+    }
 }
@@ -1,14 +1,50 @@
 pub mod algorithm;
+pub mod dorm;
+pub mod electives;
+pub mod exam;
+pub mod explore;
+pub mod groups;
 pub mod history;
+pub mod identity;
 pub mod io;
 pub mod model;
+pub mod names;
+pub mod objective;
+pub mod partition;
+pub mod preferences;
+pub mod seating;
 pub mod stats;
+pub mod tiers;
 
 // 导出核心功能
-pub use algorithm::{ConstraintValidation, DivideConfig, divide, validate_constraints};
+pub use algorithm::{
+    AlternativeConfig, ConstraintFlags, ConstraintValidation, CostBreakdown, DivideConfig,
+    DivideResult, SeedingStrategy, StopReason, TracePoint, assignment_distance, divide,
+    validate_constraints,
+};
+pub use dorm::{
+    DormAssignment, DormPlan, DormRoom, Resident, allocate_dorms, parse_room_inventory,
+};
+pub use electives::{
+    CombinationGroup, CombinationResult, divide_by_combination, parse_compatibility,
+    plan_combination_groups,
+};
+pub use exam::{ExamConfig, ExamOrder, ExamPlan, ExamRoom, ExamSeat, arrange_exam_rooms};
+pub use explore::{
+    SweepConfig, SweepEntry, SweepResult, Threshold, TightenConfig, TightenResult, auto_tighten,
+    sweep_class_counts,
+};
+pub use groups::{recommended_group_count, study_group_config};
+pub use identity::{IdentityIssue, NationalId, parse_national_id};
 pub use io::{ColumnConfig, ColumnConfigBuilder, export_to_excel, read_from_excel};
-pub use model::{Class, Gender, Student};
+pub use model::{Class, Gender, GenderAliases, Student};
+pub use names::{NameMatch, duplicate_name_groups};
+pub use objective::{Objective, SpreadObjective};
+pub use partition::{Partition, Partitionable, partition};
+pub use preferences::{Preference, PreferenceReport, parse_preferences, preference_report};
+pub use seating::{DeskMateRule, RoomLayout, SeatingChart, SeatingRules, arrange_seats};
 pub use stats::{
     DetailedStatistics, GenderBalance, Statistics, SubjectStatistics,
     calculate_detailed_statistics, calculate_statistics,
 };
+pub use tiers::{TierCutoff, TierOutcome, TierSpec, TieredResult, divide_tiered};
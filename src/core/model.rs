@@ -1,3 +1,7 @@
+use super::identity::{IdentityIssue, age_on};
+use super::preferences::Preference;
+use super::seating::SeatingChart;
+use chrono::NaiveDate;
 use rayon::prelude::*;
 use std::{collections::HashMap, str::FromStr};
 
@@ -6,16 +10,66 @@ use std::{collections::HashMap, str::FromStr};
 pub enum Gender {
     Male,
     Female,
+    /// 未填写或无法识别的性别，分班时不计入性别比例
+    Unknown,
+}
+
+impl Gender {
+    /// 界面与导出中显示的文字
+    pub fn label(self) -> &'static str {
+        match self {
+            Gender::Male => "男",
+            Gender::Female => "女",
+            Gender::Unknown => "未知",
+        }
+    }
 }
 
 impl FromStr for Gender {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "男" => Ok(Gender::Male),
-            "女" => Ok(Gender::Female),
-            _ => Err(anyhow::anyhow!("无效的性别: {}", s)),
+        match GenderAliases::default().parse(s) {
+            Gender::Unknown => Err(anyhow::anyhow!("无效的性别: {}", s)),
+            gender => Ok(gender),
+        }
+    }
+}
+
+/// 性别取值的别名表
+#[derive(Debug, Clone, PartialEq)]
+pub struct GenderAliases {
+    pub male: Vec<String>,
+    pub female: Vec<String>,
+}
+
+impl Default for GenderAliases {
+    fn default() -> Self {
+        let to_strings = |aliases: &[&str]| aliases.iter().map(|a| a.to_string()).collect();
+        Self {
+            male: to_strings(&["男", "男生", "男性", "M", "Male", "Boy"]),
+            female: to_strings(&["女", "女生", "女性", "F", "Female", "Girl"]),
+        }
+    }
+}
+
+impl GenderAliases {
+    /// 按别名表解析性别，忽略首尾空白（含全角空格）与英文大小写；
+    /// 空白或不在表中的取值视为未知
+    pub fn parse(&self, value: &str) -> Gender {
+        let value = value.trim();
+        let matches = |aliases: &[String]| {
+            !value.is_empty()
+                && aliases
+                    .iter()
+                    .any(|alias| alias.trim().eq_ignore_ascii_case(value))
+        };
+        if matches(&self.male) {
+            Gender::Male
+        } else if matches(&self.female) {
+            Gender::Female
+        } else {
+            Gender::Unknown
         }
     }
 }
@@ -29,11 +83,25 @@ pub struct Student {
     pub scores: HashMap<String, f64>,
     pub total_score: f64,
     pub extra_fields: HashMap<String, String>,
+    /// 原班级索引（从 0 开始），用于在已有分班基础上微调
+    pub original_class: Option<usize>,
+    /// 选科组合（如“物化生”），用于按选科组合组班
+    pub combination: Option<String>,
+    /// 身份证号（有效时为规范化后的号码）
+    pub national_id: Option<String>,
+    /// 由身份证号得出的出生日期
+    pub birth_date: Option<NaiveDate>,
+    /// 身份证号核对发现的问题
+    pub identity_issue: Option<IdentityIssue>,
+    /// 好友志愿：希望同班的学生
+    pub preferences: Vec<Preference>,
+    /// 身高（厘米），用于排座位
+    pub height: Option<f64>,
 }
 
 impl Student {
     pub fn new(name: String, gender: Gender, scores: HashMap<String, f64>) -> Self {
-        let total_score = scores.values().sum();
+        let total_score = Self::sum_scores(&scores);
         Self {
             name,
             id: None,
@@ -41,6 +109,13 @@ impl Student {
             scores,
             total_score,
             extra_fields: HashMap::new(),
+            original_class: None,
+            combination: None,
+            national_id: None,
+            birth_date: None,
+            identity_issue: None,
+            preferences: Vec::new(),
+            height: None,
         }
     }
 
@@ -53,6 +128,33 @@ impl Student {
         self.extra_fields = extra_fields;
         self
     }
+
+    pub fn with_original_class(mut self, original_class: Option<usize>) -> Self {
+        self.original_class = original_class;
+        self
+    }
+
+    pub fn with_combination(mut self, combination: Option<String>) -> Self {
+        self.combination = combination;
+        self
+    }
+
+    pub fn with_preferences(mut self, preferences: Vec<Preference>) -> Self {
+        self.preferences = preferences;
+        self
+    }
+
+    pub fn with_height(mut self, height: Option<f64>) -> Self {
+        self.height = height;
+        self
+    }
+
+    /// 按科目名顺序累加各科成绩，保证浮点求和结果与哈希顺序无关
+    pub fn sum_scores(scores: &HashMap<String, f64>) -> f64 {
+        let mut entries: Vec<(&String, &f64)> = scores.iter().collect();
+        entries.sort_unstable_by(|a, b| a.0.cmp(b.0));
+        entries.into_iter().map(|(_, score)| score).sum()
+    }
 }
 
 /// 班级数据结构
@@ -60,6 +162,14 @@ impl Student {
 pub struct Class {
     pub id: usize,
     pub students: Vec<Student>,
+    /// 分层分班时所属的层次（如“实验班”“平行班”）
+    pub tier: Option<String>,
+    /// 按选科组合组班时本班的选科组合（多个兼容组合以“/”分隔）
+    pub combination: Option<String>,
+    /// 分小组后每个学生所在小组的索引（与 `students` 顺序一致），未分小组时为 None
+    pub study_groups: Option<Vec<usize>>,
+    /// 座位表，未排座位时为 None
+    pub seating: Option<SeatingChart>,
 }
 
 impl Class {
@@ -67,9 +177,23 @@ impl Class {
         Self {
             id,
             students: Vec::with_capacity(64),
+            tier: None,
+            combination: None,
+            study_groups: None,
+            seating: None,
         }
     }
 
+    pub fn with_tier(mut self, tier: Option<String>) -> Self {
+        self.tier = tier;
+        self
+    }
+
+    pub fn with_combination(mut self, combination: Option<String>) -> Self {
+        self.combination = combination;
+        self
+    }
+
     #[inline]
     pub fn add_student(&mut self, student: Student) {
         self.students.push(student);
@@ -91,6 +215,24 @@ impl Class {
             .count()
     }
 
+    #[inline]
+    pub fn unknown_gender_count(&self) -> usize {
+        self.students
+            .par_iter()
+            .filter(|s| s.gender == Gender::Unknown)
+            .count()
+    }
+
+    /// 有出生日期的学生到 `on` 为止的平均年龄，没有出生日期时为 None
+    pub fn avg_age(&self, on: NaiveDate) -> Option<f64> {
+        let ages: Vec<f64> = self
+            .students
+            .iter()
+            .filter_map(|s| s.birth_date.map(|birth| age_on(birth, on)))
+            .collect();
+        (!ages.is_empty()).then(|| ages.iter().sum::<f64>() / ages.len() as f64)
+    }
+
     #[inline]
     pub fn avg_total_score(&self) -> f64 {
         if self.students.is_empty() {
@@ -153,13 +295,17 @@ impl Class {
             .collect()
     }
 
-    /// 获取性别比例
+    /// 获取性别比例（男生在已知性别学生中的比例，性别未知的学生不计入）
+    ///
+    /// 没有已知性别的学生时视为男女各半（0.5），与分班优化中的取值一致，
+    /// 避免这样的班级在校验与导出中显得性别失衡
     #[inline]
     pub fn gender_ratio(&self) -> f64 {
-        let total = self.students.len() as f64;
-        if total == 0.0 {
-            return 0.0;
+        let male = self.male_count();
+        let known = (male + self.female_count()) as f64;
+        if known == 0.0 {
+            return 0.5;
         }
-        self.male_count() as f64 / total
+        male as f64 / known
     }
 }
@@ -0,0 +1,107 @@
+use super::model::Student;
+use pinyin::ToPinyin;
+use std::collections::HashMap;
+
+/// 判定重名的方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum NameMatch {
+    /// 姓名完全相同（同名同姓）
+    Exact,
+    /// 姓名读音相同（不计声调），同时包含同名同姓与同音
+    Pinyin,
+}
+
+impl NameMatch {
+    pub fn label(self) -> &'static str {
+        match self {
+            NameMatch::Exact => "同名同姓",
+            NameMatch::Pinyin => "同名或同音",
+        }
+    }
+}
+
+/// 比较姓名用的键：去掉空白；按读音比较时转为不带声调的拼音，非汉字原样保留（英文转小写）
+pub fn name_key(name: &str, mode: NameMatch) -> String {
+    let name: String = name.chars().filter(|c| !c.is_whitespace()).collect();
+    match mode {
+        NameMatch::Exact => name,
+        NameMatch::Pinyin => name
+            .as_str()
+            .to_pinyin()
+            .zip(name.chars())
+            .map(|(pinyin, c)| match pinyin {
+                Some(pinyin) => pinyin.plain().to_string(),
+                None => c.to_lowercase().to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(" "),
+    }
+}
+
+/// 姓名键相同的学生分组，只返回至少两人的组，按组内第一个学生的顺序排列
+pub fn duplicate_name_groups(students: &[Student], mode: NameMatch) -> Vec<Vec<usize>> {
+    let mut by_key: HashMap<String, Vec<usize>> = HashMap::new();
+    for (idx, student) in students.iter().enumerate() {
+        by_key
+            .entry(name_key(&student.name, mode))
+            .or_default()
+            .push(idx);
+    }
+    let mut groups: Vec<Vec<usize>> = by_key
+        .into_values()
+        .filter(|group| group.len() > 1)
+        .collect();
+    groups.sort_unstable_by_key(|group| group[0]);
+    groups
+}
+
+/// 每个学生所在重名组的编号，不与他人重名的学生为 None
+pub(crate) fn name_group_ids(students: &[Student], mode: NameMatch) -> Vec<Option<usize>> {
+    let mut ids = vec![None; students.len()];
+    for (group_id, group) in duplicate_name_groups(students, mode).iter().enumerate() {
+        for &idx in group {
+            ids[idx] = Some(group_id);
+        }
+    }
+    ids
+}
+
+/// 重名组的显示文本，如“张伟×2”“李娜/李纳×2”
+pub fn describe_group(students: &[Student], group: &[usize]) -> String {
+    let mut names: Vec<&str> = Vec::new();
+    for &idx in group {
+        if !names.contains(&students[idx].name.as_str()) {
+            names.push(&students[idx].name);
+        }
+    }
+    format!("{}×{}", names.join("/"), group.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::model::Gender;
+
+    fn students(names: &[&str]) -> Vec<Student> {
+        names
+            .iter()
+            .map(|name| Student::new(name.to_string(), Gender::Male, HashMap::new()))
+            .collect()
+    }
+
+    #[test]
+    fn test_duplicate_name_groups() {
+        let students = students(&["张伟", "李娜", "张 伟", "章伟", "李纳", "王芳"]);
+
+        let exact = duplicate_name_groups(&students, NameMatch::Exact);
+        assert_eq!(exact, vec![vec![0, 2]]);
+        assert_eq!(describe_group(&students, &exact[0]), "张伟/张 伟×2");
+
+        let pinyin = duplicate_name_groups(&students, NameMatch::Pinyin);
+        assert_eq!(pinyin, vec![vec![0, 2, 3], vec![1, 4]]);
+        assert_eq!(
+            name_group_ids(&students, NameMatch::Pinyin),
+            vec![Some(0), Some(1), Some(0), Some(0), Some(1), None]
+        );
+    }
+}
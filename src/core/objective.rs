@@ -0,0 +1,180 @@
+use std::fmt;
+use std::sync::Arc;
+
+/// 自定义目标项，与内置的总分、性别、人数、科目等代价一起参与优化
+///
+/// 目标项自己维护按班级统计的状态：优化过程中每次调动学生都会先调用
+/// [`on_remove`](Objective::on_remove) 再调用 [`on_add`](Objective::on_add)，
+/// 之后通过 [`cost`](Objective::cost) 读取代价，因此这些方法应当是增量的、开销很小。
+/// 学生以输入切片中的索引表示，需要的学生数据由目标项自行持有（例如放在 `Arc` 中共享）。
+///
+/// 每个并行实例及其最优解各持有一份副本，先调用 [`reset`](Objective::reset)
+/// 清空状态，再对每个学生调用一次 `on_add`。
+pub trait Objective: Send + Sync {
+    /// 名称，用于代价分项与结果摘要
+    fn name(&self) -> &str;
+
+    /// 清空状态，准备统计 `num_classes` 个空班级
+    fn reset(&mut self, num_classes: usize);
+
+    /// 学生 `student` 加入 `class` 班
+    fn on_add(&mut self, student: usize, class: usize);
+
+    /// 学生 `student` 移出 `class` 班
+    fn on_remove(&mut self, student: usize, class: usize);
+
+    /// 当前分配下的代价（已乘权重），越小越好；计入软约束代价
+    fn cost(&self) -> f64;
+
+    /// 复制当前状态
+    fn clone_box(&self) -> Box<dyn Objective>;
+}
+
+impl Clone for Box<dyn Objective> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+impl fmt::Debug for dyn Objective {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Objective")
+            .field("name", &self.name())
+            .field("cost", &self.cost())
+            .finish()
+    }
+}
+
+/// 只对部分学生分班时包装目标项：组内学生的索引换算为全体学生中的索引后再交给内部目标项
+///
+/// 分层、按选科组合分班时各组学生从 0 重新编号，而目标项是按全体学生的索引构造的
+#[derive(Debug, Clone)]
+pub(crate) struct SubsetObjective {
+    inner: Box<dyn Objective>,
+    /// `indices[i]`：组内第 i 个学生在全体学生中的索引
+    indices: Arc<[usize]>,
+}
+
+impl SubsetObjective {
+    pub(crate) fn new(inner: Box<dyn Objective>, indices: Arc<[usize]>) -> Self {
+        Self { inner, indices }
+    }
+}
+
+impl Objective for SubsetObjective {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn reset(&mut self, num_classes: usize) {
+        self.inner.reset(num_classes);
+    }
+
+    fn on_add(&mut self, student: usize, class: usize) {
+        self.inner.on_add(self.indices[student], class);
+    }
+
+    fn on_remove(&mut self, student: usize, class: usize) {
+        self.inner.on_remove(self.indices[student], class);
+    }
+
+    fn cost(&self) -> f64 {
+        self.inner.cost()
+    }
+
+    fn clone_box(&self) -> Box<dyn Objective> {
+        Box::new(self.clone())
+    }
+}
+
+/// 把带标记的学生（如班干部、特长生、住校生）均匀分散到各班
+///
+/// 代价为各班标记人数的方差乘以权重
+#[derive(Debug, Clone)]
+pub struct SpreadObjective {
+    name: String,
+    /// `flags[i]`：第 i 个学生是否带标记
+    flags: Arc<[bool]>,
+    weight: f64,
+    /// 各班的标记人数
+    counts: Vec<usize>,
+}
+
+impl SpreadObjective {
+    pub fn new(name: impl Into<String>, flags: Vec<bool>, weight: f64) -> Self {
+        Self {
+            name: name.into(),
+            flags: Arc::from(flags),
+            weight,
+            counts: Vec::new(),
+        }
+    }
+
+    /// 各班的标记人数
+    pub fn counts(&self) -> &[usize] {
+        &self.counts
+    }
+}
+
+impl Objective for SpreadObjective {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn reset(&mut self, num_classes: usize) {
+        self.counts = vec![0; num_classes];
+    }
+
+    fn on_add(&mut self, student: usize, class: usize) {
+        if self.flags[student] {
+            self.counts[class] += 1;
+        }
+    }
+
+    fn on_remove(&mut self, student: usize, class: usize) {
+        if self.flags[student] {
+            self.counts[class] -= 1;
+        }
+    }
+
+    fn cost(&self) -> f64 {
+        if self.counts.is_empty() {
+            return 0.0;
+        }
+        let n = self.counts.len() as f64;
+        let mean = self.counts.iter().sum::<usize>() as f64 / n;
+        let variance = self
+            .counts
+            .iter()
+            .map(|&count| (count as f64 - mean).powi(2))
+            .sum::<f64>()
+            / n;
+        variance * self.weight
+    }
+
+    fn clone_box(&self) -> Box<dyn Objective> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spread_objective_tracks_counts() {
+        let mut objective = SpreadObjective::new("班干部", vec![true, false, true, true], 2.0);
+        objective.reset(2);
+        for (student, class) in [(0, 0), (1, 0), (2, 0), (3, 1)] {
+            objective.on_add(student, class);
+        }
+        assert_eq!(objective.counts(), &[2, 1]);
+        assert_eq!(objective.cost(), 0.5);
+
+        let mut copy: Box<dyn Objective> = Box::new(objective.clone());
+        copy.on_remove(2, 0);
+        copy.on_add(2, 1);
+        assert_eq!(copy.cost(), 0.5);
+        assert_eq!(objective.counts(), &[2, 1]);
+    }
+}
@@ -0,0 +1,275 @@
+use super::algorithm::{
+    ConstraintFlags, CostBreakdown, DivideConfig, StopReason, TracePoint, checked_warm_start,
+    search,
+};
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+/// 可以均衡分组的项目
+///
+/// 分班算法本身与学生无关：它把项目分成若干组，使各组在数值特征上的平均值接近，
+/// 在类别特征上的构成比例接近。实现这个 trait 即可复用同一套优化，例如把教师分成年级组、
+/// 把试卷分成阅卷批次。
+///
+/// 与分班的对应关系：第一个数值特征对应总分（受 `max_score_diff` 约束），其余数值特征
+/// 对应各科成绩（受 `max_subject_score_diff` 约束），类别特征对应性别（受
+/// `max_gender_ratio_diff` 约束）；代价分项沿用分班的名称。
+pub trait Partitionable {
+    /// 需要在各组间均衡平均值的数值特征，第一个为主要指标；所有项目的个数必须相同
+    fn numeric_features(&self) -> Vec<f64>;
+
+    /// 需要在各组间均匀分散的类别特征，取值为从 0 开始的类别编号，None 表示未知（不计入比例）；
+    /// 所有项目的个数必须相同，第一个类别特征还用于引导交换
+    fn categorical_features(&self) -> Vec<Option<usize>>;
+}
+
+/// 按项目索引整理的特征，供搜索使用
+#[derive(Debug, Clone, Default)]
+pub(crate) struct FeatureTable {
+    /// `numeric[i]`：第 i 个项目的数值特征
+    pub numeric: Vec<Vec<f64>>,
+    /// `categories[i]`：第 i 个项目的类别特征
+    pub categories: Vec<Vec<Option<usize>>>,
+    /// 每个类别特征的取值个数
+    pub category_sizes: Vec<usize>,
+}
+
+impl FeatureTable {
+    /// 收集各项目的特征；各项目的特征个数不一致时返回错误
+    pub fn new<T: Partitionable>(items: &[T]) -> anyhow::Result<Self> {
+        let numeric: Vec<Vec<f64>> = items.iter().map(|item| item.numeric_features()).collect();
+        let categories: Vec<Vec<Option<usize>>> = items
+            .iter()
+            .map(|item| item.categorical_features())
+            .collect();
+        let numeric_count = numeric.first().map_or(0, Vec::len);
+        let category_count = categories.first().map_or(0, Vec::len);
+        if let Some(idx) = numeric
+            .iter()
+            .position(|values| values.len() != numeric_count)
+        {
+            anyhow::bail!(
+                "第 {} 个项目有 {} 个数值特征，与第 1 个项目的 {} 个不一致",
+                idx + 1,
+                numeric[idx].len(),
+                numeric_count
+            );
+        }
+        if let Some(idx) = categories
+            .iter()
+            .position(|values| values.len() != category_count)
+        {
+            anyhow::bail!(
+                "第 {} 个项目有 {} 个类别特征，与第 1 个项目的 {} 个不一致",
+                idx + 1,
+                categories[idx].len(),
+                category_count
+            );
+        }
+
+        let category_sizes = (0..category_count)
+            .map(|c| {
+                categories
+                    .iter()
+                    .filter_map(|values| values[c])
+                    .max()
+                    .map_or(1, |max| max + 1)
+            })
+            .collect();
+        Ok(Self {
+            numeric,
+            categories,
+            category_sizes,
+        })
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.numeric.len()
+    }
+
+    /// 数值特征个数
+    #[inline]
+    pub fn numeric_count(&self) -> usize {
+        self.numeric.first().map_or(0, Vec::len)
+    }
+
+    /// 主要指标（第一个数值特征），没有数值特征时为 0
+    #[inline]
+    pub fn primary(&self, idx: usize) -> f64 {
+        self.numeric[idx].first().copied().unwrap_or(0.0)
+    }
+
+    /// 第一个类别特征的取值个数，没有类别特征时为 0
+    #[inline]
+    pub fn primary_category_size(&self) -> usize {
+        self.category_sizes.first().copied().unwrap_or(0)
+    }
+
+    /// 第一个类别特征的取值
+    #[inline]
+    pub fn primary_category(&self, idx: usize) -> Option<usize> {
+        self.categories[idx].first().copied().flatten()
+    }
+
+    /// 第一个类别特征各取值在已知项目中的占比，作为各组的目标比例
+    pub fn primary_category_shares(&self) -> Vec<f64> {
+        let size = self.primary_category_size();
+        let mut counts = vec![0usize; size];
+        for idx in 0..self.len() {
+            if let Some(value) = self.primary_category(idx) {
+                counts[value] += 1;
+            }
+        }
+        let known: usize = counts.iter().sum();
+        counts
+            .iter()
+            .map(|&count| {
+                if known == 0 {
+                    1.0 / size as f64
+                } else {
+                    count as f64 / known as f64
+                }
+            })
+            .collect()
+    }
+}
+
+/// 通用分组的结果
+#[derive(Debug, Clone)]
+pub struct Partition {
+    /// 每个项目所在组的索引（与输入顺序一致）
+    pub assignments: Vec<usize>,
+    pub num_groups: usize,
+    /// 代价分项
+    pub cost: CostBreakdown,
+    /// 各项硬约束是否满足
+    pub constraints: ConstraintFlags,
+    /// 每个实例执行的迭代次数
+    pub iterations: usize,
+    /// 并行实例数
+    pub num_instances: usize,
+    pub elapsed: Duration,
+    pub winning_instance: usize,
+    pub seed: u64,
+    pub stop_reason: StopReason,
+    pub trace: Vec<TracePoint>,
+    /// 设置原分组时相对原分组调动的项目数
+    pub moved: Option<usize>,
+    /// 各自定义目标项的名称与代价
+    pub objective_costs: Vec<(String, f64)>,
+    /// 备选方案，按代价升序
+    pub alternatives: Vec<Partition>,
+    /// 帕累托前沿，按主要指标最大差值升序
+    pub pareto_front: Vec<Partition>,
+    /// 运行前发现并已忽略的无效设置（如与项目数不匹配的热启动分配）
+    pub warnings: Vec<String>,
+}
+
+impl Partition {
+    /// 各组包含的项目索引
+    pub fn groups(&self) -> Vec<Vec<usize>> {
+        let mut groups = vec![Vec::new(); self.num_groups];
+        for (idx, &group) in self.assignments.iter().enumerate() {
+            groups[group].push(idx);
+        }
+        groups
+    }
+}
+
+/// 把项目均衡地分成 `config.num_classes` 组
+///
+/// `config` 的含义与分班相同；重名、好友志愿等与学生相关的设置只在
+/// [`divide`](super::algorithm::divide) 中生效。各项目的特征个数不一致时返回错误
+pub fn partition<T: Partitionable>(items: &[T], config: DivideConfig) -> anyhow::Result<Partition> {
+    partition_with_cancel(items, config, CancellationToken::new())
+}
+
+/// 通用分组（支持取消）
+pub fn partition_with_cancel<T: Partitionable>(
+    items: &[T],
+    config: DivideConfig,
+    cancel_token: CancellationToken,
+) -> anyhow::Result<Partition> {
+    let table = FeatureTable::new(items)?;
+    let (config, warnings) = checked_warm_start(config, items.len());
+    let mut partition = search(&table, &config, cancel_token, Default::default());
+    partition.warnings.splice(0..0, warnings);
+    Ok(partition)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::algorithm::OptimizationParams;
+
+    /// 阅卷批次：按题量均衡，来自同一学校的试卷分散
+    struct Paper {
+        pages: f64,
+        school: usize,
+    }
+
+    impl Partitionable for Paper {
+        fn numeric_features(&self) -> Vec<f64> {
+            vec![self.pages]
+        }
+
+        fn categorical_features(&self) -> Vec<Option<usize>> {
+            vec![Some(self.school)]
+        }
+    }
+
+    #[test]
+    fn test_partition_balances_generic_items() {
+        let papers: Vec<Paper> = (0..60)
+            .map(|i| Paper {
+                pages: 4.0 + ((i * 7) % 9) as f64,
+                school: i % 3,
+            })
+            .collect();
+        let params = OptimizationParams {
+            num_parallel_instances: Some(2),
+            ..Default::default()
+        };
+        let result = partition(
+            &papers,
+            DivideConfig::new(4)
+                .with_optimization_params(params)
+                .with_seed(Some(3))
+                .with_iterations(20_000),
+        )
+        .unwrap();
+
+        assert_eq!(result.assignments.len(), 60);
+        assert!(result.constraints.all());
+        for group in result.groups() {
+            assert_eq!(group.len(), 15);
+            for school in 0..3 {
+                let count = group
+                    .iter()
+                    .filter(|&&i| papers[i].school == school)
+                    .count();
+                assert!((4..=6).contains(&count));
+            }
+        }
+    }
+
+    #[test]
+    fn test_inconsistent_features_are_rejected() {
+        struct Ragged(usize);
+
+        impl Partitionable for Ragged {
+            fn numeric_features(&self) -> Vec<f64> {
+                vec![1.0; 1 + self.0 % 2]
+            }
+
+            fn categorical_features(&self) -> Vec<Option<usize>> {
+                Vec::new()
+            }
+        }
+
+        let items: Vec<Ragged> = (0..4).map(Ragged).collect();
+        let error = partition(&items, DivideConfig::new(2)).unwrap_err();
+        assert!(error.to_string().contains("第 2 个项目"));
+    }
+}
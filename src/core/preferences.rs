@@ -0,0 +1,281 @@
+use super::model::{Class, Student};
+use std::collections::HashMap;
+
+/// 学生填写的一条好友志愿
+#[derive(Debug, Clone, PartialEq)]
+pub struct Preference {
+    /// 希望同班的学生（学号或姓名）
+    pub target: String,
+    /// 权重，越大越优先满足（默认 1）
+    pub weight: f64,
+}
+
+impl Preference {
+    pub fn new(target: String, weight: f64) -> Self {
+        Self { target, weight }
+    }
+}
+
+/// 解析好友志愿单元格，如“S002:2、S003”
+///
+/// 多个志愿以逗号、顿号、分号或空白分隔；“学号:权重”（也可用全角冒号或 *）指定权重，
+/// 未指定或无法解析的权重按 1 计
+pub fn parse_preferences(value: &str) -> Vec<Preference> {
+    value
+        .split(|c: char| c.is_whitespace() || ",，、;；".contains(c))
+        .filter(|item| !item.is_empty())
+        .filter_map(|item| {
+            let (target, weight) = match item.split_once([':', '：', '*']) {
+                Some((target, weight)) => (target, weight.parse::<f64>().ok()),
+                None => (item, None),
+            };
+            let weight = weight.filter(|w| w.is_finite() && *w > 0.0).unwrap_or(1.0);
+            (!target.is_empty()).then(|| Preference::new(target.to_string(), weight))
+        })
+        .collect()
+}
+
+/// 志愿的显示文本，权重为 1 时省略
+pub fn format_preferences(preferences: &[Preference]) -> String {
+    preferences
+        .iter()
+        .map(|p| {
+            if p.weight == 1.0 {
+                p.target.clone()
+            } else {
+                format!("{}:{}", p.target, p.weight)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("、")
+}
+
+/// 按学生索引表示的好友志愿
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FriendRequest {
+    pub from: usize,
+    pub to: usize,
+    pub weight: f64,
+}
+
+/// 把志愿中的学号或姓名对应到学生索引
+///
+/// 先按学号匹配，再按姓名匹配（只匹配不重名的学生）；指向自己的志愿忽略。
+/// 返回可以对应的志愿，以及无法对应的志愿（“姓名→目标”）
+pub fn resolve_requests(students: &[&Student]) -> (Vec<FriendRequest>, Vec<String>) {
+    let mut by_id: HashMap<&str, usize> = HashMap::new();
+    let mut by_name: HashMap<&str, Option<usize>> = HashMap::new();
+    for (idx, student) in students.iter().enumerate() {
+        if let Some(id) = &student.id {
+            by_id.entry(id.as_str()).or_insert(idx);
+        }
+        by_name
+            .entry(student.name.as_str())
+            .and_modify(|entry| *entry = None)
+            .or_insert(Some(idx));
+    }
+
+    let mut requests = Vec::new();
+    let mut unresolved = Vec::new();
+    for (from, student) in students.iter().enumerate() {
+        for preference in &student.preferences {
+            let target = preference.target.as_str();
+            match by_id
+                .get(target)
+                .copied()
+                .or_else(|| by_name.get(target).copied().flatten())
+            {
+                Some(to) if to != from => requests.push(FriendRequest {
+                    from,
+                    to,
+                    weight: preference.weight,
+                }),
+                Some(_) => {}
+                None => unresolved.push(format!("{}→{}", student.name, target)),
+            }
+        }
+    }
+    (requests, unresolved)
+}
+
+/// 与学生相关的一条志愿（供优化时增量更新）
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RequestLink {
+    /// 志愿另一方的学生索引
+    pub other: usize,
+    pub weight: f64,
+    /// 是否为本人提出的志愿
+    pub outgoing: bool,
+}
+
+/// 按学生索引整理的志愿，优化时各实例共享
+#[derive(Debug)]
+pub(crate) struct PreferenceIndex {
+    /// `links[i]`：学生 i 提出的和指向学生 i 的志愿
+    pub links: Vec<Vec<RequestLink>>,
+    /// 每个学生提出的志愿数
+    pub request_counts: Vec<u32>,
+}
+
+impl PreferenceIndex {
+    /// 没有可对应的志愿时返回 None
+    pub fn new(students: &[Student]) -> Option<Self> {
+        let refs: Vec<&Student> = students.iter().collect();
+        let (requests, _) = resolve_requests(&refs);
+        if requests.is_empty() {
+            return None;
+        }
+
+        let mut links = vec![Vec::new(); students.len()];
+        let mut request_counts = vec![0; students.len()];
+        for request in requests {
+            links[request.from].push(RequestLink {
+                other: request.to,
+                weight: request.weight,
+                outgoing: true,
+            });
+            links[request.to].push(RequestLink {
+                other: request.from,
+                weight: request.weight,
+                outgoing: false,
+            });
+            request_counts[request.from] += 1;
+        }
+        Some(Self {
+            links,
+            request_counts,
+        })
+    }
+}
+
+/// 好友志愿的满足情况
+#[derive(Debug, Clone, PartialEq)]
+pub struct PreferenceReport {
+    /// 可以对应到学生的志愿数
+    pub requests: usize,
+    /// 已满足（与目标同班）的志愿数
+    pub satisfied: usize,
+    pub requested_weight: f64,
+    pub satisfied_weight: f64,
+    /// 提出了志愿但一条也未满足的学生（班级索引，姓名）
+    pub unmet_students: Vec<(usize, String)>,
+    /// 无法对应到学生的志愿（“姓名→目标”）
+    pub unresolved: Vec<String>,
+    /// `satisfied_by_student[班级][学生]`：已满足数与志愿数，未提出志愿为 None
+    pub satisfied_by_student: Vec<Vec<Option<(usize, usize)>>>,
+}
+
+impl PreferenceReport {
+    /// 按权重计算的满足率
+    pub fn satisfaction_rate(&self) -> f64 {
+        if self.requested_weight > 0.0 {
+            self.satisfied_weight / self.requested_weight
+        } else {
+            1.0
+        }
+    }
+}
+
+/// 统计分班结果中好友志愿的满足情况，没有学生填写志愿时返回 None
+pub fn preference_report(classes: &[Class]) -> Option<PreferenceReport> {
+    let placed: Vec<(usize, usize)> = classes
+        .iter()
+        .enumerate()
+        .flat_map(|(class_idx, class)| (0..class.students.len()).map(move |idx| (class_idx, idx)))
+        .collect();
+    let students: Vec<&Student> = placed
+        .iter()
+        .map(|&(class_idx, idx)| &classes[class_idx].students[idx])
+        .collect();
+    if students.iter().all(|s| s.preferences.is_empty()) {
+        return None;
+    }
+
+    let (requests, unresolved) = resolve_requests(&students);
+    let mut counts: Vec<Option<(usize, usize)>> = vec![None; students.len()];
+    let mut report = PreferenceReport {
+        requests: requests.len(),
+        satisfied: 0,
+        requested_weight: 0.0,
+        satisfied_weight: 0.0,
+        unmet_students: Vec::new(),
+        unresolved,
+        satisfied_by_student: Vec::new(),
+    };
+    for request in &requests {
+        let met = placed[request.from].0 == placed[request.to].0;
+        let entry = counts[request.from].get_or_insert((0, 0));
+        entry.1 += 1;
+        report.requested_weight += request.weight;
+        if met {
+            entry.0 += 1;
+            report.satisfied += 1;
+            report.satisfied_weight += request.weight;
+        }
+    }
+
+    report.satisfied_by_student = classes
+        .iter()
+        .map(|class| Vec::with_capacity(class.students.len()))
+        .collect();
+    for (&(class_idx, _), (count, student)) in placed.iter().zip(counts.into_iter().zip(&students))
+    {
+        if count.is_some_and(|(met, _)| met == 0) {
+            report
+                .unmet_students
+                .push((class_idx, student.name.clone()));
+        }
+        report.satisfied_by_student[class_idx].push(count);
+    }
+    Some(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::model::Gender;
+
+    fn student(name: &str, id: &str, preferences: &str) -> Student {
+        Student::new(name.to_string(), Gender::Male, HashMap::new())
+            .with_id(Some(id.to_string()))
+            .with_preferences(parse_preferences(preferences))
+    }
+
+    #[test]
+    fn test_parse_preferences() {
+        assert_eq!(
+            parse_preferences(" S002:2、S003，李娜：0.5 王芳*x "),
+            vec![
+                Preference::new("S002".to_string(), 2.0),
+                Preference::new("S003".to_string(), 1.0),
+                Preference::new("李娜".to_string(), 0.5),
+                Preference::new("王芳".to_string(), 1.0),
+            ]
+        );
+        assert!(parse_preferences("").is_empty());
+        assert_eq!(
+            format_preferences(&parse_preferences("S002:2,S003")),
+            "S002:2、S003"
+        );
+    }
+
+    #[test]
+    fn test_preference_report() {
+        let mut first = Class::new(0);
+        first.add_student(student("张伟", "S001", "S002:2 李娜"));
+        first.add_student(student("王芳", "S002", "S001"));
+        let mut second = Class::new(1);
+        second.add_student(student("李娜", "S003", "S002、S009"));
+
+        let report = preference_report(&[first, second]).unwrap();
+        assert_eq!(report.requests, 4);
+        assert_eq!(report.satisfied, 2);
+        assert_eq!(report.satisfaction_rate(), 3.0 / 5.0);
+        assert_eq!(report.unmet_students, vec![(1, "李娜".to_string())]);
+        assert_eq!(report.unresolved, vec!["李娜→S009".to_string()]);
+        assert_eq!(
+            report.satisfied_by_student,
+            vec![vec![Some((1, 2)), Some((1, 1))], vec![Some((0, 1))]]
+        );
+    }
+}
@@ -0,0 +1,589 @@
+use super::model::{Class, Gender, Student};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+/// 教室的座位布局，第 0 排离讲台最近
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoomLayout {
+    pub rows: usize,
+    pub columns: usize,
+    /// 过道位置：在这些列（从 0 开始）之后留出过道，过道两侧的座位不算同桌也不算相邻
+    pub aisles_after: Vec<usize>,
+}
+
+impl RoomLayout {
+    pub fn new(rows: usize, columns: usize) -> Self {
+        Self {
+            rows,
+            columns,
+            aisles_after: Vec::new(),
+        }
+    }
+
+    pub fn with_aisles(mut self, mut aisles_after: Vec<usize>) -> Self {
+        aisles_after.sort_unstable();
+        aisles_after.dedup();
+        self.aisles_after = aisles_after;
+        self
+    }
+
+    /// 座位数
+    pub fn capacity(&self) -> usize {
+        self.rows * self.columns
+    }
+
+    /// 第 `col` 列与第 `col + 1` 列之间是否有过道
+    pub fn has_aisle_after(&self, col: usize) -> bool {
+        self.aisles_after.contains(&col)
+    }
+
+    /// 两列之间（不含两端）是否隔着过道
+    fn across_aisle(&self, a: usize, b: usize) -> bool {
+        let (left, right) = (a.min(b), a.max(b));
+        (left..right).any(|col| self.has_aisle_after(col))
+    }
+
+    /// 同桌：过道分隔出的每一段内，从左起每两列为一张课桌
+    fn desk_mate_column(&self, col: usize) -> Option<usize> {
+        let start = (0..col)
+            .rev()
+            .find(|&c| self.has_aisle_after(c))
+            .map_or(0, |c| c + 1);
+        let mate = if (col - start).is_multiple_of(2) {
+            col + 1
+        } else {
+            col - 1
+        };
+        (mate < self.columns && !self.across_aisle(col, mate)).then_some(mate)
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        if self.rows == 0 || self.columns == 0 {
+            anyhow::bail!("座位的排数和列数必须大于 0");
+        }
+        if let Some(&col) = self.aisles_after.iter().find(|&&c| c + 1 >= self.columns) {
+            anyhow::bail!(
+                "过道位置“第 {} 列之后”超出范围，应在第 1~{} 列之后",
+                col + 1,
+                self.columns - 1
+            );
+        }
+        Ok(())
+    }
+}
+
+/// 同桌的搭配方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DeskMateRule {
+    /// 不限
+    #[default]
+    Any,
+    /// 男女同桌
+    AlternateGender,
+    /// 成绩互补：总分排名前一半与后一半的学生同桌
+    MixedAbility,
+}
+
+impl DeskMateRule {
+    pub const ALL: [DeskMateRule; 3] = [
+        DeskMateRule::Any,
+        DeskMateRule::AlternateGender,
+        DeskMateRule::MixedAbility,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            DeskMateRule::Any => "不限",
+            DeskMateRule::AlternateGender => "男女同桌",
+            DeskMateRule::MixedAbility => "成绩互补",
+        }
+    }
+}
+
+/// 排座位的规则
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SeatingRules {
+    /// 按身高排座：矮的坐前排，高的坐后排（没有身高的学生按全班平均身高计）
+    pub by_height: bool,
+    pub desk_mates: DeskMateRule,
+    /// 需要分开的学生（学号或姓名），同一组内任意两人不同桌、前后左右及斜对角都不相邻
+    pub keep_apart: Vec<Vec<String>>,
+}
+
+impl SeatingRules {
+    pub fn with_height_order(mut self, by_height: bool) -> Self {
+        self.by_height = by_height;
+        self
+    }
+
+    pub fn with_desk_mates(mut self, desk_mates: DeskMateRule) -> Self {
+        self.desk_mates = desk_mates;
+        self
+    }
+
+    pub fn with_keep_apart(mut self, keep_apart: Vec<Vec<String>>) -> Self {
+        self.keep_apart = keep_apart;
+        self
+    }
+}
+
+/// 解析需要分开的学生：每行一组，组内以逗号、顿号、分号或空白分隔，少于两人的行忽略
+pub fn parse_keep_apart(text: &str) -> Vec<Vec<String>> {
+    text.lines()
+        .map(|line| {
+            line.split(|c: char| c.is_whitespace() || ",，、;；".contains(c))
+                .filter(|item| !item.is_empty())
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        })
+        .filter(|group| group.len() > 1)
+        .collect()
+}
+
+/// 座位表
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeatingChart {
+    pub layout: RoomLayout,
+    /// `seats[row][col]`：坐在该位置的学生在班级中的索引，空座为 None
+    pub seats: Vec<Vec<Option<usize>>>,
+    /// 未能完全满足的规则（如“张伟与李娜相邻”）
+    pub issues: Vec<String>,
+}
+
+impl SeatingChart {
+    /// 学生（班级中的索引）所在的排与列
+    pub fn seat_of(&self, student: usize) -> Option<(usize, usize)> {
+        self.seats.iter().enumerate().find_map(|(row, seats)| {
+            seats
+                .iter()
+                .position(|&seat| seat == Some(student))
+                .map(|col| (row, col))
+        })
+    }
+}
+
+/// 前排比后排高出多少厘米以内不算违反身高规则
+const HEIGHT_TOLERANCE: f64 = 3.0;
+/// 空座位排在有人的座位前面的代价（空座应留在后排）
+const EMPTY_FRONT_COST: f64 = 50.0;
+/// 一张课桌不符合同桌规则的代价
+const DESK_MATE_COST: f64 = 20.0;
+/// 需要分开的两名学生相邻的代价
+const KEEP_APART_COST: f64 = 10_000.0;
+/// 局部搜索的迭代次数
+const SEATING_ITERATIONS: usize = 40_000;
+const INITIAL_TEMPERATURE: f64 = 20.0;
+const FINAL_TEMPERATURE: f64 = 0.01;
+
+/// 排座位时使用的学生数据
+struct SeatingProblem<'a> {
+    layout: &'a RoomLayout,
+    rules: &'a SeatingRules,
+    /// 身高（没有身高的按平均身高），不按身高排时为空
+    heights: Vec<f64>,
+    genders: Vec<Gender>,
+    /// 总分是否在全班前一半
+    upper_half: Vec<bool>,
+    /// 需要分开的学生对
+    apart_pairs: Vec<(usize, usize)>,
+}
+
+impl<'a> SeatingProblem<'a> {
+    fn new(
+        students: &[Student],
+        layout: &'a RoomLayout,
+        rules: &'a SeatingRules,
+        issues: &mut Vec<String>,
+    ) -> Self {
+        let heights = if rules.by_height {
+            let known: Vec<f64> = students.iter().filter_map(|s| s.height).collect();
+            if known.is_empty() {
+                issues.push("没有身高数据，未按身高排座".to_string());
+                Vec::new()
+            } else {
+                let avg = known.iter().sum::<f64>() / known.len() as f64;
+                students.iter().map(|s| s.height.unwrap_or(avg)).collect()
+            }
+        } else {
+            Vec::new()
+        };
+
+        let mut ranked: Vec<usize> = (0..students.len()).collect();
+        ranked.sort_by(|&a, &b| students[b].total_score.total_cmp(&students[a].total_score));
+        let mut upper_half = vec![false; students.len()];
+        for &idx in &ranked[..students.len() / 2] {
+            upper_half[idx] = true;
+        }
+
+        Self {
+            layout,
+            rules,
+            heights,
+            genders: students.iter().map(|s| s.gender).collect(),
+            upper_half,
+            apart_pairs: resolve_keep_apart(students, &rules.keep_apart, issues),
+        }
+    }
+
+    /// 两名学生是否符合同桌规则
+    fn desk_mates_ok(&self, a: usize, b: usize) -> bool {
+        match self.rules.desk_mates {
+            DeskMateRule::Any => true,
+            DeskMateRule::AlternateGender => {
+                self.genders[a] == Gender::Unknown
+                    || self.genders[b] == Gender::Unknown
+                    || self.genders[a] != self.genders[b]
+            }
+            DeskMateRule::MixedAbility => self.upper_half[a] != self.upper_half[b],
+        }
+    }
+
+    /// 两个座位是否相邻（前后左右及斜对角，不隔过道）
+    fn adjacent(&self, (r1, c1): (usize, usize), (r2, c2): (usize, usize)) -> bool {
+        r1.abs_diff(r2) <= 1 && c1.abs_diff(c2) <= 1 && !self.layout.across_aisle(c1, c2)
+    }
+
+    /// 座位表的代价，越小越好
+    fn cost(&self, seats: &[Option<usize>]) -> f64 {
+        let columns = self.layout.columns;
+        let mut cost = 0.0;
+
+        for row in 0..self.layout.rows {
+            for col in 0..columns {
+                let seat = seats[row * columns + col];
+
+                // 前后两排：空座留在后排，按身高时矮的在前
+                if row + 1 < self.layout.rows {
+                    let behind = seats[(row + 1) * columns + col];
+                    match (seat, behind) {
+                        (None, Some(_)) => cost += EMPTY_FRONT_COST,
+                        (Some(front), Some(back)) if !self.heights.is_empty() => {
+                            cost += (self.heights[front] - self.heights[back]).max(0.0);
+                        }
+                        _ => {}
+                    }
+                }
+
+                // 同桌（每张课桌只算一次）
+                if let (Some(a), Some(mate)) = (seat, self.layout.desk_mate_column(col))
+                    && mate > col
+                    && let Some(b) = seats[row * columns + mate]
+                    && !self.desk_mates_ok(a, b)
+                {
+                    cost += DESK_MATE_COST;
+                }
+            }
+        }
+
+        if !self.apart_pairs.is_empty() {
+            let positions = self.positions(seats);
+            cost += self
+                .apart_pairs
+                .iter()
+                .filter(|&&(a, b)| self.adjacent(positions[a], positions[b]))
+                .count() as f64
+                * KEEP_APART_COST;
+        }
+        cost
+    }
+
+    /// 初始座位：按身高（或原顺序）从前排到后排依次就座，空座留在最后
+    fn initial_seats(&self, count: usize) -> Vec<Option<usize>> {
+        let mut order: Vec<usize> = (0..count).collect();
+        if !self.heights.is_empty() {
+            order.sort_by(|&a, &b| self.heights[a].total_cmp(&self.heights[b]));
+        }
+        let mut seats: Vec<Option<usize>> = order.into_iter().map(Some).collect();
+        seats.resize(self.layout.capacity(), None);
+        seats
+    }
+
+    /// 整理最终座位表中未满足的规则
+    fn describe_issues(
+        &self,
+        students: &[Student],
+        seats: &[Option<usize>],
+        issues: &mut Vec<String>,
+    ) {
+        let columns = self.layout.columns;
+        let positions = self.positions(seats);
+        for &(a, b) in &self.apart_pairs {
+            if self.adjacent(positions[a], positions[b]) {
+                issues.push(format!("{}与{}相邻", students[a].name, students[b].name));
+            }
+        }
+
+        let mut desk_conflicts = 0;
+        let mut height_conflicts = 0;
+        for row in 0..self.layout.rows {
+            for col in 0..columns {
+                let Some(a) = seats[row * columns + col] else {
+                    continue;
+                };
+                if let Some(mate) = self.layout.desk_mate_column(col)
+                    && mate > col
+                    && let Some(b) = seats[row * columns + mate]
+                    && !self.desk_mates_ok(a, b)
+                {
+                    desk_conflicts += 1;
+                }
+                if !self.heights.is_empty()
+                    && row + 1 < self.layout.rows
+                    && let Some(back) = seats[(row + 1) * columns + col]
+                    && self.heights[a] > self.heights[back] + HEIGHT_TOLERANCE
+                {
+                    height_conflicts += 1;
+                }
+            }
+        }
+        if desk_conflicts > 0 {
+            let rule = match self.rules.desk_mates {
+                DeskMateRule::AlternateGender => "同桌同性别",
+                _ => "同桌成绩未互补",
+            };
+            issues.push(format!("{} {} 桌", rule, desk_conflicts));
+        }
+        if height_conflicts > 0 {
+            issues.push(format!(
+                "{} 处前排比后排高 {}厘米以上",
+                height_conflicts, HEIGHT_TOLERANCE
+            ));
+        }
+    }
+
+    /// 每名学生所在的排与列
+    fn positions(&self, seats: &[Option<usize>]) -> Vec<(usize, usize)> {
+        let columns = self.layout.columns;
+        let mut positions = vec![(0, 0); self.genders.len()];
+        for (idx, seat) in seats.iter().enumerate() {
+            if let Some(student) = *seat {
+                positions[student] = (idx / columns, idx % columns);
+            }
+        }
+        positions
+    }
+}
+
+/// 把需要分开的学生（学号或姓名）对应到班级中的索引，返回组内两两组成的学生对
+fn resolve_keep_apart(
+    students: &[Student],
+    groups: &[Vec<String>],
+    issues: &mut Vec<String>,
+) -> Vec<(usize, usize)> {
+    let find = |target: &str| {
+        students
+            .iter()
+            .position(|s| s.id.as_deref() == Some(target))
+            .or_else(|| {
+                let mut matches = students
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, s)| s.name == target);
+                match (matches.next(), matches.next()) {
+                    (Some((idx, _)), None) => Some(idx),
+                    _ => None,
+                }
+            })
+    };
+
+    let mut pairs = Vec::new();
+    for group in groups {
+        let members: Vec<usize> = group
+            .iter()
+            .filter_map(|target| {
+                let idx = find(target);
+                if idx.is_none() {
+                    issues.push(format!("本班找不到学生“{}”或有重名", target));
+                }
+                idx
+            })
+            .collect();
+        for (i, &a) in members.iter().enumerate() {
+            for &b in &members[i + 1..] {
+                if a != b {
+                    pairs.push((a, b));
+                }
+            }
+        }
+    }
+    pairs
+}
+
+/// 为一个班级排座位
+///
+/// 先按身高（或原顺序）从前排到后排就座，再用模拟退火交换座位，
+/// 使需要分开的学生不相邻、同桌符合搭配规则、前排不比后排高。
+/// 无法完全满足的规则记录在 `issues` 中；相同的种子得到相同的座位表。
+pub fn arrange_seats(
+    class: &Class,
+    layout: &RoomLayout,
+    rules: &SeatingRules,
+    seed: u64,
+) -> anyhow::Result<SeatingChart> {
+    layout.validate()?;
+    let count = class.students.len();
+    if count > layout.capacity() {
+        anyhow::bail!(
+            "班级 {} 有 {} 人，超过座位数 {}（{} 排 × {} 列）",
+            class.id + 1,
+            count,
+            layout.capacity(),
+            layout.rows,
+            layout.columns
+        );
+    }
+
+    let mut issues = Vec::new();
+    let problem = SeatingProblem::new(&class.students, layout, rules, &mut issues);
+    let mut seats = problem.initial_seats(count);
+    let mut cost = problem.cost(&seats);
+    let mut best = (seats.clone(), cost);
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let capacity = layout.capacity();
+    let cooling = (FINAL_TEMPERATURE / INITIAL_TEMPERATURE).powf(1.0 / SEATING_ITERATIONS as f64);
+    let mut temperature = INITIAL_TEMPERATURE;
+    for _ in 0..SEATING_ITERATIONS {
+        if best.1 == 0.0 || capacity < 2 {
+            break;
+        }
+        let a = rng.random_range(0..capacity);
+        let b = rng.random_range(0..capacity);
+        if a == b || (seats[a].is_none() && seats[b].is_none()) {
+            continue;
+        }
+
+        seats.swap(a, b);
+        let new_cost = problem.cost(&seats);
+        let delta = new_cost - cost;
+        if delta <= 0.0 || rng.random::<f64>() < (-delta / temperature).exp() {
+            cost = new_cost;
+            if cost < best.1 {
+                best = (seats.clone(), cost);
+            }
+        } else {
+            seats.swap(a, b);
+        }
+        temperature *= cooling;
+    }
+
+    let seats = best.0;
+    problem.describe_issues(&class.students, &seats, &mut issues);
+    Ok(SeatingChart {
+        layout: layout.clone(),
+        seats: seats.chunks(layout.columns).map(<[_]>::to_vec).collect(),
+        issues,
+    })
+}
+
+impl Class {
+    /// 为本班排座位，结果写入 `seating`（见 [`arrange_seats`]）
+    pub fn arrange_seats(
+        &mut self,
+        layout: &RoomLayout,
+        rules: &SeatingRules,
+        seed: u64,
+    ) -> anyhow::Result<&SeatingChart> {
+        let chart = arrange_seats(self, layout, rules, seed)?;
+        Ok(self.seating.insert(chart))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::algorithm::tests::sample_class;
+
+    /// 男女交替、带学号与身高的班级
+    fn seating_class(count: usize) -> Class {
+        let mut class = sample_class(0, count);
+        for (i, student) in class.students.iter_mut().enumerate() {
+            student.gender = if i % 2 == 0 {
+                Gender::Male
+            } else {
+                Gender::Female
+            };
+            student.id = Some(format!("S{:03}", i));
+            student.height = Some(150.0 + ((i * 13) % 30) as f64);
+        }
+        class
+    }
+
+    #[test]
+    fn test_room_layout_desk_mates_respect_aisles() {
+        let layout = RoomLayout::new(2, 7).with_aisles(vec![1, 4]);
+        let mates: Vec<Option<usize>> = (0..7).map(|c| layout.desk_mate_column(c)).collect();
+        assert_eq!(
+            mates,
+            vec![Some(1), Some(0), Some(3), Some(2), None, Some(6), Some(5)]
+        );
+        assert!(
+            RoomLayout::new(2, 4)
+                .with_aisles(vec![3])
+                .validate()
+                .is_err()
+        );
+        assert_eq!(
+            parse_keep_apart("张伟、李娜\n王芳\nS001 S002，S003"),
+            vec![
+                vec!["张伟".to_string(), "李娜".to_string()],
+                vec!["S001".to_string(), "S002".to_string(), "S003".to_string()],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_arrange_seats_honours_rules() {
+        let mut class = seating_class(30);
+        let layout = RoomLayout::new(6, 6).with_aisles(vec![1, 3]);
+        let rules = SeatingRules::default()
+            .with_height_order(true)
+            .with_desk_mates(DeskMateRule::AlternateGender)
+            .with_keep_apart(vec![vec![
+                "S000".to_string(),
+                "学生1".to_string(),
+                "S002".to_string(),
+            ]]);
+        let chart = class.arrange_seats(&layout, &rules, 7).unwrap().clone();
+
+        // 每人一个座位，空座都在最后一排
+        let mut seated: Vec<usize> = chart.seats.iter().flatten().flatten().copied().collect();
+        seated.sort_unstable();
+        assert_eq!(seated, (0..30).collect::<Vec<_>>());
+        assert!(
+            chart.seats[..5]
+                .iter()
+                .all(|row| row.iter().all(Option::is_some))
+        );
+
+        // 需要分开的学生互不相邻
+        let seat = |idx| chart.seat_of(idx).unwrap();
+        for (a, b) in [(0, 1), (0, 2), (1, 2)] {
+            let ((r1, c1), (r2, c2)) = (seat(a), seat(b));
+            assert!(r1.abs_diff(r2) > 1 || c1.abs_diff(c2) > 1 || layout.across_aisle(c1, c2));
+        }
+
+        // 同桌男女搭配
+        for row in &chart.seats {
+            for col in [0, 2, 4] {
+                if let (Some(a), Some(b)) = (row[col], row[col + 1]) {
+                    assert_ne!(class.students[a].gender, class.students[b].gender);
+                }
+            }
+        }
+
+        // 各排平均身高从前往后递增
+        let avg_height = |row: &Vec<Option<usize>>| {
+            let heights: Vec<f64> = row
+                .iter()
+                .flatten()
+                .map(|&idx| class.students[idx].height.unwrap())
+                .collect();
+            heights.iter().sum::<f64>() / heights.len() as f64
+        };
+        assert!(avg_height(&chart.seats[0]) < avg_height(&chart.seats[4]));
+        assert_eq!(class.seating.as_ref(), Some(&chart));
+
+        let too_small = RoomLayout::new(4, 6);
+        assert!(arrange_seats(&class, &too_small, &rules, 7).is_err());
+    }
+}
@@ -189,17 +189,7 @@ fn calculate_gender_balance(classes: &[Class]) -> GenderBalance {
         .sum::<f64>()
         / female_counts.len() as f64;
 
-    let ratios: Vec<f64> = classes
-        .par_iter()
-        .map(|c| {
-            let total = c.students.len() as f64;
-            if total > 0.0 {
-                c.male_count() as f64 / total
-            } else {
-                0.0
-            }
-        })
-        .collect();
+    let ratios: Vec<f64> = classes.par_iter().map(|c| c.gender_ratio()).collect();
 
     let ratio_mean = ratios.par_iter().sum::<f64>() / ratios.len() as f64;
     let ratio_variance = ratios
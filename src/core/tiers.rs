@@ -0,0 +1,391 @@
+use super::algorithm::{DivideConfig, DivideResult, OptimizationParams, divide_with_cancel};
+use super::model::{Class, Student};
+use super::objective::{Objective, SubsetObjective};
+use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
+
+/// 分层的划分方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TierCutoff {
+    /// 按总分排名取前若干名
+    Rank(usize),
+    /// 取总分不低于该分数的学生
+    Score(f64),
+}
+
+/// 一个层次（如实验班、平行班）的设置
+#[derive(Debug, Clone)]
+pub struct TierSpec {
+    /// 层次名称，导出时作为班级的层次标签
+    pub name: String,
+    /// 划分方式；最后一个层次总是接收剩余的全部学生，忽略此项
+    pub cutoff: TierCutoff,
+    pub num_classes: usize,
+    /// 层内均衡使用的预设参数：只取约束阈值等由预设方案决定的字段
+    /// （见 [`OptimizationParams::with_preset`]），其余设置沿用公共配置
+    pub optimization_params: OptimizationParams,
+}
+
+impl TierSpec {
+    pub fn new(name: impl Into<String>, cutoff: TierCutoff, num_classes: usize) -> Self {
+        Self {
+            name: name.into(),
+            cutoff,
+            num_classes,
+            optimization_params: OptimizationParams::default(),
+        }
+    }
+
+    pub fn with_optimization_params(mut self, params: OptimizationParams) -> Self {
+        self.optimization_params = params;
+        self
+    }
+}
+
+/// 单个层次的分班结果
+#[derive(Debug, Clone)]
+pub struct TierOutcome {
+    pub name: String,
+    /// 本层第一个班的全局班级索引
+    pub first_class: usize,
+    /// 本层学生在输入中的索引
+    pub student_indices: Vec<usize>,
+    /// 层内分班结果（班级索引从 0 开始）
+    pub result: DivideResult,
+}
+
+/// 分层分班结果
+#[derive(Debug, Clone)]
+pub struct TieredResult {
+    pub tiers: Vec<TierOutcome>,
+    /// 所有层次的班级，编号跨层连续，并带有层次标签
+    pub classes: Vec<Class>,
+    /// 每个学生所在的全局班级索引（与输入学生顺序一致）
+    pub assignments: Vec<usize>,
+}
+
+impl TieredResult {
+    /// 是否有层次在取消时只得到部分结果
+    pub fn is_partial(&self) -> bool {
+        self.tiers.iter().any(|tier| tier.result.is_partial())
+    }
+
+    /// 用于界面摘要与导出的运行信息
+    pub fn summary_items(&self) -> Vec<(String, String)> {
+        let mut items = vec![
+            ("学生总数".to_string(), self.assignments.len().to_string()),
+            ("班级数量".to_string(), self.classes.len().to_string()),
+        ];
+        for tier in &self.tiers {
+            let num_classes = tier.result.classes.len();
+            let v = &tier.result.validation;
+            items.push((
+                tier.name.clone(),
+                format!(
+                    "{}人，{}个班（{}~{}班），总分最大差值 {:.2}分，性别比例最大差 {:.1}%",
+                    tier.student_indices.len(),
+                    num_classes,
+                    tier.first_class + 1,
+                    tier.first_class + num_classes,
+                    v.max_score_diff,
+                    v.max_gender_ratio_diff * 100.0
+                ),
+            ));
+        }
+        if let Some(first) = self.tiers.first() {
+            items.push(("随机种子".to_string(), first.result.seed.to_string()));
+        }
+        items
+    }
+}
+
+/// 按总分从高到低把学生划入各层次，返回每层学生的索引
+fn split_into_tiers(students: &[Student], tiers: &[TierSpec]) -> Vec<Vec<usize>> {
+    let mut ranked: Vec<usize> = (0..students.len()).collect();
+    ranked.sort_by(|&a, &b| students[b].total_score.total_cmp(&students[a].total_score));
+
+    let mut remaining = ranked.as_slice();
+    tiers
+        .iter()
+        .enumerate()
+        .map(|(index, tier)| {
+            let take = if index + 1 == tiers.len() {
+                remaining.len()
+            } else {
+                match tier.cutoff {
+                    TierCutoff::Rank(count) => count.min(remaining.len()),
+                    TierCutoff::Score(score) => remaining
+                        .iter()
+                        .take_while(|&&idx| students[idx].total_score >= score)
+                        .count(),
+                }
+            };
+            let (taken, rest) = remaining.split_at(take);
+            remaining = rest;
+            let mut indices = taken.to_vec();
+            indices.sort_unstable();
+            indices
+        })
+        .collect()
+}
+
+/// 分层分班
+///
+/// 先按总分把学生划入各层次（如前 100 名进实验班，其余进平行班），再在每层内部
+/// 用该层的预设参数独立均衡。班级编号跨层连续，每个班级带有所属层次的标签。
+/// `config` 提供班级数以外的公共设置（种子、时间预算、重名与好友志愿等优化参数）。
+pub fn divide_tiered(
+    students: &[Student],
+    tiers: &[TierSpec],
+    config: &DivideConfig,
+    cancel_token: CancellationToken,
+) -> anyhow::Result<TieredResult> {
+    if tiers.is_empty() {
+        anyhow::bail!("请至少设置一个层次");
+    }
+
+    let groups = split_into_tiers(students, tiers);
+    for (tier, indices) in tiers.iter().zip(&groups) {
+        if tier.num_classes == 0 {
+            anyhow::bail!("层次“{}”的班级数必须大于 0", tier.name);
+        }
+        if indices.len() < tier.num_classes {
+            anyhow::bail!(
+                "层次“{}”只有 {} 名学生，不足以分成 {} 个班",
+                tier.name,
+                indices.len(),
+                tier.num_classes
+            );
+        }
+    }
+
+    let group_configs = tiers.iter().map(|tier| DivideConfig {
+        num_classes: tier.num_classes,
+        optimization_params: config
+            .optimization_params
+            .clone()
+            .with_preset(&tier.optimization_params),
+        ..config.clone()
+    });
+    let (results, mut classes, assignments) =
+        divide_groups(students, &groups, group_configs, cancel_token);
+
+    let mut outcomes = Vec::with_capacity(tiers.len());
+    for ((tier, indices), (first_class, result)) in tiers.iter().zip(groups).zip(results) {
+        for class in &mut classes[first_class..first_class + result.classes.len()] {
+            class.tier = Some(tier.name.clone());
+        }
+        outcomes.push(TierOutcome {
+            name: tier.name.clone(),
+            first_class,
+            student_indices: indices,
+            result,
+        });
+    }
+
+    Ok(TieredResult {
+        tiers: outcomes,
+        classes,
+        assignments,
+    })
+}
+
+/// 各组学生分别用对应的配置独立分班，再按组的顺序拼接
+///
+/// 返回每组第一个班的全局索引与组内结果、编号跨组连续的全部班级，以及每个学生的全局班级索引。
+/// 原分班、热启动与备选方案都针对全体学生，分组时不适用；自定义目标项按全体学生的索引构造，
+/// 分组时换算为组内索引后继续生效。
+pub(crate) fn divide_groups(
+    students: &[Student],
+    groups: &[Vec<usize>],
+    configs: impl Iterator<Item = DivideConfig>,
+    cancel_token: CancellationToken,
+) -> (Vec<(usize, DivideResult)>, Vec<Class>, Vec<usize>) {
+    let mut results = Vec::with_capacity(groups.len());
+    let mut classes: Vec<Class> = Vec::new();
+    let mut assignments = vec![0; students.len()];
+    for (indices, config) in groups.iter().zip(configs) {
+        let group_students: Vec<Student> = indices.iter().map(|&i| students[i].clone()).collect();
+        let shared_indices: Arc<[usize]> = Arc::from(indices.as_slice());
+        let objectives = config
+            .objectives
+            .iter()
+            .map(|objective| {
+                Box::new(SubsetObjective::new(
+                    objective.clone(),
+                    shared_indices.clone(),
+                )) as Box<dyn Objective>
+            })
+            .collect();
+        let group_config = DivideConfig {
+            objectives,
+            reference_assignments: None,
+            initial_assignments: None,
+            active_classes: None,
+            alternatives: None,
+            pareto: false,
+            ..config
+        };
+        let result = divide_with_cancel(&group_students, group_config, cancel_token.clone());
+
+        let first_class = classes.len();
+        for (&student_idx, &class_id) in indices.iter().zip(&result.assignments) {
+            assignments[student_idx] = first_class + class_id;
+        }
+        classes.extend(result.classes.iter().map(|class| {
+            let mut class = class.clone();
+            class.id += first_class;
+            class
+        }));
+        results.push((first_class, result));
+    }
+    (results, classes, assignments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::algorithm::tests::sample_students;
+    use crate::core::names::NameMatch;
+
+    #[test]
+    fn test_tiers_take_top_students_and_number_classes_continuously() {
+        let students = sample_students(80);
+        let params = OptimizationParams {
+            num_parallel_instances: Some(1),
+            ..Default::default()
+        };
+        let tiers = vec![
+            TierSpec::new("实验班", TierCutoff::Rank(20), 1),
+            TierSpec::new("平行班", TierCutoff::Rank(0), 3),
+        ];
+        let config = DivideConfig::default()
+            .with_optimization_params(params)
+            .with_seed(Some(3))
+            .with_iterations(5_000);
+
+        let result = divide_tiered(&students, &tiers, &config, CancellationToken::new()).unwrap();
+
+        assert_eq!(result.classes.len(), 4);
+        let ids: Vec<usize> = result.classes.iter().map(|c| c.id).collect();
+        assert_eq!(ids, vec![0, 1, 2, 3]);
+        assert_eq!(result.classes[0].tier.as_deref(), Some("实验班"));
+        assert_eq!(result.classes[3].tier.as_deref(), Some("平行班"));
+
+        // 实验班的最低分不低于平行班的最高分
+        let lowest_top = result.classes[0]
+            .students
+            .iter()
+            .map(|s| s.total_score)
+            .fold(f64::INFINITY, f64::min);
+        let highest_rest = result.classes[1..]
+            .iter()
+            .flat_map(|c| &c.students)
+            .map(|s| s.total_score)
+            .fold(0.0, f64::max);
+        assert_eq!(result.classes[0].students.len(), 20);
+        assert!(lowest_top >= highest_rest);
+        for (idx, &class_id) in result.assignments.iter().enumerate() {
+            assert!(
+                result.classes[class_id]
+                    .students
+                    .iter()
+                    .any(|s| s.name == students[idx].name)
+            );
+        }
+    }
+
+    #[test]
+    fn test_tiered_division_maps_objectives_to_tier_students() {
+        let students = sample_students(80);
+        let params = OptimizationParams {
+            num_parallel_instances: Some(2),
+            initial_temperature: 20.0,
+            temperature_diversity_delta: 0.0,
+            ..Default::default()
+        };
+        let tiers = vec![
+            TierSpec::new("实验班", TierCutoff::Rank(20), 1)
+                .with_optimization_params(params.clone()),
+            TierSpec::new("平行班", TierCutoff::Rank(0), 3)
+                .with_optimization_params(params.clone()),
+        ];
+
+        // 标记平行班中的 6 名学生（总分最低的几名），要求均匀分到 3 个班
+        let mut by_score: Vec<usize> = (0..students.len()).collect();
+        by_score.sort_by(|&a, &b| students[a].total_score.total_cmp(&students[b].total_score));
+        let mut flags = vec![false; students.len()];
+        for &idx in &by_score[..6] {
+            flags[idx] = true;
+        }
+        let config = DivideConfig::default()
+            .with_optimization_params(params)
+            .with_seed(Some(5))
+            .with_iterations(30_000)
+            .with_objective(crate::core::objective::SpreadObjective::new(
+                "标记",
+                flags.clone(),
+                1000.0,
+            ));
+
+        let result = divide_tiered(&students, &tiers, &config, CancellationToken::new()).unwrap();
+        let mut counts = vec![0; result.classes.len()];
+        for (idx, &class_id) in result.assignments.iter().enumerate() {
+            if flags[idx] {
+                counts[class_id] += 1;
+            }
+        }
+        assert_eq!(counts, vec![0, 2, 2, 2]);
+    }
+
+    #[test]
+    fn test_tiers_keep_global_settings_outside_presets() {
+        let mut students = sample_students(80);
+        // 平行班中三名重名学生：重名设置只在公共参数中开启
+        let mut by_score: Vec<usize> = (0..students.len()).collect();
+        by_score.sort_by(|&a, &b| students[a].total_score.total_cmp(&students[b].total_score));
+        for &idx in &by_score[..3] {
+            students[idx].name = "张伟".to_string();
+        }
+        let params = OptimizationParams {
+            num_parallel_instances: Some(1),
+            duplicate_names: Some(NameMatch::Exact),
+            ..Default::default()
+        };
+        let tiers = vec![
+            TierSpec::new("实验班", TierCutoff::Rank(20), 1)
+                .with_optimization_params(OptimizationParams::strict()),
+            TierSpec::new("平行班", TierCutoff::Rank(0), 3)
+                .with_optimization_params(OptimizationParams::relaxed()),
+        ];
+        let config = DivideConfig::default()
+            .with_optimization_params(params)
+            .with_seed(Some(2))
+            .with_iterations(20_000);
+
+        let result = divide_tiered(&students, &tiers, &config, CancellationToken::new()).unwrap();
+        let mut classes: Vec<usize> = by_score[..3]
+            .iter()
+            .map(|&idx| result.assignments[idx])
+            .collect();
+        classes.sort_unstable();
+        assert_eq!(classes, vec![1, 2, 3]);
+        assert!(result.tiers[1].result.validation.name_constraints_met);
+    }
+
+    #[test]
+    fn test_tier_with_too_few_students_is_rejected() {
+        let students = sample_students(10);
+        let tiers = vec![
+            TierSpec::new("实验班", TierCutoff::Score(1000.0), 1),
+            TierSpec::new("平行班", TierCutoff::Rank(0), 2),
+        ];
+        let result = divide_tiered(
+            &students,
+            &tiers,
+            &DivideConfig::default(),
+            CancellationToken::new(),
+        );
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,187 @@
+use crate::core::explore::{SweepConfig, SweepResult, Threshold};
+use dioxus::prelude::*;
+
+#[component]
+pub fn ClassSweepPanel(
+    num_classes: Signal<usize>,
+    sweep_result: Signal<Option<SweepResult>>,
+    on_sweep: EventHandler<SweepConfig>,
+) -> Element {
+    let mut from = use_signal(|| num_classes().saturating_sub(2).max(2));
+    let mut to = use_signal(|| num_classes() + 2);
+    let mut seconds = use_signal(|| 3u64);
+    let mut min_size = use_signal(|| None::<usize>);
+    let mut max_size = use_signal(|| None::<usize>);
+
+    let start_sweep = move |_| {
+        let (low, high) = (from().min(to()), from().max(to()));
+        on_sweep.call(SweepConfig {
+            class_counts: low..=high,
+            time_budget: std::time::Duration::from_secs(seconds()),
+            min_class_size: min_size(),
+            max_class_size: max_size(),
+        });
+    };
+
+    rsx! {
+        div { class: "collapse collapse-arrow bg-base-200 mb-8",
+            input { r#type: "checkbox" }
+            div { class: "collapse-title font-medium", "班级数量对比" }
+            div { class: "collapse-content",
+                p { class: "text-sm text-base-content/70 mb-4",
+                    "对一组班级数量分别快速分班，比较各方案的班级人数、可达差值与约束满足情况"
+                }
+
+                div { class: "flex flex-wrap items-end gap-4 mb-4",
+                    label { class: "form-control",
+                        span { class: "label-text mb-1", "班级数量范围" }
+                        div { class: "flex items-center gap-2",
+                            input {
+                                r#type: "number",
+                                class: "input input-bordered input-sm w-20",
+                                min: "2",
+                                max: "100",
+                                value: "{from}",
+                                oninput: move |evt| {
+                                    if let Ok(val) = evt.value().parse::<usize>() && (2..=100).contains(&val) {
+                                        from.set(val);
+                                    }
+                                },
+                            }
+                            span { "至" }
+                            input {
+                                r#type: "number",
+                                class: "input input-bordered input-sm w-20",
+                                min: "2",
+                                max: "100",
+                                value: "{to}",
+                                oninput: move |evt| {
+                                    if let Ok(val) = evt.value().parse::<usize>() && (2..=100).contains(&val) {
+                                        to.set(val);
+                                    }
+                                },
+                            }
+                        }
+                    }
+                    label { class: "form-control",
+                        span { class: "label-text mb-1", "每个方案用时（秒）" }
+                        input {
+                            r#type: "number",
+                            class: "input input-bordered input-sm w-20",
+                            min: "1",
+                            value: "{seconds}",
+                            oninput: move |evt| {
+                                if let Ok(val) = evt.value().parse::<u64>() && val > 0 {
+                                    seconds.set(val);
+                                }
+                            },
+                        }
+                    }
+                    label { class: "form-control",
+                        span { class: "label-text mb-1", "班级人数政策" }
+                        div { class: "flex items-center gap-2",
+                            input {
+                                r#type: "number",
+                                class: "input input-bordered input-sm w-20",
+                                min: "1",
+                                placeholder: "下限",
+                                value: min_size().map(|v| v.to_string()).unwrap_or_default(),
+                                oninput: move |evt| min_size.set(evt.value().parse().ok()),
+                            }
+                            span { "至" }
+                            input {
+                                r#type: "number",
+                                class: "input input-bordered input-sm w-20",
+                                min: "1",
+                                placeholder: "上限",
+                                value: max_size().map(|v| v.to_string()).unwrap_or_default(),
+                                oninput: move |evt| max_size.set(evt.value().parse().ok()),
+                            }
+                            span { "人" }
+                        }
+                    }
+                    button {
+                        class: "btn btn-secondary btn-sm",
+                        onclick: start_sweep,
+                        "开始对比"
+                    }
+                }
+
+                if let Some(result) = sweep_result() {
+                    if let Some(recommended) = result.recommended {
+                        div { class: "alert alert-success mb-4",
+                            span { "推荐开设 {recommended} 个班" }
+                            button {
+                                class: "btn btn-sm",
+                                onclick: move |_| num_classes.set(recommended),
+                                "采用"
+                            }
+                        }
+                    } else {
+                        div { class: "alert alert-warning mb-4",
+                            span { "没有符合班级人数政策的方案，请调整范围或政策" }
+                        }
+                    }
+
+                    div { class: "overflow-x-auto",
+                        table { class: "table table-zebra table-sm w-full",
+                            thead {
+                                tr {
+                                    th { "班级数" }
+                                    th { "班级人数" }
+                                    th { "{Threshold::Score.label()}" }
+                                    th { "{Threshold::Subject.label()}" }
+                                    th { "{Threshold::Gender.label()}" }
+                                    th { "硬约束" }
+                                    th { "人数政策" }
+                                    th {}
+                                }
+                            }
+                            tbody {
+                                for entry in result.entries {
+                                    tr {
+                                        key: "{entry.num_classes}",
+                                        class: if result.recommended == Some(entry.num_classes) { "font-bold" } else { "" },
+                                        td { "{entry.num_classes}" }
+                                        td {
+                                            if entry.min_class_size == entry.max_class_size {
+                                                "{entry.min_class_size}"
+                                            } else {
+                                                "{entry.min_class_size}~{entry.max_class_size}"
+                                            }
+                                        }
+                                        td { {Threshold::Score.format(entry.max_score_diff)} }
+                                        td { {Threshold::Subject.format(entry.max_subject_diff)} }
+                                        td { {Threshold::Gender.format(entry.max_gender_ratio_diff)} }
+                                        td {
+                                            if entry.constraints_met {
+                                                span { class: "badge badge-success", "满足" }
+                                            } else {
+                                                span { class: "badge badge-error", "未满足" }
+                                            }
+                                        }
+                                        td {
+                                            if entry.size_policy_met {
+                                                span { class: "badge badge-success", "符合" }
+                                            } else {
+                                                span { class: "badge badge-warning", "不符合" }
+                                            }
+                                        }
+                                        td {
+                                            button {
+                                                class: "btn btn-xs btn-outline",
+                                                disabled: num_classes() == entry.num_classes,
+                                                onclick: move |_| num_classes.set(entry.num_classes),
+                                                "采用"
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
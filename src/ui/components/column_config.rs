@@ -1,9 +1,11 @@
 use super::types::{ColumnMapping, ColumnType};
+use crate::core::model::GenderAliases;
 use dioxus::prelude::*;
 
 #[component]
 pub fn ColumnConfigView(
     column_mappings: Signal<Vec<ColumnMapping>>,
+    gender_aliases: Signal<GenderAliases>,
     on_confirm: EventHandler<()>,
     on_back: EventHandler<()>,
 ) -> Element {
@@ -45,6 +47,11 @@ pub fn ColumnConfigView(
                                         option { value: "subject", "科目成绩" }
                                         option { value: "total", "总成绩" }
                                         option { value: "extra", "保留列" }
+                                        option { value: "original_class", "原班级" }
+                                        option { value: "combination", "选科组合" }
+                                        option { value: "national_id", "身份证号" }
+                                        option { value: "preferences", "好友志愿" }
+                                        option { value: "height", "身高" }
                                         option { value: "ignore", "忽略" }
                                     }
                                 }
@@ -54,6 +61,40 @@ pub fn ColumnConfigView(
                 }
             }
 
+            // 性别取值
+            div { class: "collapse collapse-arrow bg-base-200 mb-6",
+                input { r#type: "checkbox" }
+                div { class: "collapse-title font-medium", "性别取值" }
+                div { class: "collapse-content",
+                    p { class: "text-sm text-base-content/70 mb-4",
+                        "性别列中表示男、女的写法，用逗号分隔，不区分英文大小写；空白或其他取值记为性别未知，分班时不计入性别比例"
+                    }
+                    div { class: "flex flex-wrap gap-4",
+                        label { class: "form-control",
+                            span { class: "label-text mb-1", "男" }
+                            input {
+                                class: "input input-bordered input-sm w-72",
+                                value: gender_aliases.read().male.join("，"),
+                                onchange: move |evt| gender_aliases.write().male = split_aliases(&evt.value()),
+                            }
+                        }
+                        label { class: "form-control",
+                            span { class: "label-text mb-1", "女" }
+                            input {
+                                class: "input input-bordered input-sm w-72",
+                                value: gender_aliases.read().female.join("，"),
+                                onchange: move |evt| gender_aliases.write().female = split_aliases(&evt.value()),
+                            }
+                        }
+                        button {
+                            class: "btn btn-sm btn-ghost self-end",
+                            onclick: move |_| gender_aliases.set(GenderAliases::default()),
+                            "恢复默认"
+                        }
+                    }
+                }
+            }
+
             div { class: "flex justify-between",
                 button {
                     class: "btn btn-outline",
@@ -69,3 +110,11 @@ pub fn ColumnConfigView(
         }
     }
 }
+
+/// 按逗号或顿号拆分别名列表
+fn split_aliases(text: &str) -> Vec<String> {
+    text.split(|c: char| ",，、".contains(c))
+        .map(|alias| alias.trim().to_string())
+        .filter(|alias| !alias.is_empty())
+        .collect()
+}
@@ -1,11 +1,31 @@
-use crate::{core::algorithm::OptimizationParams, ui::INFO};
+use crate::{
+    core::{
+        algorithm::{AlternativeConfig, OptimizationParams, SeedingStrategy},
+        explore::Threshold,
+        names::NameMatch,
+        tiers::{TierCutoff, TierSpec},
+    },
+    ui::INFO,
+};
 use dioxus::prelude::*;
 
 #[component]
 pub fn DivisionConfigView(
     num_classes: Signal<usize>,
     optimization_params: Signal<OptimizationParams>,
+    seed: Signal<Option<u64>>,
+    time_budget: Signal<Option<u64>>,
+    trace_interval: Signal<Option<usize>>,
+    alternatives: Signal<Option<AlternativeConfig>>,
+    pareto: Signal<bool>,
+    tiers: Signal<Vec<TierSpec>>,
+    combination_mode: bool,
+    preference_mode: bool,
+    compatibility: Signal<String>,
+    reference_mode: bool,
+    tighten_priority: Signal<Vec<Threshold>>,
     on_start: EventHandler<()>,
+    on_auto_tighten: EventHandler<()>,
     on_back: EventHandler<()>,
 ) -> Element {
     // 高级参数展开状态
@@ -53,11 +73,14 @@ pub fn DivisionConfigView(
                         onchange: move |evt| {
                             let value = evt.value();
                             preset.set(value.clone());
-                            match value.as_str() {
-                                "relaxed" => optimization_params.set(OptimizationParams::relaxed()),
-                                "strict" => optimization_params.set(OptimizationParams::strict()),
-                                _ => optimization_params.set(OptimizationParams::default()),
-                            }
+                            // 方案只决定约束阈值与权重，本页单独设置的其余参数切换时保留
+                            let preset_params = match value.as_str() {
+                                "relaxed" => OptimizationParams::relaxed(),
+                                "strict" => OptimizationParams::strict(),
+                                _ => OptimizationParams::default(),
+                            };
+                            let current = optimization_params.read().clone();
+                            optimization_params.set(current.with_preset(&preset_params));
                         },
                         option { value: "default", "默认（推荐）" }
                         option { value: "relaxed", "宽松（更快速）" }
@@ -69,6 +92,295 @@ pub fn DivisionConfigView(
                         }
                     }
                 }
+                // 随机种子
+                div { class: "form-control w-full max-w-xs",
+                    label { class: "label",
+                        span { class: "label-text font-medium", "随机种子" }
+                        input {
+                            r#type: "number",
+                            class: "input input-bordered w-40 mr-2",
+                            placeholder: "留空则随机",
+                            value: seed().map(|s| s.to_string()).unwrap_or_default(),
+                            min: "0",
+                            oninput: move |evt| {
+                                let value = evt.value();
+                                if value.trim().is_empty() {
+                                    seed.set(None);
+                                } else if let Ok(val) = value.trim().parse::<u64>() {
+                                    seed.set(Some(val));
+                                }
+                            },
+                        }
+                    }
+                    label { class: "label",
+                        span { class: "label-text-alt text-base-content/60",
+                            "💡 使用相同的种子和参数可以复现之前的分班结果；设置了时间预算时，停止的时机取决于机器速度，结果不保证相同"
+                        }
+                    }
+                }
+                // 时间预算
+                div { class: "form-control w-full max-w-xs",
+                    label { class: "label",
+                        span { class: "label-text font-medium", "时间预算（秒）" }
+                        input {
+                            r#type: "number",
+                            class: "input input-bordered w-24 mr-2",
+                            placeholder: "不限",
+                            value: time_budget().map(|s| s.to_string()).unwrap_or_default(),
+                            min: "1",
+                            oninput: move |evt| {
+                                let value = evt.value();
+                                if value.trim().is_empty() {
+                                    time_budget.set(None);
+                                } else if let Ok(val) = value.trim().parse::<u64>() && val > 0 {
+                                    time_budget.set(Some(val));
+                                }
+                            },
+                        }
+                    }
+                    label { class: "label",
+                        span { class: "label-text-alt text-base-content/60",
+                            "💡 在限定时间内给出最优结果，设置后不再保证可复现"
+                        }
+                    }
+                }
+                // 收敛轨迹
+                div { class: "form-control w-full max-w-xs",
+                    label { class: "label",
+                        span { class: "label-text font-medium", "轨迹记录间隔（迭代）" }
+                        input {
+                            r#type: "number",
+                            class: "input input-bordered w-28 mr-2",
+                            placeholder: "不记录",
+                            value: trace_interval().map(|s| s.to_string()).unwrap_or_default(),
+                            min: "100",
+                            step: "100",
+                            oninput: move |evt| {
+                                let value = evt.value();
+                                if value.trim().is_empty() {
+                                    trace_interval.set(None);
+                                } else if let Ok(val) = value.trim().parse::<usize>() && val > 0 {
+                                    trace_interval.set(Some(val));
+                                }
+                            },
+                        }
+                    }
+                    label { class: "label",
+                        span { class: "label-text-alt text-base-content/60",
+                            "💡 记录温度、代价和接受率的变化，用于调整冷却速率等参数"
+                        }
+                    }
+                }
+                // 备选方案
+                div { class: "form-control w-full max-w-md",
+                    label { class: "label",
+                        span { class: "label-text font-medium", "方案数量" }
+                        input {
+                            r#type: "number",
+                            class: "input input-bordered w-20 mr-2",
+                            placeholder: "1",
+                            value: alternatives().map(|a| a.count.to_string()).unwrap_or_default(),
+                            min: "1",
+                            max: "10",
+                            oninput: move |evt| {
+                                match evt.value().trim().parse::<usize>() {
+                                    Ok(count) if (2..=10).contains(&count) => {
+                                        let config = alternatives().unwrap_or_default();
+                                        alternatives.set(Some(AlternativeConfig { count, ..config }));
+                                    }
+                                    _ => alternatives.set(None),
+                                }
+                            },
+                        }
+                        if let Some(config) = alternatives() {
+                            span { class: "label-text", "至少相差" }
+                            input {
+                                r#type: "number",
+                                class: "input input-bordered w-20 mx-2",
+                                value: "{config.min_distance}",
+                                min: "1",
+                                oninput: move |evt| {
+                                    if let Ok(min_distance) = evt.value().trim().parse::<usize>()
+                                        && min_distance > 0
+                                    {
+                                        alternatives.set(Some(AlternativeConfig { min_distance, ..config }));
+                                    }
+                                },
+                            }
+                            span { class: "label-text", "人" }
+                        }
+                    }
+                    label { class: "label",
+                        span { class: "label-text-alt text-base-content/60",
+                            "💡 同时给出多个代价相近、彼此差异足够大的方案，在结果页选定后再导出"
+                        }
+                    }
+                }
+                // 多目标模式
+                div { class: "form-control w-full max-w-md",
+                    label { class: "label cursor-pointer justify-start gap-4",
+                        span { class: "label-text font-medium", "多目标模式" }
+                        input {
+                            r#type: "checkbox",
+                            class: "toggle toggle-primary",
+                            checked: pareto(),
+                            onchange: move |evt| pareto.set(evt.checked()),
+                        }
+                    }
+                    label { class: "label",
+                        span { class: "label-text-alt text-base-content/60",
+                            "💡 展示总分、性别、单科均衡之间的取舍，在结果页的前沿图中挑选方案"
+                        }
+                    }
+                }
+                // 重名学生
+                div { class: "form-control w-full max-w-md",
+                    label { class: "label",
+                        span { class: "label-text font-medium", "避免重名同班" }
+                    }
+                    select {
+                        class: "select select-bordered w-40 mx-2",
+                        value: match optimization_params.read().duplicate_names {
+                            None => "off",
+                            Some(NameMatch::Exact) => "exact",
+                            Some(NameMatch::Pinyin) => "pinyin",
+                        },
+                        onchange: move |evt| {
+                            optimization_params.write().duplicate_names = match evt.value().as_str() {
+                                "exact" => Some(NameMatch::Exact),
+                                "pinyin" => Some(NameMatch::Pinyin),
+                                _ => None,
+                            };
+                        },
+                        option { value: "off", "不检查" }
+                        option { value: "exact", "{NameMatch::Exact.label()}" }
+                        option { value: "pinyin", "{NameMatch::Pinyin.label()}" }
+                    }
+                    label { class: "label",
+                        span { class: "label-text-alt text-base-content/60",
+                            "💡 把姓名相同（或读音相同）的学生分到不同班级，便于点名；人数超过班级数时无法完全避免，会在结果中标出"
+                        }
+                    }
+                }
+                // 好友志愿
+                if preference_mode {
+                    div { class: "form-control w-full max-w-md",
+                        label { class: "label",
+                            span { class: "label-text font-medium", "好友志愿" }
+                        }
+                        div { class: "flex items-center gap-2",
+                            span { class: "text-sm", "每条未满足志愿" }
+                            input {
+                                r#type: "number",
+                                class: "input input-bordered input-sm w-20",
+                                value: "{optimization_params.read().preference_weight}",
+                                step: "0.5",
+                                min: "0",
+                                oninput: move |evt| {
+                                    if let Ok(val) = evt.value().parse::<f64>() && val >= 0.0 {
+                                        optimization_params.write().preference_weight = val;
+                                    }
+                                },
+                            }
+                            span { class: "text-sm", "志愿全未满足的学生" }
+                            input {
+                                r#type: "number",
+                                class: "input input-bordered input-sm w-20",
+                                value: "{optimization_params.read().unmet_student_weight}",
+                                step: "1",
+                                min: "0",
+                                oninput: move |evt| {
+                                    if let Ok(val) = evt.value().parse::<f64>() && val >= 0.0 {
+                                        optimization_params.write().unmet_student_weight = val;
+                                    }
+                                },
+                            }
+                        }
+                        label { class: "label",
+                            span { class: "label-text-alt text-base-content/60",
+                                "💡 已配置好友志愿列：在均衡的前提下尽量满足志愿，优先保证每人至少满足一条；数值越大越优先，设为 0 则忽略"
+                            }
+                        }
+                    }
+                }
+                // 选科组合
+                if combination_mode {
+                    div { class: "form-control w-full max-w-md",
+                        label { class: "label",
+                            span { class: "label-text font-medium", "可以同班的选科组合" }
+                        }
+                        textarea {
+                            class: "textarea textarea-bordered h-28",
+                            placeholder: "每行一组，如：物化生 物化地",
+                            value: "{compatibility}",
+                            oninput: move |evt| compatibility.set(evt.value()),
+                        }
+                        label { class: "label",
+                            span { class: "label-text-alt text-base-content/60",
+                                "💡 已配置选科组合列：同一行的组合可以同班，未列出的组合单独成班；按各组人数分配上面的班级总数，再在组内均衡"
+                            }
+                        }
+                    }
+                }
+                // 分层分班
+                if !combination_mode {
+                    div { class: "form-control w-full",
+                        label { class: "label cursor-pointer justify-start gap-4",
+                            span { class: "label-text font-medium", "分层分班" }
+                            input {
+                                r#type: "checkbox",
+                                class: "toggle toggle-primary",
+                                checked: !tiers.read().is_empty(),
+                                onchange: move |evt| {
+                                    if evt.checked() {
+                                        tiers.set(vec![
+                                            TierSpec::new("实验班", TierCutoff::Rank(50), 1),
+                                            TierSpec::new("平行班", TierCutoff::Rank(0), num_classes()),
+                                        ]);
+                                    } else {
+                                        tiers.set(Vec::new());
+                                    }
+                                },
+                            }
+                        }
+                        if !tiers.read().is_empty() {
+                            TierEditor { tiers }
+                        }
+                        label { class: "label",
+                            span { class: "label-text-alt text-base-content/60",
+                                "💡 先按总分把学生划入各层次，再在层内分别均衡；开启后以各层班级数为准，班级编号跨层连续"
+                            }
+                        }
+                    }
+                }
+
+                // 微调模式
+                if reference_mode {
+                    div { class: "alert alert-warning flex-col items-start",
+                        div { class: "font-bold", "微调模式" }
+                        div { class: "text-sm",
+                            "已配置原班级列：将在原分班基础上插入新生并重新平衡，尽量少调动学生。原班级为空的学生视为新生。"
+                        }
+                        div { class: "flex items-center gap-4",
+                            span { class: "text-sm font-medium", "每调动一人的代价" }
+                            input {
+                                r#type: "number",
+                                class: "input input-bordered input-sm w-28",
+                                value: "{optimization_params.read().move_penalty}",
+                                step: "1",
+                                min: "0",
+                                oninput: move |evt| {
+                                    if let Ok(val) = evt.value().parse::<f64>() && val >= 0.0 {
+                                        optimization_params.write().move_penalty = val;
+                                    }
+                                },
+                            }
+                            span { class: "text-xs text-base-content/60",
+                                "越大调动越少，越小班级越均衡"
+                            }
+                        }
+                    }
+                }
 
                 // 约束说明
                 div { class: "alert alert-info",
@@ -534,6 +846,50 @@ pub fn DivisionConfigView(
                                         "触发重新加热所需的最小接受次数阈值。"
                                     }
                                 }
+
+                                // 收敛判定窗口
+                                div { class: "flex items-center gap-4",
+                                    label { class: "shrink-0 w-48 text-sm font-medium",
+                                        "收敛判定窗口"
+                                    }
+                                    input {
+                                        r#type: "number",
+                                        class: "input input-bordered input-sm w-28",
+                                        value: "{optimization_params.read().plateau_window}",
+                                        step: "1000",
+                                        min: "0",
+                                        oninput: move |evt| {
+                                            if let Ok(val) = evt.value().parse::<usize>() {
+                                                optimization_params.write().plateau_window = val;
+                                            }
+                                        },
+                                    }
+                                    span { class: "text-xs text-base-content/60 flex-1",
+                                        "硬约束全部满足后，连续多少次迭代没有改进即停止（0 表示不启用）。"
+                                    }
+                                }
+
+                                // 初始解策略
+                                div { class: "flex items-center gap-4",
+                                    label { class: "shrink-0 w-48 text-sm font-medium",
+                                        "初始解策略"
+                                    }
+                                    select {
+                                        class: "select select-bordered select-sm w-56",
+                                        value: "{optimization_params.read().seeding_strategy.key()}",
+                                        onchange: move |evt| {
+                                            optimization_params.write().seeding_strategy = SeedingStrategy::from_key(
+                                                &evt.value(),
+                                            );
+                                        },
+                                        for strategy in SeedingStrategy::ALL {
+                                            option { key: "{strategy.key()}", value: strategy.key(), {strategy.label()} }
+                                        }
+                                    }
+                                    span { class: "text-xs text-base-content/60 flex-1",
+                                        "退火开始前的初始分班方法；最大差分法同时平衡总分、各科与性别，通常起点更好。"
+                                    }
+                                }
                             }
 
                             // 重置按钮
@@ -558,11 +914,182 @@ pub fn DivisionConfigView(
                     onclick: move |_| on_back.call(()),
                     "返回"
                 }
-                button {
-                    class: "btn btn-primary",
-                    onclick: move |_| on_start.call(()),
-                    "开始分班"
+                div { class: "flex gap-2",
+                    div { class: "join",
+                        select {
+                            class: "select select-bordered join-item",
+                            title: "自动收紧时优先收紧的阈值，其余按默认顺序",
+                            onchange: move |evt| {
+                                let first = Threshold::ALL
+                                    .into_iter()
+                                    .find(|t| t.label() == evt.value())
+                                    .unwrap_or(Threshold::Score);
+                                let mut priority = vec![first];
+                                priority.extend(Threshold::ALL.into_iter().filter(|t| *t != first));
+                                tighten_priority.set(priority);
+                            },
+                            for threshold in Threshold::ALL {
+                                option {
+                                    value: "{threshold.label()}",
+                                    selected: tighten_priority().first() == Some(&threshold),
+                                    "优先{threshold.label()}"
+                                }
+                            }
+                        }
+                        button {
+                            class: "btn btn-outline btn-primary join-item",
+                            title: "多次分班并二分搜索，找出当前数据能满足的最严阈值",
+                            onclick: move |_| on_auto_tighten.call(()),
+                            "自动收紧阈值"
+                        }
+                    }
+                    button {
+                        class: "btn btn-primary",
+                        onclick: move |_| on_start.call(()),
+                        "开始分班"
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// 分层设置表：每行一个层次，按总分从高到低依次划分，最后一层接收剩余学生
+#[component]
+fn TierEditor(tiers: Signal<Vec<TierSpec>>) -> Element {
+    let count = tiers.read().len();
+
+    rsx! {
+        div { class: "overflow-x-auto",
+            table { class: "table table-sm",
+                thead {
+                    tr {
+                        th { "层次名称" }
+                        th { "划分方式" }
+                        th { "班级数" }
+                        th { "均衡方案" }
+                        th {}
+                    }
                 }
+                tbody {
+                    for (index , tier) in tiers().into_iter().enumerate() {
+                        tr { key: "{index}",
+                            td {
+                                input {
+                                    class: "input input-bordered input-sm w-28",
+                                    value: "{tier.name}",
+                                    oninput: move |evt| tiers.write()[index].name = evt.value(),
+                                }
+                            }
+                            td {
+                                if index + 1 == count {
+                                    span { class: "text-sm text-base-content/70", "其余学生" }
+                                } else {
+                                    div { class: "join",
+                                        select {
+                                            class: "select select-bordered select-sm join-item",
+                                            onchange: move |evt| {
+                                                tiers.write()[index].cutoff = match evt.value().as_str() {
+                                                    "score" => TierCutoff::Score(600.0),
+                                                    _ => TierCutoff::Rank(50),
+                                                };
+                                            },
+                                            option {
+                                                value: "rank",
+                                                selected: matches!(tier.cutoff, TierCutoff::Rank(_)),
+                                                "总分前"
+                                            }
+                                            option {
+                                                value: "score",
+                                                selected: matches!(tier.cutoff, TierCutoff::Score(_)),
+                                                "总分不低于"
+                                            }
+                                        }
+                                        input {
+                                            r#type: "number",
+                                            class: "input input-bordered input-sm join-item w-24",
+                                            min: "0",
+                                            value: match tier.cutoff {
+                                                TierCutoff::Rank(rank) => rank.to_string(),
+                                                TierCutoff::Score(score) => score.to_string(),
+                                            },
+                                            oninput: move |evt| {
+                                                let value = evt.value();
+                                                let mut tiers = tiers.write();
+                                                match tiers[index].cutoff {
+                                                    TierCutoff::Rank(ref mut rank) => {
+                                                        if let Ok(val) = value.parse() {
+                                                            *rank = val;
+                                                        }
+                                                    }
+                                                    TierCutoff::Score(ref mut score) => {
+                                                        if let Ok(val) = value.parse() {
+                                                            *score = val;
+                                                        }
+                                                    }
+                                                }
+                                            },
+                                        }
+                                        span { class: "join-item flex items-center px-2 bg-base-200 text-sm",
+                                            if matches!(tier.cutoff, TierCutoff::Rank(_)) {
+                                                "名"
+                                            } else {
+                                                "分"
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                            td {
+                                input {
+                                    r#type: "number",
+                                    class: "input input-bordered input-sm w-20",
+                                    min: "1",
+                                    value: "{tier.num_classes}",
+                                    oninput: move |evt| {
+                                        if let Ok(val) = evt.value().parse::<usize>() && val > 0 {
+                                            tiers.write()[index].num_classes = val;
+                                        }
+                                    },
+                                }
+                            }
+                            td {
+                                select {
+                                    class: "select select-bordered select-sm",
+                                    onchange: move |evt| {
+                                        tiers.write()[index].optimization_params = match evt.value().as_str() {
+                                            "relaxed" => OptimizationParams::relaxed(),
+                                            "strict" => OptimizationParams::strict(),
+                                            _ => OptimizationParams::default(),
+                                        };
+                                    },
+                                    option { value: "default", "默认" }
+                                    option { value: "relaxed", "宽松" }
+                                    option { value: "strict", "严格" }
+                                }
+                            }
+                            td {
+                                button {
+                                    class: "btn btn-ghost btn-xs",
+                                    disabled: count <= 2,
+                                    onclick: move |_| {
+                                        tiers.write().remove(index);
+                                    },
+                                    "删除"
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            button {
+                class: "btn btn-outline btn-sm mt-2",
+                onclick: move |_| {
+                    let mut tiers = tiers.write();
+                    let position = tiers.len().saturating_sub(1);
+                    tiers.insert(position, TierSpec::new("新层次", TierCutoff::Rank(50), 1));
+                },
+                "添加层次"
             }
         }
     }
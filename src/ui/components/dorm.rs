@@ -0,0 +1,27 @@
+use dioxus::prelude::*;
+
+/// 宿舍分配：选择宿舍清单后按性别与床位分配并导出
+#[component]
+pub fn DormPanel(on_allocate_dorms: EventHandler<()>) -> Element {
+    rsx! {
+        div { class: "collapse collapse-arrow bg-base-200 mt-4",
+            input { r#type: "checkbox" }
+            div { class: "collapse-title font-medium", "宿舍分配" }
+            div { class: "collapse-content space-y-4",
+                p { class: "text-sm text-base-content/70",
+                    "按性别与床位数把学生分进宿舍，同班同学尽量住在同一楼栋的同一楼层、每间宿舍的班级尽量少；导出“宿舍分配”表与各宿舍名单"
+                }
+                p { class: "text-sm text-base-content/70",
+                    "宿舍清单为 Excel 或 CSV 文件，需包含“房间号”“床位数”列，可选“楼栋”“楼层”“性别”列；未填性别的房间男女均可"
+                }
+                div { class: "flex justify-end",
+                    button {
+                        class: "btn btn-sm btn-primary",
+                        onclick: move |_| on_allocate_dorms.call(()),
+                        "选择宿舍清单并分配"
+                    }
+                }
+            }
+        }
+    }
+}
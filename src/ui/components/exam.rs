@@ -0,0 +1,125 @@
+use crate::core::exam::{
+    DEFAULT_EXAM_ROOM_CAPACITY, DEFAULT_EXAM_ROOM_COLUMNS, ExamConfig, ExamOrder,
+};
+use dioxus::prelude::*;
+
+/// 考场安排设置：考场人数、列数与排序方式，确认后导出考场安排
+#[component]
+pub fn ExamRoomPanel(
+    /// 全年级人数
+    student_count: usize,
+    on_export_exam: EventHandler<ExamConfig>,
+) -> Element {
+    let mut room_capacity = use_signal(|| DEFAULT_EXAM_ROOM_CAPACITY);
+    let mut columns = use_signal(|| DEFAULT_EXAM_ROOM_COLUMNS);
+    let mut order = use_signal(ExamOrder::default);
+    let mut capacities_text = use_signal(String::new);
+
+    let capacities = move || -> Vec<usize> {
+        capacities_text
+            .read()
+            .split(|c: char| !c.is_ascii_digit())
+            .filter_map(|item| item.parse::<usize>().ok())
+            .filter(|&capacity| capacity > 0)
+            .collect()
+    };
+    let room_count = {
+        let mut remaining = student_count;
+        let mut count = 0;
+        for capacity in capacities() {
+            if remaining == 0 {
+                break;
+            }
+            remaining = remaining.saturating_sub(capacity);
+            count += 1;
+        }
+        count + remaining.div_ceil(room_capacity().max(1))
+    };
+
+    rsx! {
+        div { class: "collapse collapse-arrow bg-base-200 mt-4",
+            input { r#type: "checkbox" }
+            div { class: "collapse-title font-medium", "考场安排" }
+            div { class: "collapse-content space-y-4",
+                p { class: "text-sm text-base-content/70",
+                    "把全年级编入考场并分配座位号，同班考生前后左右尽量不相邻；导出“考场安排”“座位表”及各考场门贴名单"
+                }
+                div { class: "flex flex-wrap items-end gap-4",
+                    label { class: "form-control",
+                        span { class: "label-text mb-1", "每个考场人数" }
+                        input {
+                            r#type: "number",
+                            class: "input input-bordered input-sm w-24",
+                            min: "1",
+                            value: "{room_capacity}",
+                            oninput: move |evt| {
+                                if let Ok(val) = evt.value().parse::<usize>() && val > 0 {
+                                    room_capacity.set(val);
+                                }
+                            },
+                        }
+                    }
+                    label { class: "form-control",
+                        span { class: "label-text mb-1", "每个考场列数" }
+                        input {
+                            r#type: "number",
+                            class: "input input-bordered input-sm w-24",
+                            min: "1",
+                            value: "{columns}",
+                            oninput: move |evt| {
+                                if let Ok(val) = evt.value().parse::<usize>() && val > 0 {
+                                    columns.set(val);
+                                }
+                            },
+                        }
+                    }
+                    label { class: "form-control",
+                        span { class: "label-text mb-1", "前几个考场的人数（可选）" }
+                        input {
+                            r#type: "text",
+                            class: "input input-bordered input-sm w-40",
+                            placeholder: "如 40,40",
+                            value: "{capacities_text}",
+                            oninput: move |evt| capacities_text.set(evt.value()),
+                        }
+                    }
+                    label { class: "form-control",
+                        span { class: "label-text mb-1", "考场顺序" }
+                        select {
+                            class: "select select-bordered select-sm",
+                            onchange: move |evt| {
+                                if let Ok(index) = evt.value().parse::<usize>() {
+                                    order.set(ExamOrder::ALL[index]);
+                                }
+                            },
+                            for (index , item) in ExamOrder::ALL.into_iter().enumerate() {
+                                option {
+                                    key: "{index}",
+                                    value: "{index}",
+                                    selected: order() == item,
+                                    "{item.label()}"
+                                }
+                            }
+                        }
+                    }
+                }
+                div { class: "flex items-center justify-end gap-4",
+                    span { class: "text-sm", "共 {student_count} 人，需 {room_count} 个考场" }
+                    button {
+                        class: "btn btn-sm btn-primary",
+                        onclick: move |_| {
+                            on_export_exam
+                                .call(
+                                    ExamConfig::new(room_capacity())
+                                        .with_capacities(capacities())
+                                        .with_columns(columns())
+                                        .with_order(order()),
+                                )
+                        },
+                        "导出考场安排"
+                    }
+                }
+            }
+        }
+    }
+}
@@ -198,6 +198,18 @@ pub fn HistoryCollapsedView(refresh_trigger: u32) -> Element {
                             div { "{record.num_students}" }
                             div { class: "font-semibold", "输出格式:" }
                             div { {record.format.to_uppercase()} }
+                            div { class: "font-semibold", "随机种子:" }
+                            div {
+                                {record.seed.map(|s| s.to_string()).unwrap_or_else(|| "-".to_string())}
+                            }
+                            div { class: "font-semibold", "并行实例数:" }
+                            div {
+                                {record.num_instances.map(|n| n.to_string()).unwrap_or_else(|| "-".to_string())}
+                            }
+                            div { class: "font-semibold", "迭代次数:" }
+                            div {
+                                {record.iterations.map(|n| n.to_string()).unwrap_or_else(|| "-".to_string())}
+                            }
                         }
                         div { class: "divider text-xs", "优化参数" }
                         div { class: "grid grid-cols-2 gap-2 text-xs",
@@ -213,6 +225,8 @@ pub fn HistoryCollapsedView(refresh_trigger: u32) -> Element {
                             div { "{record.optimization_params.initial_temperature}" }
                             div { class: "font-semibold", "冷却速率:" }
                             div { "{record.optimization_params.cooling_rate}" }
+                            div { class: "font-semibold", "初始解策略:" }
+                            div { {record.optimization_params.seeding_strategy.label()} }
                         }
                     }
                     div { class: "modal-action",
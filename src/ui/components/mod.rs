@@ -26,8 +26,20 @@ pub use column_config::*;
 pub mod division_config;
 pub use division_config::*;
 
+pub mod class_sweep;
+pub use class_sweep::*;
+
 pub mod processing;
 pub use processing::*;
 
 pub mod results;
 pub use results::*;
+
+pub mod seating;
+pub use seating::*;
+
+pub mod exam;
+pub use exam::*;
+
+pub mod dorm;
+pub use dorm::*;
@@ -1,8 +1,19 @@
 use crate::{
-    core::model::{Class, Student},
+    core::{
+        algorithm::{DivideResult, TracePoint, assignment_distance},
+        exam::ExamConfig,
+        groups::{MAX_STUDY_GROUPS, MIN_STUDY_GROUPS},
+        model::{Class, Student},
+        seating::{RoomLayout, SeatingRules},
+    },
     ui::{
         WARNING,
-        components::types::{ColumnMapping, ColumnType},
+        components::{
+            dorm::DormPanel,
+            exam::ExamRoomPanel,
+            seating::{SeatingCharts, SeatingSettings},
+            types::{ColumnMapping, ColumnType},
+        },
     },
 };
 use dioxus::prelude::*;
@@ -12,7 +23,27 @@ pub fn ResultsView(
     classes: Signal<Vec<Class>>,
     summary: Option<String>,
     column_mappings: Signal<Vec<ColumnMapping>>,
+    trace: Signal<Vec<TracePoint>>,
+    options: Signal<Vec<DivideResult>>,
+    selected_option: Signal<Option<usize>>,
+    on_select_option: EventHandler<usize>,
+    front: Signal<Vec<DivideResult>>,
+    on_select_front: EventHandler<usize>,
     on_export: EventHandler<String>,
+    on_export_trace: EventHandler<String>,
+    on_reoptimize: EventHandler<Vec<usize>>,
+    /// 是否支持继续优化与部分重新优化（分层分班的结果不支持）
+    refinable: bool,
+    improvement: Option<String>,
+    on_continue: EventHandler<u64>,
+    /// 把指定班级（索引）分成若干个学习小组，小组数为 0 表示按人数自动确定
+    on_divide_groups: EventHandler<(usize, usize)>,
+    /// 按布局与规则为指定班级（索引）排座位
+    on_arrange_seats: EventHandler<(usize, RoomLayout, SeatingRules)>,
+    /// 按配置安排考场并导出
+    on_export_exam: EventHandler<ExamConfig>,
+    /// 选择宿舍清单并分配宿舍
+    on_allocate_dorms: EventHandler<()>,
     on_restart: EventHandler<()>,
     on_back: EventHandler<()>,
 ) -> Element {
@@ -35,15 +66,46 @@ pub fn ResultsView(
         .map(|m| m.name.clone())
         .collect();
 
+    // 微调模式下显示原班级
+    let has_original_class = classes
+        .read()
+        .iter()
+        .any(|class| class.students.iter().any(|s| s.original_class.is_some()));
+
+    // 有身份证号时按出生日期统计平均年龄
+    let today = chrono::Local::now().date_naive();
+    let has_birth_date = classes
+        .read()
+        .iter()
+        .any(|class| class.avg_age(today).is_some());
+
+    // 有班级分了小组时显示小组列
+    let has_study_groups = classes
+        .read()
+        .iter()
+        .any(|class| class.study_groups.is_some());
+
+    // 有身高时才能按身高排座；有班级排了座位时显示座位表
+    let has_height = classes
+        .read()
+        .iter()
+        .any(|class| class.students.iter().any(|s| s.height.is_some()));
+    let has_seating = classes.read().iter().any(|class| class.seating.is_some());
+    let student_count: usize = classes.read().iter().map(|c| c.students.len()).sum();
+
     // 计算分页
     let classes_read = classes.read();
-    let all_students: Vec<(usize, &Student)> = classes_read
+    let all_students: Vec<(usize, Option<usize>, &Student)> = classes_read
         .iter()
         .flat_map(|class| {
             class
                 .students
                 .iter()
-                .map(move |student| (class.id, student))
+                .enumerate()
+                .map(move |(idx, student)| {
+                    let group = class.study_groups.as_ref().map(|groups| groups[idx]);
+                    (class.id, group, student)
+                })
         })
         .collect();
 
@@ -55,6 +117,19 @@ pub fn ResultsView(
     // 选项卡状态
     let mut active_tab = use_signal(|| "statistics");
 
+    // 选中需要重新优化的班级
+    let mut selected_classes = use_signal(Vec::<usize>::new);
+
+    // 继续优化的时长（秒）
+    let mut continue_seconds = use_signal(|| 30u64);
+
+    // 每班的小组数，0 表示按人数自动确定
+    let mut group_count = use_signal(|| 0usize);
+
+    // 排座位的教室布局与规则（默认 7 排 8 列，两列一张课桌）
+    let seat_layout = use_signal(|| RoomLayout::new(7, 8).with_aisles(vec![1, 3, 5]));
+    let seat_rules = use_signal(SeatingRules::default);
+
     // 检查是否有数据
     if classes_read.is_empty() {
         return rsx! {
@@ -85,6 +160,16 @@ pub fn ResultsView(
                 }
             }
 
+            // 继续优化的效果
+            if let Some(improvement_text) = improvement {
+                div { class: "alert alert-success",
+                    div { class: "w-full",
+                        h3 { class: "font-bold mb-2", "优化前后对比" }
+                        pre { class: "whitespace-pre-wrap text-sm", "{improvement_text}" }
+                    }
+                }
+            }
+
             // 选项卡
             div { class: "tabs tabs-boxed bg-base-200 p-1",
                 a {
@@ -97,6 +182,34 @@ pub fn ResultsView(
                     onclick: move |_| active_tab.set("students"),
                     "学生分班结果"
                 }
+                if !trace.read().is_empty() {
+                    a {
+                        class: if *active_tab.read() == "trace" { "tab tab-active" } else { "tab" },
+                        onclick: move |_| active_tab.set("trace"),
+                        "收敛曲线"
+                    }
+                }
+                if has_seating {
+                    a {
+                        class: if *active_tab.read() == "seating" { "tab tab-active" } else { "tab" },
+                        onclick: move |_| active_tab.set("seating"),
+                        "座位表"
+                    }
+                }
+                if options.read().len() > 1 {
+                    a {
+                        class: if *active_tab.read() == "options" { "tab tab-active" } else { "tab" },
+                        onclick: move |_| active_tab.set("options"),
+                        "备选方案"
+                    }
+                }
+                if !front.read().is_empty() {
+                    a {
+                        class: if *active_tab.read() == "front" { "tab tab-active" } else { "tab" },
+                        onclick: move |_| active_tab.set("front"),
+                        "多目标前沿"
+                    }
+                }
             }
 
             // 选项卡内容
@@ -108,6 +221,9 @@ pub fn ResultsView(
                             table { class: "table table-sm",
                                 thead {
                                     tr {
+                                        if refinable {
+                                            th {}
+                                        }
                                         th { "班级" }
                                         th { "人数" }
                                         th { "男生" }
@@ -117,12 +233,46 @@ pub fn ResultsView(
                                             th { key: "{subject}", "{subject}" }
                                         }
                                         th { "平均总分" }
+                                        if has_birth_date {
+                                            th { "平均年龄" }
+                                        }
+                                        th { "学习小组" }
+                                        th { "座位" }
                                     }
                                 }
                                 tbody {
-                                    for class in classes.read().iter() {
+                                    for (class_idx , class) in classes.read().iter().enumerate() {
                                         tr { key: "{class.id}",
-                                            td { class: "font-semibold", "班级 {class.id + 1}" }
+                                            if refinable {
+                                                td {
+                                                    input {
+                                                        r#type: "checkbox",
+                                                        class: "checkbox checkbox-sm",
+                                                        checked: selected_classes.read().contains(&class.id),
+                                                        onchange: {
+                                                            let class_id = class.id;
+                                                            move |evt: FormEvent| {
+                                                                let mut selected = selected_classes.write();
+                                                                if evt.checked() {
+                                                                    selected.push(class_id);
+                                                                    selected.sort_unstable();
+                                                                } else {
+                                                                    selected.retain(|&c| c != class_id);
+                                                                }
+                                                            }
+                                                        },
+                                                    }
+                                                }
+                                            }
+                                            td { class: "font-semibold",
+                                                "班级 {class.id + 1}"
+                                                if let Some(tier) = &class.tier {
+                                                    span { class: "badge badge-outline badge-sm ml-2", "{tier}" }
+                                                }
+                                                if let Some(combination) = &class.combination {
+                                                    span { class: "badge badge-info badge-outline badge-sm ml-2", "{combination}" }
+                                                }
+                                            }
                                             td { "{class.students.len()}" }
                                             td { "{class.male_count()}" }
                                             td { "{class.female_count()}" }
@@ -131,11 +281,87 @@ pub fn ResultsView(
                                                 td { key: "{subject}", "{class.avg_subject_score(subject):.2}" }
                                             }
                                             td { "{class.avg_total_score():.2}" }
+                                            if has_birth_date {
+                                                td {
+                                                    if let Some(age) = class.avg_age(today) {
+                                                        "{age:.2}"
+                                                    }
+                                                }
+                                            }
+                                            td {
+                                                if class.study_groups.is_some() {
+                                                    span { class: "badge badge-success badge-sm mr-2",
+                                                        "{class.study_group_count()} 组"
+                                                    }
+                                                }
+                                                button {
+                                                    class: "btn btn-xs btn-outline",
+                                                    onclick: move |_| on_divide_groups.call((class_idx, group_count())),
+                                                    if class.study_groups.is_some() {
+                                                        "重新分组"
+                                                    } else {
+                                                        "分小组"
+                                                    }
+                                                }
+                                            }
+                                            td {
+                                                button {
+                                                    class: "btn btn-xs btn-outline",
+                                                    onclick: move |_| on_arrange_seats.call((class_idx, seat_layout(), seat_rules())),
+                                                    if class.seating.is_some() {
+                                                        "重新排座"
+                                                    } else {
+                                                        "排座位"
+                                                    }
+                                                }
+                                            }
                                         }
                                     }
                                 }
                             }
                         }
+                        div { class: "flex items-center justify-end gap-2 mt-4",
+                            span { class: "text-sm text-base-content/60",
+                                "在班内按总分、各科与性别均衡地分成学习小组，导出时增加“小组”列与“小组统计”表"
+                            }
+                            select {
+                                class: "select select-bordered select-sm",
+                                onchange: move |evt| {
+                                    if let Ok(count) = evt.value().parse::<usize>() {
+                                        group_count.set(count);
+                                    }
+                                },
+                                option { value: "0", selected: group_count() == 0, "按人数自动确定" }
+                                for count in MIN_STUDY_GROUPS..=MAX_STUDY_GROUPS {
+                                    option {
+                                        key: "{count}",
+                                        value: "{count}",
+                                        selected: group_count() == count,
+                                        "{count} 组"
+                                    }
+                                }
+                            }
+                        }
+                        SeatingSettings { layout: seat_layout, rules: seat_rules, has_height }
+                        ExamRoomPanel { student_count, on_export_exam }
+                        DormPanel { on_allocate_dorms }
+                        if refinable {
+                            div { class: "flex items-center justify-end gap-2 mt-4",
+                                span { class: "text-sm text-base-content/60",
+                                    "勾选两个及以上班级，只在这些班级之间重新分配，其余班级保持不变"
+                                }
+                                button {
+                                    class: "btn btn-sm btn-secondary",
+                                    disabled: selected_classes.read().len() < 2,
+                                    onclick: move |_| {
+                                        let selected = selected_classes.read().clone();
+                                        selected_classes.set(Vec::new());
+                                        on_reoptimize.call(selected);
+                                    },
+                                    "重新优化选中班级"
+                                }
+                            }
+                        }
                     }
                 },
                 "students" => rsx! {
@@ -149,6 +375,12 @@ pub fn ResultsView(
                                         th { "班级" }
                                         th { "姓名" }
                                         th { "性别" }
+                                        if has_study_groups {
+                                            th { "小组" }
+                                        }
+                                        if has_original_class {
+                                            th { "原班级" }
+                                        }
                                         for field in extra_fields.iter() {
                                             th { key: "{field}", "{field}" }
                                         }
@@ -159,16 +391,35 @@ pub fn ResultsView(
                                     }
                                 }
                                 tbody {
-                                    for (idx , (class_id , student)) in all_students.iter().enumerate().skip(start_idx).take(end_idx - start_idx) {
+                                    for (idx , (class_id , group , student)) in all_students.iter().enumerate().skip(start_idx).take(end_idx - start_idx) {
                                         tr { key: "{idx}",
                                             td { "{idx + 1}" }
                                             td { class: "font-semibold", "{class_id + 1}" }
-                                            td { "{student.name}" }
                                             td {
-                                                if student.gender == crate::core::model::Gender::Male {
-                                                    "男"
-                                                } else {
-                                                    "女"
+                                                "{student.name}"
+                                                if let Some(issue) = &student.identity_issue {
+                                                    span {
+                                                        class: "badge badge-warning badge-sm ml-2",
+                                                        title: issue.describe(),
+                                                        "身份证核对"
+                                                    }
+                                                }
+                                            }
+                                            td { "{student.gender.label()}" }
+                                            if has_study_groups {
+                                                td {
+                                                    if let Some(group) = group {
+                                                        "{group + 1}"
+                                                    }
+                                                }
+                                            }
+                                            if has_original_class {
+                                                td {
+                                                    class: if student.original_class.is_some_and(|c| c != *class_id) { "text-warning font-semibold" } else { "" },
+                                                    match student.original_class {
+                                                        Some(c) => rsx! { "{c + 1}" },
+                                                        None => rsx! { "新生" },
+                                                    }
                                                 }
                                             }
                                             for field in extra_fields.iter() {
@@ -259,6 +510,32 @@ pub fn ResultsView(
                         }
                     }
                 },
+                "trace" => rsx! {
+                    div { class: "space-y-4",
+                        TraceChart { trace }
+                        div { class: "flex justify-end gap-2",
+                            button {
+                                class: "btn btn-sm btn-outline",
+                                onclick: move |_| on_export_trace.call("csv".to_string()),
+                                "导出轨迹 CSV"
+                            }
+                            button {
+                                class: "btn btn-sm btn-outline",
+                                onclick: move |_| on_export_trace.call("json".to_string()),
+                                "导出轨迹 JSON"
+                            }
+                        }
+                    }
+                },
+                "seating" => rsx! {
+                    SeatingCharts { classes }
+                },
+                "options" => rsx! {
+                    OptionsTable { options, selected_option, on_select_option }
+                },
+                "front" => rsx! {
+                    ParetoChart { front, on_select: on_select_front }
+                },
                 _ => rsx! {
                     div {}
                 },
@@ -271,6 +548,28 @@ pub fn ResultsView(
                     onclick: move |_| on_back.call(()),
                     "← 上一步"
                 }
+                if refinable {
+                    div { class: "join",
+                        input {
+                            r#type: "number",
+                            class: "input input-bordered join-item w-20",
+                            title: "继续优化的时长（秒）",
+                            value: "{continue_seconds}",
+                            min: "1",
+                            oninput: move |evt| {
+                                if let Ok(val) = evt.value().parse::<u64>() && val > 0 {
+                                    continue_seconds.set(val);
+                                }
+                            },
+                        }
+                        span { class: "join-item flex items-center px-2 bg-base-200 text-sm", "秒" }
+                        button {
+                            class: "btn btn-accent join-item",
+                            onclick: move |_| on_continue.call(continue_seconds()),
+                            "继续优化"
+                        }
+                    }
+                }
                 button {
                     class: "btn btn-primary",
                     onclick: move |_| on_export.call("xlsx".to_string()),
@@ -290,3 +589,311 @@ pub fn ResultsView(
         }
     }
 }
+
+/// 备选方案对比：每列一个方案，选用后以该方案作为当前结果
+#[component]
+fn OptionsTable(
+    options: Signal<Vec<DivideResult>>,
+    selected_option: Signal<Option<usize>>,
+    on_select_option: EventHandler<usize>,
+) -> Element {
+    let options = options.read();
+    let Some(best) = options.first() else {
+        return rsx! {};
+    };
+    let cost_terms: Vec<&'static str> = best.cost.terms().iter().map(|(name, _)| *name).collect();
+
+    rsx! {
+        div { class: "space-y-4",
+            p { class: "text-sm text-base-content/70",
+                "以下方案代价相近且彼此差异足够大，可任选其一作为最终结果后再导出"
+            }
+            div { class: "overflow-x-auto",
+                table { class: "table table-zebra table-sm w-full",
+                    thead {
+                        tr {
+                            th {}
+                            for index in 0..options.len() {
+                                th { key: "{index}",
+                                    "方案 {index + 1}"
+                                    if selected_option() == Some(index) {
+                                        span { class: "badge badge-primary badge-sm ml-2", "当前" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    tbody {
+                        tr {
+                            td { class: "font-medium", "与方案 1 的差异" }
+                            for (index , option) in options.iter().enumerate() {
+                                td { key: "{index}",
+                                    "{assignment_distance(&best.assignments, &option.assignments)}人"
+                                }
+                            }
+                        }
+                        tr {
+                            td { class: "font-medium", "总代价" }
+                            for (index , option) in options.iter().enumerate() {
+                                td { key: "{index}", "{option.cost.total():.2}" }
+                            }
+                        }
+                        tr {
+                            td { class: "font-medium", "总分最大差值" }
+                            for (index , option) in options.iter().enumerate() {
+                                td { key: "{index}", "{option.validation.max_score_diff:.2}分" }
+                            }
+                        }
+                        tr {
+                            td { class: "font-medium", "性别比例最大差" }
+                            for (index , option) in options.iter().enumerate() {
+                                td { key: "{index}",
+                                    "{option.validation.max_gender_ratio_diff * 100.0:.1}%"
+                                }
+                            }
+                        }
+                        for (term , name) in cost_terms.into_iter().enumerate() {
+                            tr { key: "{name}",
+                                td { class: "text-base-content/70", "{name}" }
+                                for (index , option) in options.iter().enumerate() {
+                                    td { key: "{index}", "{option.cost.terms()[term].1:.2}" }
+                                }
+                            }
+                        }
+                        tr {
+                            td {}
+                            for index in 0..options.len() {
+                                td { key: "{index}",
+                                    button {
+                                        class: "btn btn-xs btn-primary",
+                                        disabled: selected_option() == Some(index),
+                                        onclick: move |_| on_select_option.call(index),
+                                        "选用"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// 帕累托前沿散点图：横轴总分最大差值，纵轴性别比例最大差，颜色深浅表示单科最大差值
+#[component]
+fn ParetoChart(front: Signal<Vec<DivideResult>>, on_select: EventHandler<usize>) -> Element {
+    const WIDTH: f64 = 720.0;
+    const HEIGHT: f64 = 320.0;
+    const PADDING: f64 = 48.0;
+
+    let front = front.read();
+    let subject_diff = |result: &DivideResult| {
+        result
+            .validation
+            .subject_max_diffs
+            .iter()
+            .map(|(_, diff)| *diff)
+            .fold(0.0, f64::max)
+    };
+    let max_x = front
+        .iter()
+        .map(|r| r.validation.max_score_diff)
+        .fold(0.0_f64, f64::max)
+        .max(1e-9);
+    let max_y = front
+        .iter()
+        .map(|r| r.validation.max_gender_ratio_diff)
+        .fold(0.0_f64, f64::max)
+        .max(1e-9);
+    let (min_subject, max_subject) = front
+        .iter()
+        .map(subject_diff)
+        .fold((f64::INFINITY, 0.0_f64), |(lo, hi), v| {
+            (lo.min(v), hi.max(v))
+        });
+
+    let points: Vec<(usize, f64, f64, f64, String)> = front
+        .iter()
+        .enumerate()
+        .map(|(index, result)| {
+            let v = &result.validation;
+            let x = PADDING + v.max_score_diff / max_x * (WIDTH - 2.0 * PADDING);
+            let y = HEIGHT - PADDING - v.max_gender_ratio_diff / max_y * (HEIGHT - 2.0 * PADDING);
+            let subject = subject_diff(result);
+            // 单科差值越小颜色越深
+            let opacity = if max_subject > min_subject {
+                1.0 - 0.7 * (subject - min_subject) / (max_subject - min_subject)
+            } else {
+                1.0
+            };
+            let title = format!(
+                "总分最大差值 {:.2}分，性别比例最大差 {:.1}%，单科最大差值 {:.2}分（点击打开）",
+                v.max_score_diff,
+                v.max_gender_ratio_diff * 100.0,
+                subject
+            );
+            (index, x, y, opacity, title)
+        })
+        .collect();
+
+    rsx! {
+        div { class: "space-y-2",
+            div { class: "text-sm text-base-content/70",
+                "共 {front.len()} 个互不支配的方案：任何一个方案都无法在不牺牲其他目标的情况下改进某一目标。点击圆点打开对应的分班结果。"
+            }
+            svg {
+                class: "w-full bg-base-200 rounded",
+                view_box: "0 0 {WIDTH} {HEIGHT}",
+                line {
+                    x1: "{PADDING}",
+                    y1: "{HEIGHT - PADDING}",
+                    x2: "{WIDTH - PADDING}",
+                    y2: "{HEIGHT - PADDING}",
+                    stroke: "currentColor",
+                    stroke_opacity: "0.4",
+                }
+                line {
+                    x1: "{PADDING}",
+                    y1: "{PADDING}",
+                    x2: "{PADDING}",
+                    y2: "{HEIGHT - PADDING}",
+                    stroke: "currentColor",
+                    stroke_opacity: "0.4",
+                }
+                text {
+                    x: "{WIDTH - PADDING}",
+                    y: "{HEIGHT - PADDING / 3.0}",
+                    text_anchor: "end",
+                    font_size: "12",
+                    fill: "currentColor",
+                    "总分最大差值（最大 {max_x:.2}分）"
+                }
+                text {
+                    x: "{PADDING}",
+                    y: "{PADDING / 2.0}",
+                    font_size: "12",
+                    fill: "currentColor",
+                    "性别比例最大差（最大 {max_y * 100.0:.1}%）"
+                }
+                for (index , x , y , opacity , title) in points {
+                    circle {
+                        key: "{index}",
+                        cx: "{x:.1}",
+                        cy: "{y:.1}",
+                        r: "7",
+                        fill: "#3b82f6",
+                        fill_opacity: "{opacity:.2}",
+                        stroke: "#1e40af",
+                        style: "cursor: pointer",
+                        onclick: move |_| on_select.call(index),
+                        title { "{title}" }
+                    }
+                }
+            }
+            div { class: "text-xs text-base-content/60",
+                "颜色越深表示单科最大差值越小（{min_subject:.2}分 ~ {max_subject:.2}分）"
+            }
+        }
+    }
+}
+
+/// 收敛曲线：各实例最优代价随迭代次数的变化（纵轴为对数刻度）
+#[component]
+fn TraceChart(trace: Signal<Vec<TracePoint>>) -> Element {
+    const WIDTH: f64 = 720.0;
+    const HEIGHT: f64 = 280.0;
+    const PADDING: f64 = 40.0;
+    const COLORS: [&str; 8] = [
+        "#3b82f6", "#ef4444", "#10b981", "#f59e0b", "#8b5cf6", "#ec4899", "#14b8a6", "#64748b",
+    ];
+
+    let trace = trace.read();
+    let num_instances = trace.iter().map(|p| p.instance + 1).max().unwrap_or(0);
+    let max_iteration = trace.iter().map(|p| p.iteration).max().unwrap_or(1).max(1) as f64;
+
+    // 代价跨越多个数量级，使用 log10(1 + cost)
+    let scale = |cost: f64| (1.0 + cost.max(0.0)).log10();
+    let max_y = trace
+        .iter()
+        .map(|p| scale(p.best_cost))
+        .fold(0.0_f64, f64::max)
+        .max(1e-9);
+
+    let to_x =
+        |iteration: usize| PADDING + iteration as f64 / max_iteration * (WIDTH - 2.0 * PADDING);
+    let to_y = |cost: f64| HEIGHT - PADDING - scale(cost) / max_y * (HEIGHT - 2.0 * PADDING);
+
+    let lines: Vec<(usize, String)> = (0..num_instances)
+        .map(|instance| {
+            let points = trace
+                .iter()
+                .filter(|p| p.instance == instance)
+                .map(|p| format!("{:.1},{:.1}", to_x(p.iteration), to_y(p.best_cost)))
+                .collect::<Vec<_>>()
+                .join(" ");
+            (instance, points)
+        })
+        .collect();
+
+    let final_best = trace
+        .iter()
+        .map(|p| p.best_cost)
+        .fold(f64::INFINITY, f64::min);
+    let total_reheats: usize = (0..num_instances)
+        .filter_map(|instance| trace.iter().rfind(|p| p.instance == instance))
+        .map(|p| p.reheats)
+        .sum();
+
+    rsx! {
+        div { class: "space-y-2",
+            div { class: "text-sm text-base-content/70",
+                "实例数: {num_instances}　最终最优代价: {final_best:.4}　累计重新加热: {total_reheats} 次"
+            }
+            svg {
+                class: "w-full bg-base-200 rounded",
+                view_box: "0 0 {WIDTH} {HEIGHT}",
+                line {
+                    x1: "{PADDING}",
+                    y1: "{HEIGHT - PADDING}",
+                    x2: "{WIDTH - PADDING}",
+                    y2: "{HEIGHT - PADDING}",
+                    stroke: "currentColor",
+                    stroke_opacity: "0.4",
+                }
+                line {
+                    x1: "{PADDING}",
+                    y1: "{PADDING}",
+                    x2: "{PADDING}",
+                    y2: "{HEIGHT - PADDING}",
+                    stroke: "currentColor",
+                    stroke_opacity: "0.4",
+                }
+                text {
+                    x: "{WIDTH - PADDING}",
+                    y: "{HEIGHT - PADDING / 2.0}",
+                    text_anchor: "end",
+                    font_size: "12",
+                    fill: "currentColor",
+                    "迭代 {max_iteration}"
+                }
+                text {
+                    x: "{PADDING}",
+                    y: "{PADDING / 2.0}",
+                    font_size: "12",
+                    fill: "currentColor",
+                    "最优代价（对数）"
+                }
+                for (instance , points) in lines {
+                    polyline {
+                        key: "{instance}",
+                        points: "{points}",
+                        fill: "none",
+                        stroke: COLORS[instance % COLORS.len()],
+                        stroke_width: "1.5",
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,198 @@
+use crate::core::{
+    model::{Class, Gender},
+    seating::{DeskMateRule, RoomLayout, SeatingRules, parse_keep_apart},
+};
+use dioxus::prelude::*;
+
+/// 排座位设置：教室布局与座位规则
+#[component]
+pub fn SeatingSettings(
+    layout: Signal<RoomLayout>,
+    rules: Signal<SeatingRules>,
+    /// 是否导入了身高列
+    has_height: bool,
+) -> Element {
+    let mut aisles_text = use_signal(|| {
+        layout
+            .read()
+            .aisles_after
+            .iter()
+            .map(|col| (col + 1).to_string())
+            .collect::<Vec<_>>()
+            .join(",")
+    });
+    let mut keep_apart_text = use_signal(String::new);
+
+    rsx! {
+        div { class: "collapse collapse-arrow bg-base-200 mt-4",
+            input { r#type: "checkbox" }
+            div { class: "collapse-title font-medium", "排座位设置" }
+            div { class: "collapse-content space-y-4",
+                p { class: "text-sm text-base-content/70",
+                    "按教室布局为每个班排座位：第 1 排离讲台最近，过道两侧不算同桌也不算相邻；导出 Excel 时每个已排座位的班级增加一张座位表"
+                }
+                div { class: "flex flex-wrap items-end gap-4",
+                    label { class: "form-control",
+                        span { class: "label-text mb-1", "排数 × 列数" }
+                        div { class: "flex items-center gap-2",
+                            input {
+                                r#type: "number",
+                                class: "input input-bordered input-sm w-20",
+                                min: "1",
+                                value: "{layout.read().rows}",
+                                oninput: move |evt| {
+                                    if let Ok(val) = evt.value().parse::<usize>() && val > 0 {
+                                        layout.write().rows = val;
+                                    }
+                                },
+                            }
+                            span { "×" }
+                            input {
+                                r#type: "number",
+                                class: "input input-bordered input-sm w-20",
+                                min: "1",
+                                value: "{layout.read().columns}",
+                                oninput: move |evt| {
+                                    if let Ok(val) = evt.value().parse::<usize>() && val > 0 {
+                                        layout.write().columns = val;
+                                    }
+                                },
+                            }
+                        }
+                    }
+                    label { class: "form-control",
+                        span { class: "label-text mb-1", "过道在第几列之后" }
+                        input {
+                            r#type: "text",
+                            class: "input input-bordered input-sm w-32",
+                            placeholder: "如 2,4,6",
+                            value: "{aisles_text}",
+                            oninput: move |evt| {
+                                let value = evt.value();
+                                let aisles: Vec<usize> = value
+                                    .split(|c: char| !c.is_ascii_digit())
+                                    .filter_map(|item| item.parse::<usize>().ok())
+                                    .filter_map(|col| col.checked_sub(1))
+                                    .collect();
+                                let current = layout.read().clone();
+                                layout.set(current.with_aisles(aisles));
+                                aisles_text.set(value);
+                            },
+                        }
+                    }
+                    label { class: "form-control",
+                        span { class: "label-text mb-1", "同桌搭配" }
+                        select {
+                            class: "select select-bordered select-sm",
+                            onchange: move |evt| {
+                                if let Ok(index) = evt.value().parse::<usize>() {
+                                    rules.write().desk_mates = DeskMateRule::ALL[index];
+                                }
+                            },
+                            for (index , rule) in DeskMateRule::ALL.into_iter().enumerate() {
+                                option {
+                                    key: "{index}",
+                                    value: "{index}",
+                                    selected: rules.read().desk_mates == rule,
+                                    "{rule.label()}"
+                                }
+                            }
+                        }
+                    }
+                    label { class: "label cursor-pointer gap-2",
+                        input {
+                            r#type: "checkbox",
+                            class: "checkbox checkbox-sm",
+                            disabled: !has_height,
+                            checked: rules.read().by_height,
+                            onchange: move |evt| rules.write().by_height = evt.checked(),
+                        }
+                        span { class: "label-text",
+                            if has_height {
+                                "按身高排座（矮的在前）"
+                            } else {
+                                "按身高排座（未导入身高列）"
+                            }
+                        }
+                    }
+                }
+                label { class: "form-control",
+                    span { class: "label-text mb-1",
+                        "需要分开的学生：每行一组，填写学号或姓名，组内任意两人不相邻"
+                    }
+                    textarea {
+                        class: "textarea textarea-bordered textarea-sm h-20",
+                        placeholder: "张伟、李娜\nS003 S017",
+                        value: "{keep_apart_text}",
+                        oninput: move |evt| {
+                            rules.write().keep_apart = parse_keep_apart(&evt.value());
+                            keep_apart_text.set(evt.value());
+                        },
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// 各班座位表：讲台在上，第 1 排离讲台最近
+#[component]
+pub fn SeatingCharts(classes: Signal<Vec<Class>>) -> Element {
+    let classes = classes.read();
+
+    rsx! {
+        div { class: "space-y-6",
+            for class in classes.iter() {
+                if let Some(chart) = &class.seating {
+                    div { key: "{class.id}", class: "space-y-2",
+                        h3 { class: "font-semibold", "班级 {class.id + 1}" }
+                        div { class: "overflow-x-auto",
+                            table { class: "table table-xs text-center",
+                                thead {
+                                    tr {
+                                        th {}
+                                        th {
+                                            colspan: chart.layout.columns + chart.layout.aisles_after.len(),
+                                            class: "bg-base-200",
+                                            "讲台"
+                                        }
+                                    }
+                                }
+                                tbody {
+                                    for (row , seats) in chart.seats.iter().enumerate() {
+                                        tr { key: "{row}",
+                                            th { "第 {row + 1} 排" }
+                                            for (col , seat) in seats.iter().enumerate() {
+                                                td {
+                                                    class: seat_class(seat.map(|idx| class.students[idx].gender)),
+                                                    if let Some(idx) = seat {
+                                                        "{class.students[*idx].name}"
+                                                    }
+                                                }
+                                                if chart.layout.has_aisle_after(col) {
+                                                    td { class: "w-4" }
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                        for issue in chart.issues.iter() {
+                            p { class: "text-sm text-warning", "⚠ {issue}" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// 座位格样式：按性别着色
+fn seat_class(gender: Option<Gender>) -> &'static str {
+    match gender {
+        Some(Gender::Male) => "border border-base-300 text-info",
+        Some(Gender::Female) => "border border-base-300 text-secondary",
+        _ => "border border-base-300",
+    }
+}
@@ -23,6 +23,11 @@ pub enum ColumnType {
     TotalScore,
     Subject,
     Extra,
+    OriginalClass,
+    Combination,
+    NationalId,
+    Preferences,
+    Height,
     Ignore,
 }
 
@@ -35,6 +40,11 @@ impl ColumnType {
             ColumnType::TotalScore => "total",
             ColumnType::Subject => "subject",
             ColumnType::Extra => "extra",
+            ColumnType::OriginalClass => "original_class",
+            ColumnType::Combination => "combination",
+            ColumnType::NationalId => "national_id",
+            ColumnType::Preferences => "preferences",
+            ColumnType::Height => "height",
             ColumnType::Ignore => "ignore",
         }
     }
@@ -47,6 +57,11 @@ impl ColumnType {
             "total" => ColumnType::TotalScore,
             "subject" => ColumnType::Subject,
             "extra" => ColumnType::Extra,
+            "original_class" => ColumnType::OriginalClass,
+            "combination" => ColumnType::Combination,
+            "national_id" => ColumnType::NationalId,
+            "preferences" => ColumnType::Preferences,
+            "height" => ColumnType::Height,
             _ => ColumnType::Ignore,
         }
     }
@@ -92,8 +92,18 @@ pub fn infer_column_type(name: &str) -> ColumnType {
 
     if name_lower.contains("姓名") || name_lower.contains("name") {
         ColumnType::Name
+    } else if name_lower.contains("身份证") {
+        ColumnType::NationalId
     } else if name_lower.contains("性别") || name_lower.contains("gender") {
         ColumnType::Gender
+    } else if name_lower.contains("身高") || name_lower.contains("height") {
+        ColumnType::Height
+    } else if name_lower.contains("好友")
+        || name_lower.contains("朋友")
+        || name_lower.contains("同伴")
+        || name_lower.contains("friend")
+    {
+        ColumnType::Preferences
     } else if name_lower.contains("学号")
         || name_lower.contains("id")
         || name_lower.contains("编号")
@@ -102,6 +112,8 @@ pub fn infer_column_type(name: &str) -> ColumnType {
     } else if name_lower.contains("总分") || name_lower.contains("总成绩") || name_lower == "total"
     {
         ColumnType::TotalScore
+    } else if name_lower.contains("选科") || name_lower.contains("组合") {
+        ColumnType::Combination
     } else if name_lower.contains("语文")
         || name_lower.contains("数学")
         || name_lower.contains("英语")
@@ -115,10 +127,9 @@ pub fn infer_column_type(name: &str) -> ColumnType {
         || name_lower.contains("外语")
     {
         ColumnType::Subject
-    } else if name_lower.contains("班级")
-        || name_lower.contains("备注")
-        || name_lower.contains("原班级")
-    {
+    } else if name_lower.contains("原班级") {
+        ColumnType::OriginalClass
+    } else if name_lower.contains("班级") || name_lower.contains("备注") {
         ColumnType::Extra
     } else {
         ColumnType::Ignore
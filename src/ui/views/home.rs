@@ -1,13 +1,26 @@
 use crate::core::{
     algorithm::{
-        DivideConfig, OptimizationParams, divide_with_cancel, validate_constraints_with_params,
+        AlternativeConfig, DivideConfig, DivideResult, OptimizationParams, TracePoint,
+        divide_with_cancel,
     },
+    dorm::{allocate_dorms, parse_room_inventory},
+    electives::{divide_by_combination, parse_compatibility},
+    exam::{ExamConfig, arrange_exam_rooms},
+    explore::{
+        SweepConfig, SweepResult, Threshold, TightenConfig, auto_tighten, sweep_class_counts,
+    },
+    groups::{recommended_group_count, study_group_config},
     history::{HistoryManager, HistoryRecord},
-    io::{ColumnConfig, export_to_csv, export_to_excel, read_from_csv, read_from_excel},
-    model::Class,
+    io::{
+        ColumnConfig, export_dorm_plan, export_exam_plan, export_to_csv, export_to_excel,
+        export_trace_to_csv, export_trace_to_json, import_summary, read_from_csv, read_from_excel,
+    },
+    model::{Class, GenderAliases, Student},
+    seating::{RoomLayout, SeatingRules},
+    tiers::{TierSpec, divide_tiered},
 };
 use crate::ui::components::*;
-use crate::ui::{ERROR, HISTORY, LOGO, SUCCESS};
+use crate::ui::{ERROR, HISTORY, INFO, LOGO, SUCCESS, WARNING};
 use dioxus::prelude::*;
 use rfd::AsyncFileDialog;
 use tokio_util::sync::CancellationToken;
@@ -19,13 +32,36 @@ pub fn Home() -> Element {
     let mut headers = use_signal(Vec::<String>::new);
     let mut preview_data = use_signal(Vec::<Vec<String>>::new);
     let mut column_mappings = use_signal(Vec::<ColumnMapping>::new);
+    let mut gender_aliases = use_signal(GenderAliases::default); // 性别取值的别名表
+    let mut import_message = use_signal(|| None::<String>); // 最近一次读取学生数据的摘要
     let num_classes = use_signal(|| 2);
     let mut processing = use_signal(|| false);
     let mut error_message = use_signal(|| None::<String>);
     let mut success_message = use_signal(|| None::<String>);
     let mut result_classes = use_signal(Vec::<Class>::new);
     let mut result_summary = use_signal(|| None::<String>);
+    let mut result_students = use_signal(Vec::<Student>::new); // 分班输入的学生（用于继续优化）
+    let mut result_assignments = use_signal(Vec::<usize>::new); // 当前结果的班级分配
+    let mut result_partial = use_signal(|| false); // 当前结果是否为取消时的部分结果
     let mut optimization_params = use_signal(OptimizationParams::default);
+    let mut seed = use_signal(|| None::<u64>); // 用户指定的随机种子
+    let mut result_seed = use_signal(|| None::<u64>); // 本次分班实际使用的种子
+    let mut result_run_info = use_signal(Vec::<(String, String)>::new); // 导出用的运行信息
+    let mut time_budget = use_signal(|| None::<u64>); // 时间预算（秒）
+    let mut trace_interval = use_signal(|| None::<usize>); // 收敛轨迹记录间隔
+    let mut result_trace = use_signal(Vec::<TracePoint>::new); // 本次分班的收敛轨迹
+    let mut last_result = use_signal(|| None::<DivideResult>); // 当前结果（继续优化时用于对比）
+    let mut result_improvement = use_signal(|| None::<String>); // 继续优化前后的对比
+    let tighten_priority = use_signal(|| Threshold::ALL.to_vec()); // 自动收紧的优先顺序
+    let mut sweep_result = use_signal(|| None::<SweepResult>); // 班级数量对比结果
+    let mut alternatives = use_signal(|| None::<AlternativeConfig>); // 备选方案设置
+    let mut result_options = use_signal(Vec::<DivideResult>::new); // 最优解与备选方案
+    let mut selected_option = use_signal(|| Some(0usize)); // 当前选用的方案，选用前沿方案时为 None
+    let mut pareto = use_signal(|| false); // 多目标模式
+    let mut result_front = use_signal(Vec::<DivideResult>::new); // 多目标模式的帕累托前沿
+    let mut tiers = use_signal(Vec::<TierSpec>::new); // 分层分班设置，为空表示不分层
+    let mut compatibility = use_signal(String::new); // 可以同班的选科组合，每行一组
+    let mut result_grouped = use_signal(|| false); // 当前结果是否为分组（分层或选科）分班
     let mut last_export_path = use_signal(|| None::<String>);
     let history_refresh = use_signal(|| 0u32); // 用于触发历史记录刷新
     let mut cancel_token = use_signal(|| None::<CancellationToken>); // 取消标志
@@ -84,10 +120,11 @@ pub fn Home() -> Element {
             .read()
             .iter()
             .any(|m| m.column_type == ColumnType::Name);
+        // 没有性别列时由身份证号推出性别
         let has_gender = column_mappings
             .read()
             .iter()
-            .any(|m| m.column_type == ColumnType::Gender);
+            .any(|m| matches!(m.column_type, ColumnType::Gender | ColumnType::NationalId));
         let has_subjects = column_mappings
             .read()
             .iter()
@@ -98,7 +135,7 @@ pub fn Home() -> Element {
             return;
         }
         if !has_gender {
-            error_message.set(Some("请指定性别列".to_string()));
+            error_message.set(Some("请指定性别列或身份证号列".to_string()));
             return;
         }
         if !has_subjects {
@@ -110,16 +147,249 @@ pub fn Home() -> Element {
         step.set(AppStep::ConfigureDivision);
     };
 
-    // 开始分班
-    let start_division = move |_| {
+    // 展示分班结果，`extra_info` 追加到运行信息中
+    let mut show_result = move |students: Vec<Student>,
+                                mut result: DivideResult,
+                                previous: Option<DivideResult>,
+                                extra_info: Vec<(String, String)>| {
+        let alternatives = std::mem::take(&mut result.alternatives);
+        result_grouped.set(false);
+        result_front.set(std::mem::take(&mut result.pareto_front));
+        let mut run_info = result.summary_items();
+        run_info.extend(extra_info);
+        let summary = format_items(&run_info);
+
+        // 取消时保留当前找到的最优解，由用户决定接受、继续优化或丢弃
+        if result.is_partial() {
+            success_message.set(None);
+            error_message.set(Some(
+                "分班已取消，当前显示的是已找到的最优部分结果".to_string(),
+            ));
+        } else {
+            success_message.set(Some("分班成功！".to_string()));
+        }
+
+        // 热启动时与之前的结果对比
+        let improvement =
+            previous.map(|previous| format_items(&result.improvement_items(&previous)));
+        result_improvement.set(improvement);
+        last_result.set(Some(result.clone()));
+
+        // 方案 1 为最优解，其后为备选方案
+        let mut options = vec![result.clone()];
+        options.extend(alternatives);
+        result_options.set(options);
+        selected_option.set(Some(0));
+
+        result_partial.set(result.is_partial());
+        result_summary.set(Some(summary));
+        result_seed.set(Some(result.seed));
+        result_run_info.set(run_info);
+        result_trace.set(result.trace);
+        result_assignments.set(result.assignments);
+        result_students.set(students);
+        result_classes.set(result.classes);
+        step.set(AppStep::Results);
+    };
+
+    // 在后台线程运行分班算法并展示结果（开始分班与继续优化共用）
+    let run_division = move |students: Vec<Student>,
+                             divide_config: DivideConfig,
+                             cancel: CancellationToken,
+                             previous: Option<DivideResult>| async move {
+        // 在单独的线程中运行分班算法，避免阻塞 UI
+        let divide_result = tokio::task::spawn_blocking(move || {
+            let result = divide_with_cancel(&students, divide_config, cancel);
+            (students, result)
+        })
+        .await;
+
+        match divide_result {
+            Ok((students, result)) => show_result(students, result, previous, Vec::new()),
+            Err(e) => {
+                error_message.set(Some(format!("分班任务失败: {}", e)));
+                step.set(AppStep::ConfigureDivision);
+            }
+        }
+    };
+
+    // 在后台线程自动收紧阈值，并把找到的最严阈值写回参数设置
+    let run_auto_tighten = move |students: Vec<Student>,
+                                 divide_config: DivideConfig,
+                                 tighten: TightenConfig,
+                                 cancel: CancellationToken| async move {
+        let tighten_result = tokio::task::spawn_blocking(move || {
+            let result = auto_tighten(&students, divide_config, &tighten, cancel);
+            (students, result)
+        })
+        .await;
+
+        match tighten_result {
+            Ok((students, tightened)) => {
+                let extra_info = tightened.summary_items();
+                optimization_params.set(tightened.params);
+                show_result(students, tightened.result, None, extra_info);
+            }
+            Err(e) => {
+                error_message.set(Some(format!("自动收紧任务失败: {}", e)));
+                step.set(AppStep::ConfigureDivision);
+            }
+        }
+    };
+
+    // 展示分组分班结果；各组独立均衡，结果不支持继续优化
+    let mut show_grouped = move |students: Vec<Student>,
+                                 run_info: Vec<(String, String)>,
+                                 partial: bool,
+                                 run_seed: Option<u64>,
+                                 assignments: Vec<usize>,
+                                 classes: Vec<Class>,
+                                 message: &str| {
+        if partial {
+            success_message.set(None);
+            error_message.set(Some(
+                "分班已取消，当前显示的是已找到的最优部分结果".to_string(),
+            ));
+        } else {
+            success_message.set(Some(message.to_string()));
+        }
+
+        result_grouped.set(true);
+        result_improvement.set(None);
+        last_result.set(None);
+        result_options.set(Vec::new());
+        result_front.set(Vec::new());
+        result_partial.set(partial);
+        result_summary.set(Some(format_items(&run_info)));
+        result_seed.set(run_seed);
+        result_run_info.set(run_info);
+        result_trace.set(Vec::new());
+        result_assignments.set(assignments);
+        result_students.set(students);
+        result_classes.set(classes);
+        step.set(AppStep::Results);
+    };
+
+    // 在后台线程分层分班
+    let run_tiered = move |students: Vec<Student>,
+                           tier_specs: Vec<TierSpec>,
+                           divide_config: DivideConfig,
+                           cancel: CancellationToken| async move {
+        let tiered_result = tokio::task::spawn_blocking(move || {
+            let result = divide_tiered(&students, &tier_specs, &divide_config, cancel);
+            (students, result)
+        })
+        .await;
+
+        match tiered_result {
+            Ok((students, Ok(result))) => show_grouped(
+                students,
+                result.summary_items(),
+                result.is_partial(),
+                result.tiers.first().map(|tier| tier.result.seed),
+                result.assignments,
+                result.classes,
+                "分层分班成功！",
+            ),
+            Ok((_, Err(e))) => {
+                error_message.set(Some(format!("分层设置有误: {}", e)));
+                step.set(AppStep::ConfigureDivision);
+            }
+            Err(e) => {
+                error_message.set(Some(format!("分班任务失败: {}", e)));
+                step.set(AppStep::ConfigureDivision);
+            }
+        }
+    };
+
+    // 在后台线程按选科组合组班
+    let run_combination = move |students: Vec<Student>,
+                                compatible: Vec<Vec<String>>,
+                                divide_config: DivideConfig,
+                                cancel: CancellationToken| async move {
+        let combination_result = tokio::task::spawn_blocking(move || {
+            let result = divide_by_combination(&students, &compatible, &divide_config, cancel);
+            (students, result)
+        })
+        .await;
+
+        match combination_result {
+            Ok((students, Ok(result))) => show_grouped(
+                students,
+                result.summary_items(),
+                result.is_partial(),
+                result.groups.first().map(|(_, _, result)| result.seed),
+                result.assignments,
+                result.classes,
+                "选科组班成功！",
+            ),
+            Ok((_, Err(e))) => {
+                error_message.set(Some(format!("选科组合设置有误: {}", e)));
+                step.set(AppStep::ConfigureDivision);
+            }
+            Err(e) => {
+                error_message.set(Some(format!("分班任务失败: {}", e)));
+                step.set(AppStep::ConfigureDivision);
+            }
+        }
+    };
+
+    // 在后台线程对比多个班级数量，完成后回到参数设置页
+    let run_sweep = move |students: Vec<Student>,
+                          divide_config: DivideConfig,
+                          sweep: SweepConfig,
+                          cancel: CancellationToken| async move {
+        let result = tokio::task::spawn_blocking(move || {
+            sweep_class_counts(&students, divide_config, &sweep, cancel)
+        })
+        .await;
+
+        match result {
+            Ok(result) => {
+                success_message.set(Some(format!(
+                    "已完成 {} 个班级数量的对比",
+                    result.entries.len()
+                )));
+                sweep_result.set(Some(result));
+            }
+            Err(e) => error_message.set(Some(format!("班级数量对比失败: {}", e))),
+        }
+        step.set(AppStep::ConfigureDivision);
+    };
+
+    // 映射了选科组合列时按组合组班
+    let has_combination_column = move || {
+        column_mappings
+            .read()
+            .iter()
+            .any(|m| m.column_type == ColumnType::Combination)
+    };
+
+    // 映射了好友志愿列时显示志愿权重设置
+    let has_preference_column = move || {
+        column_mappings
+            .read()
+            .iter()
+            .any(|m| m.column_type == ColumnType::Preferences)
+    };
+
+    // 读取学生数据后按 `mode` 分班、自动收紧阈值或对比班级数量
+    let mut launch = move |mode: RunMode| {
         let path = match file_path.read().clone() {
             Some(p) => p,
             None => return,
         };
 
         let mappings = column_mappings.read().clone();
+        let aliases = gender_aliases();
         let classes = *num_classes.read();
         let opt_params = optimization_params.read().clone();
+        // 未指定种子时随机生成一个，便于之后复现
+        let run_seed = seed.read().unwrap_or_else(|| rand::random::<u32>() as u64);
+        let budget = time_budget().map(std::time::Duration::from_secs);
+        let trace = trace_interval();
+        let alternative_config = alternatives();
+        let pareto_mode = pareto();
 
         // 创建取消标志
         let cancel = CancellationToken::new();
@@ -135,7 +405,7 @@ pub fn Home() -> Element {
             tokio::time::sleep(std::time::Duration::from_millis(100)).await;
 
             // 构建列配置
-            let mut config_builder = ColumnConfig::builder();
+            let mut config_builder = ColumnConfig::builder().gender_aliases(aliases);
             let mut subject_names = Vec::new();
             let mut extra_field_names = Vec::new();
 
@@ -163,6 +433,21 @@ pub fn Home() -> Element {
                             config_builder.add_extra(mapping.name.clone(), mapping.index);
                         extra_field_names.push(mapping.name.clone());
                     }
+                    ColumnType::OriginalClass => {
+                        config_builder = config_builder.original_class(mapping.index);
+                    }
+                    ColumnType::Combination => {
+                        config_builder = config_builder.combination(mapping.index);
+                    }
+                    ColumnType::NationalId => {
+                        config_builder = config_builder.national_id(mapping.index);
+                    }
+                    ColumnType::Preferences => {
+                        config_builder = config_builder.preferences(mapping.index);
+                    }
+                    ColumnType::Height => {
+                        config_builder = config_builder.height(mapping.index);
+                    }
                     ColumnType::Ignore => {}
                 }
             }
@@ -179,49 +464,33 @@ pub fn Home() -> Element {
 
                     match students_result {
                         Ok(students) => {
-                            let student_count = students.len();
+                            import_message.set(Some(import_summary(&students)));
+                            let reference = reference_assignments(&students);
                             let divide_config = DivideConfig::new(classes)
-                                .with_optimization_params(opt_params.clone());
+                                .with_optimization_params(opt_params)
+                                .with_seed(Some(run_seed))
+                                .with_time_budget(budget)
+                                .with_trace_interval(trace)
+                                .with_reference_assignments(reference)
+                                .with_alternatives(alternative_config)
+                                .with_pareto(pareto_mode);
 
-                            // 在单独的线程中运行分班算法，避免阻塞 UI
-                            let cancel_clone = cancel.clone();
-                            let divide_result = tokio::task::spawn_blocking(move || {
-                                divide_with_cancel(&students, divide_config, cancel_clone)
-                            })
-                            .await;
-
-                            match divide_result {
-                                Ok(classes_result) => {
-                                    // 检查是否被取消
-                                    if cancel.is_cancelled() {
-                                        error_message.set(Some("分班已取消".to_string()));
-                                        step.set(AppStep::ConfigureDivision);
-                                    } else {
-                                        let divide_config = DivideConfig::new(classes)
-                                            .with_optimization_params(opt_params.clone());
-                                        let validation = validate_constraints_with_params(
-                                            &classes_result,
-                                            &divide_config.optimization_params,
-                                        );
-
-                                        let summary = format!(
-                                            "学生总数: {}\n班级数量: {}\n总分最大差值: {:.2}分\n性别比例最大差: {:.1}%\n班级人数最大差: {}人",
-                                            student_count,
-                                            classes,
-                                            validation.max_score_diff,
-                                            validation.max_gender_ratio_diff * 100.0,
-                                            validation.max_class_size_diff,
-                                        );
-
-                                        result_summary.set(Some(summary));
-                                        result_classes.set(classes_result);
-                                        success_message.set(Some("分班成功！".to_string()));
-                                        step.set(AppStep::Results);
-                                    }
+                            match mode {
+                                RunMode::Divide => {
+                                    run_division(students, divide_config, cancel, None).await
                                 }
-                                Err(e) => {
-                                    error_message.set(Some(format!("分班任务失败: {}", e)));
-                                    step.set(AppStep::ConfigureDivision);
+                                RunMode::Tighten(tighten) => {
+                                    run_auto_tighten(students, divide_config, tighten, cancel).await
+                                }
+                                RunMode::Sweep(sweep) => {
+                                    run_sweep(students, divide_config, sweep, cancel).await
+                                }
+                                RunMode::Tiered(tier_specs) => {
+                                    run_tiered(students, tier_specs, divide_config, cancel).await
+                                }
+                                RunMode::Combination(compatible) => {
+                                    run_combination(students, compatible, divide_config, cancel)
+                                        .await
                                 }
                             }
                         }
@@ -242,6 +511,172 @@ pub fn Home() -> Element {
         });
     };
 
+    let start_division = move |_| {
+        let tier_specs = tiers();
+        if has_combination_column() {
+            launch(RunMode::Combination(parse_compatibility(&compatibility())))
+        } else if tier_specs.is_empty() {
+            launch(RunMode::Divide)
+        } else {
+            launch(RunMode::Tiered(tier_specs))
+        }
+    };
+
+    // 在 [0, 当前阈值] 上逐项二分，找出能满足的最严阈值组合
+    let start_auto_tighten = move |_| {
+        launch(RunMode::Tighten(TightenConfig {
+            priority: tighten_priority(),
+            ..Default::default()
+        }))
+    };
+
+    let start_sweep = move |sweep: SweepConfig| launch(RunMode::Sweep(sweep));
+
+    // 以当前结果为初始解继续优化（热启动），可只在部分班级之间调整
+    let mut warm_start = move |budget: Option<std::time::Duration>,
+                               active_classes: Option<Vec<usize>>| {
+        let students = result_students.read().clone();
+        let assignments = result_assignments.read().clone();
+        if students.is_empty() || assignments.len() != students.len() {
+            return;
+        }
+
+        let divide_config = DivideConfig::new(*num_classes.read())
+            .with_optimization_params(optimization_params.read().clone())
+            .with_seed(Some(rand::random::<u32>() as u64))
+            .with_time_budget(budget)
+            .with_trace_interval(trace_interval())
+            .with_reference_assignments(reference_assignments(&students))
+            .with_initial_assignments(Some(assignments))
+            .with_active_classes(active_classes)
+            .with_alternatives(alternatives())
+            .with_pareto(pareto());
+        let previous = last_result();
+
+        let cancel = CancellationToken::new();
+        cancel_token.set(Some(cancel.clone()));
+
+        processing.set(true);
+        step.set(AppStep::Processing);
+        error_message.set(None);
+        success_message.set(None);
+
+        spawn(async move {
+            run_division(students, divide_config, cancel, previous).await;
+            processing.set(false);
+            cancel_token.set(None);
+        });
+    };
+
+    // 取消后继续优化部分结果
+    let resume_division =
+        move |_| warm_start(time_budget().map(std::time::Duration::from_secs), None);
+
+    // 只在选中的班级之间重新优化，其余班级保持不变
+    let reoptimize_classes = move |active_classes: Vec<usize>| {
+        warm_start(
+            time_budget().map(std::time::Duration::from_secs),
+            Some(active_classes),
+        )
+    };
+
+    // 在结果页面继续优化指定的时间
+    let continue_optimization =
+        move |seconds: u64| warm_start(Some(std::time::Duration::from_secs(seconds)), None);
+
+    // 在后台线程把结果中的一个班级分成学习小组
+    let divide_groups = move |(class_idx, count): (usize, usize)| {
+        let Some(mut class) = result_classes.read().get(class_idx).cloned() else {
+            return;
+        };
+        let num_groups = if count == 0 {
+            recommended_group_count(class.students.len())
+        } else {
+            count
+        };
+        let config = study_group_config(num_groups)
+            .with_seed(Some(seed().unwrap_or_else(|| rand::random::<u32>() as u64)));
+        error_message.set(None);
+        success_message.set(None);
+
+        spawn(async move {
+            let outcome = tokio::task::spawn_blocking(move || {
+                class
+                    .divide_into_groups(config)
+                    .map(|result| (class, result))
+            })
+            .await;
+
+            match outcome {
+                Ok(Ok((class, result))) => {
+                    let sizes = result.classes.iter().map(|group| group.students.len());
+                    let v = &result.validation;
+                    success_message.set(Some(format!(
+                        "班级 {} 已分为 {} 个小组（每组 {}~{} 人），总分最大差值 {:.2}分，性别比例最大差 {:.1}%",
+                        class.id + 1,
+                        num_groups,
+                        sizes.clone().min().unwrap_or(0),
+                        sizes.max().unwrap_or(0),
+                        v.max_score_diff,
+                        v.max_gender_ratio_diff * 100.0
+                    )));
+                    if let Some(slot) = result_classes.write().get_mut(class_idx) {
+                        *slot = class;
+                    }
+                }
+                Ok(Err(e)) => error_message.set(Some(format!("分小组失败: {}", e))),
+                Err(e) => error_message.set(Some(format!("分小组任务失败: {}", e))),
+            }
+        });
+    };
+
+    // 为结果中的一个班级排座位
+    let arrange_class_seats =
+        move |(class_idx, layout, rules): (usize, RoomLayout, SeatingRules)| {
+            let seat_seed = seed().unwrap_or_else(|| rand::random::<u32>() as u64);
+            let mut classes = result_classes.write();
+            let Some(class) = classes.get_mut(class_idx) else {
+                return;
+            };
+            match class.arrange_seats(&layout, &rules, seat_seed) {
+                Ok(chart) => {
+                    let detail = if chart.issues.is_empty() {
+                        "所有规则均已满足".to_string()
+                    } else {
+                        format!("未完全满足：{}", chart.issues.join("；"))
+                    };
+                    error_message.set(None);
+                    success_message.set(Some(format!(
+                        "班级 {} 座位已排好，{}",
+                        class.id + 1,
+                        detail
+                    )));
+                }
+                Err(e) => {
+                    success_message.set(None);
+                    error_message.set(Some(format!("排座位失败: {}", e)));
+                }
+            }
+        };
+
+    // 丢弃部分结果，返回参数设置
+    let discard_result = move |_| {
+        result_classes.set(Vec::new());
+        result_students.set(Vec::new());
+        result_assignments.set(Vec::new());
+        result_summary.set(None);
+        result_seed.set(None);
+        result_run_info.set(Vec::new());
+        result_trace.set(Vec::new());
+        result_options.set(Vec::new());
+        result_front.set(Vec::new());
+        last_result.set(None);
+        result_improvement.set(None);
+        result_partial.set(false);
+        error_message.set(None);
+        step.set(AppStep::ConfigureDivision);
+    };
+
     // 取消分班
     let cancel_division = move |_| {
         if let Some(token) = cancel_token.read().as_ref() {
@@ -254,7 +689,13 @@ pub fn Home() -> Element {
         let classes = result_classes.read().clone();
         let mappings = column_mappings.read().clone();
         let input_path_val = file_path.read().clone();
-        let num_classes_val = *num_classes.read();
+        let num_classes_val = classes.len();
+        let seed_val = *result_seed.read();
+        let run_val = last_result
+            .read()
+            .as_ref()
+            .map(|result| (result.num_instances, result.iterations));
+        let run_info = result_run_info.read().clone();
         let mut refresh = history_refresh;
 
         spawn(async move {
@@ -292,7 +733,13 @@ pub fn Home() -> Element {
                 let export_result = if output_path.to_lowercase().ends_with(".csv") {
                     export_to_csv(&classes, &output_path, &subjects_refs, &extras_refs)
                 } else {
-                    export_to_excel(&classes, &output_path, &subjects_refs, &extras_refs)
+                    export_to_excel(
+                        &classes,
+                        &output_path,
+                        &subjects_refs,
+                        &extras_refs,
+                        &run_info,
+                    )
                 };
 
                 match export_result {
@@ -311,6 +758,11 @@ pub fn Home() -> Element {
                                 num_students,
                                 format.clone(),
                                 params,
+                                seed_val,
+                            )
+                            .with_run(
+                                run_val.map(|(instances, _)| instances),
+                                run_val.map(|(_, iterations)| iterations),
                             );
                             let _ = manager.add(record);
                             // 触发历史记录刷新
@@ -329,6 +781,166 @@ pub fn Home() -> Element {
         });
     };
 
+    // 以备选方案或前沿上的方案作为当前结果
+    let mut open_result = move |result: DivideResult, label: String| {
+        let mut run_info = result.summary_items();
+        run_info.push(("选用方案".to_string(), label));
+        result_summary.set(Some(format_items(&run_info)));
+        result_run_info.set(run_info);
+        result_assignments.set(result.assignments.clone());
+        result_classes.set(result.classes.clone());
+        last_result.set(Some(result));
+    };
+
+    let select_option = move |index: usize| {
+        let Some(option) = result_options.read().get(index).cloned() else {
+            return;
+        };
+        open_result(option, format!("方案 {}", index + 1));
+        selected_option.set(Some(index));
+    };
+
+    let select_front = move |index: usize| {
+        let Some(result) = result_front.read().get(index).cloned() else {
+            return;
+        };
+        open_result(result, format!("前沿方案 {}", index + 1));
+        selected_option.set(None);
+    };
+
+    // 导出收敛轨迹
+    let export_trace = move |format: String| {
+        let trace = result_trace.read().clone();
+
+        spawn(async move {
+            let (file_name, filter_name, filter_ext) = match format.as_str() {
+                "json" => ("收敛轨迹.json", "JSON Files", vec!["json"]),
+                _ => ("收敛轨迹.csv", "CSV Files", vec!["csv"]),
+            };
+
+            if let Some(file) = AsyncFileDialog::new()
+                .set_file_name(file_name)
+                .add_filter(filter_name, &filter_ext)
+                .save_file()
+                .await
+            {
+                let output_path = file.path().to_string_lossy().to_string();
+                let export_result = if output_path.to_lowercase().ends_with(".json") {
+                    export_trace_to_json(&trace, &output_path)
+                } else {
+                    export_trace_to_csv(&trace, &output_path)
+                };
+
+                match export_result {
+                    Ok(_) => success_message.set(Some(format!(
+                        "轨迹导出成功！\n文件已保存至: {}",
+                        output_path
+                    ))),
+                    Err(e) => error_message.set(Some(format!("轨迹导出失败: {}", e))),
+                }
+            }
+        });
+    };
+
+    // 安排考场并导出
+    let export_exam = move |config: ExamConfig| {
+        let classes = result_classes.read().clone();
+        let config = config.with_seed(seed().unwrap_or_else(|| rand::random::<u32>() as u64));
+
+        spawn(async move {
+            let plan = match arrange_exam_rooms(&classes, &config) {
+                Ok(plan) => plan,
+                Err(e) => {
+                    error_message.set(Some(format!("安排考场失败: {}", e)));
+                    return;
+                }
+            };
+
+            if let Some(file) = AsyncFileDialog::new()
+                .set_file_name("考场安排.xlsx")
+                .add_filter("Excel Files", &["xlsx"])
+                .save_file()
+                .await
+            {
+                let output_path = file.path().to_string_lossy().to_string();
+                match export_exam_plan(&classes, &plan, &output_path) {
+                    Ok(_) => {
+                        let conflicts = if plan.conflicts == 0 {
+                            "同班考生均不相邻".to_string()
+                        } else {
+                            format!("有 {} 对同班考生相邻", plan.conflicts)
+                        };
+                        success_message.set(Some(format!(
+                            "考场安排导出成功！共 {} 人、{} 个考场（{}），{}\n文件已保存至: {}",
+                            plan.student_count(),
+                            plan.rooms.len(),
+                            config.order.label(),
+                            conflicts,
+                            output_path
+                        )));
+                    }
+                    Err(e) => error_message.set(Some(format!("考场安排导出失败: {}", e))),
+                }
+            }
+        });
+    };
+
+    // 按宿舍清单分配宿舍并导出
+    let allocate_class_dorms = move |_| {
+        let classes = result_classes.read().clone();
+
+        spawn(async move {
+            let Some(inventory) = AsyncFileDialog::new()
+                .add_filter("宿舍清单", &["xls", "xlsx", "csv"])
+                .pick_file()
+                .await
+            else {
+                return;
+            };
+            let inventory_path = inventory.path().to_string_lossy().to_string();
+            let plan = match read_file_all_data(&inventory_path)
+                .and_then(|(headers, rows)| parse_room_inventory(&headers, &rows))
+                .and_then(|rooms| allocate_dorms(&classes, &rooms))
+            {
+                Ok(plan) => plan,
+                Err(e) => {
+                    error_message.set(Some(format!("宿舍分配失败: {}", e)));
+                    return;
+                }
+            };
+
+            if let Some(file) = AsyncFileDialog::new()
+                .set_file_name("宿舍分配.xlsx")
+                .add_filter("Excel Files", &["xlsx"])
+                .save_file()
+                .await
+            {
+                let output_path = file.path().to_string_lossy().to_string();
+                match export_dorm_plan(&classes, &plan, &output_path) {
+                    Ok(_) => {
+                        let mut detail = format!(
+                            "共入住 {} 人、{} 间宿舍，其中 {} 间住有多个班级",
+                            plan.assigned_count(),
+                            plan.rooms.len(),
+                            plan.mixed_rooms()
+                        );
+                        if !plan.unassigned.is_empty() {
+                            detail.push_str(&format!(
+                                "；{} 人因床位不足或性别未知未能分配",
+                                plan.unassigned.len()
+                            ));
+                        }
+                        success_message.set(Some(format!(
+                            "宿舍分配导出成功！{}\n文件已保存至: {}",
+                            detail, output_path
+                        )));
+                    }
+                    Err(e) => error_message.set(Some(format!("宿舍分配导出失败: {}", e))),
+                }
+            }
+        });
+    };
+
     rsx! {
         div { class: "min-h-screen bg-base-200 p-4 md:p-8",
             div { class: "max-w-7xl mx-auto",
@@ -344,11 +956,31 @@ pub fn Home() -> Element {
                             headers.set(Vec::new());
                             preview_data.set(Vec::new());
                             column_mappings.set(Vec::new());
+                            gender_aliases.set(GenderAliases::default());
+                            import_message.set(None);
                             result_classes.set(Vec::new());
                             result_summary.set(None);
+                            result_seed.set(None);
+                            result_run_info.set(Vec::new());
+                            result_trace.set(Vec::new());
+                            result_options.set(Vec::new());
+                            result_front.set(Vec::new());
+                            last_result.set(None);
+                            result_improvement.set(None);
+                            result_students.set(Vec::new());
+                            result_assignments.set(Vec::new());
+                            result_partial.set(false);
                             success_message.set(None);
                             error_message.set(None);
                             optimization_params.set(OptimizationParams::default());
+                            seed.set(None);
+                            time_budget.set(None);
+                            trace_interval.set(None);
+                            alternatives.set(None);
+                            pareto.set(false);
+                            tiers.set(Vec::new());
+                            compatibility.set(String::new());
+                            sweep_result.set(None);
                         },
                     }
                 }
@@ -406,6 +1038,22 @@ pub fn Home() -> Element {
                     }
                 }
 
+                // 导入摘要
+                if let Some(msg) = import_message.read().as_ref() {
+                    div { class: "alert alert-info mb-4 animate-fade-in shadow-lg",
+                        img {
+                            class: "stroke-current shrink-0 h-6 w-6",
+                            src: INFO,
+                        }
+                        span { "{msg}" }
+                        button {
+                            class: "btn btn-sm btn-ghost",
+                            onclick: move |_| import_message.set(None),
+                            "✕"
+                        }
+                    }
+                }
+
                 // 主内容卡片
                 div { class: "card bg-base-100 shadow-xl",
                     div { class: "card-body p-4 md:p-8",
@@ -424,6 +1072,7 @@ pub fn Home() -> Element {
                             AppStep::ConfigureColumns => rsx! {
                                 ColumnConfigView {
                                     column_mappings,
+                                    gender_aliases,
                                     on_confirm: confirm_columns,
                                     on_back: move |_| step.set(AppStep::PreviewData),
                                 }
@@ -432,19 +1081,79 @@ pub fn Home() -> Element {
                                 DivisionConfigView {
                                     num_classes,
                                     optimization_params,
+                                    seed,
+                                    time_budget,
+                                    trace_interval,
+                                    alternatives,
+                                    pareto,
+                                    tiers,
+                                    combination_mode: has_combination_column(),
+                                    preference_mode: has_preference_column(),
+                                    compatibility,
+                                    reference_mode: column_mappings
+                                        .read()
+                                        .iter()
+                                        .any(|m| m.column_type == ColumnType::OriginalClass),
+                                    tighten_priority,
                                     on_start: start_division,
+                                    on_auto_tighten: start_auto_tighten,
                                     on_back: move |_| step.set(AppStep::ConfigureColumns),
                                 }
+                                ClassSweepPanel { num_classes, sweep_result, on_sweep: start_sweep }
                             },
                             AppStep::Processing => rsx! {
                                 ProcessingView { num_classes, optimization_params, on_cancel: cancel_division }
                             },
                             AppStep::Results => rsx! {
+                                // 部分结果操作
+                                if result_partial() {
+                                    div { class: "alert alert-warning mb-4",
+                                        img {
+                                            class: "stroke-current shrink-0 h-6 w-6",
+                                            src: WARNING,
+                                        }
+                                        span { "这是取消时得到的部分结果，约束可能尚未全部满足。" }
+                                        div { class: "flex gap-2",
+                                            button {
+                                                class: "btn btn-sm btn-primary",
+                                                onclick: move |_| result_partial.set(false),
+                                                "接受"
+                                            }
+                                            if !result_grouped() {
+                                                button {
+                                                    class: "btn btn-sm btn-secondary",
+                                                    onclick: resume_division,
+                                                    "继续优化"
+                                                }
+                                            }
+                                            button {
+                                                class: "btn btn-sm btn-outline",
+                                                onclick: discard_result,
+                                                "丢弃"
+                                            }
+                                        }
+                                    }
+                                }
                                 ResultsView {
                                     classes: result_classes,
                                     summary: result_summary.read().clone(),
                                     column_mappings,
+                                    trace: result_trace,
+                                    options: result_options,
+                                    selected_option,
+                                    on_select_option: select_option,
+                                    front: result_front,
+                                    on_select_front: select_front,
                                     on_export: export_results,
+                                    on_export_trace: export_trace,
+                                    on_reoptimize: reoptimize_classes,
+                                    refinable: !result_grouped(),
+                                    improvement: result_improvement(),
+                                    on_continue: continue_optimization,
+                                    on_divide_groups: divide_groups,
+                                    on_arrange_seats: arrange_class_seats,
+                                    on_export_exam: export_exam,
+                                    on_allocate_dorms: allocate_class_dorms,
                                     on_back: move |_| {
                                         step.set(AppStep::ConfigureDivision);
                                     },
@@ -454,11 +1163,31 @@ pub fn Home() -> Element {
                                         headers.set(Vec::new());
                                         preview_data.set(Vec::new());
                                         column_mappings.set(Vec::new());
+                                        gender_aliases.set(GenderAliases::default());
+                                        import_message.set(None);
                                         result_classes.set(Vec::new());
                                         result_summary.set(None);
+                                        result_seed.set(None);
+                                        result_run_info.set(Vec::new());
+                                        result_trace.set(Vec::new());
+                                        result_options.set(Vec::new());
+                                        result_front.set(Vec::new());
+                                        last_result.set(None);
+                                        result_improvement.set(None);
+                                        result_students.set(Vec::new());
+                                        result_assignments.set(Vec::new());
+                                        result_partial.set(false);
                                         success_message.set(None);
                                         error_message.set(None);
                                         optimization_params.set(OptimizationParams::default());
+                                        seed.set(None);
+                                        time_budget.set(None);
+                                        trace_interval.set(None);
+                                        alternatives.set(None);
+                                        pareto.set(false);
+                                        tiers.set(Vec::new());
+                                        compatibility.set(String::new());
+                                        sweep_result.set(None);
                                     },
                                 }
                             },
@@ -481,3 +1210,34 @@ pub fn Home() -> Element {
         }
     }
 }
+
+/// 有原班级时进入微调模式，返回各学生的原班级
+fn reference_assignments(students: &[Student]) -> Option<Vec<Option<usize>>> {
+    students
+        .iter()
+        .any(|s| s.original_class.is_some())
+        .then(|| students.iter().map(|s| s.original_class).collect())
+}
+
+/// 后台任务类型
+enum RunMode {
+    /// 按当前参数分班
+    Divide,
+    /// 自动收紧阈值
+    Tighten(TightenConfig),
+    /// 对比多个班级数量
+    Sweep(SweepConfig),
+    /// 分层分班
+    Tiered(Vec<TierSpec>),
+    /// 按兼容的选科组合组班
+    Combination(Vec<Vec<String>>),
+}
+
+/// 把运行信息格式化为每行一项的文本
+fn format_items(items: &[(String, String)]) -> String {
+    items
+        .iter()
+        .map(|(key, value)| format!("{}: {}", key, value))
+        .collect::<Vec<_>>()
+        .join("\n")
+}